@@ -0,0 +1,88 @@
+//! With the `generate-man` feature, generate shell completions into `target/completions/`
+//! and man pages into `target/man/` from the definition of the command line arguments.
+
+fn main() {
+    #[cfg(feature = "generate-man")]
+    generate::generate().expect("Cannot generate completions and man pages");
+}
+
+#[cfg(feature = "generate-man")]
+mod generate {
+    use crate::cli::Cli;
+    use clap::{Command, CommandFactory};
+    use clap_complete::Shell;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{self, BufWriter, Write};
+    use std::path::{Path, PathBuf};
+
+    pub fn generate() -> io::Result<()> {
+        println!("cargo:rerun-if-changed=src/cli.rs");
+        println!("cargo:rerun-if-changed=src/defaults.rs");
+
+        let target_dir = env::var_os("CARGO_TARGET_DIR").map_or_else(
+            || PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("target"),
+            PathBuf::from,
+        );
+        let mut command = Cli::command();
+        // Propagate the global options to the subcommands, whose man pages list them.
+        command.build();
+        let name = command.get_name().to_owned();
+
+        let completions_dir = target_dir.join("completions");
+        fs::create_dir_all(&completions_dir)?;
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            clap_complete::generate_to(shell, &mut command, &name, &completions_dir)?;
+        }
+
+        let man_dir = target_dir.join("man");
+        fs::create_dir_all(&man_dir)?;
+        // Each subcommand gets its own man page named like `fastx-statistics-stats.1`, like the subcommands of git.
+        for subcommand in command.get_subcommands() {
+            if subcommand.get_name() == "help" {
+                continue;
+            }
+            let page_name: &'static str =
+                Box::leak(format!("{name}-{}", subcommand.get_name()).into_boxed_str());
+            render_man_page(subcommand.clone().name(page_name), &man_dir)?;
+        }
+        render_man_page(command, &man_dir)
+    }
+
+    fn render_man_page(command: Command, man_dir: &Path) -> io::Result<()> {
+        let path = man_dir.join(format!("{}.1", command.get_name()));
+        let mut output = BufWriter::new(File::create(path)?);
+        clap_mangen::Man::new(command).render(&mut output)?;
+        output.flush()
+    }
+}
+
+// The argument definitions refer to these items of the library, which the build script cannot depend on.
+// Only their names are needed to generate the documentation, except for the defaults,
+// which are included from the same file as in the library.
+#[cfg(feature = "generate-man")]
+#[allow(dead_code)]
+#[path = "src/cli.rs"]
+mod cli;
+
+#[cfg(feature = "generate-man")]
+#[path = "src/defaults.rs"]
+mod defaults;
+
+#[cfg(feature = "generate-man")]
+use defaults::{
+    DEFAULT_MIN_POLY_TAIL_LENGTH, DEFAULT_MIN_TELOMERE_REPEATS, DEFAULT_RESERVOIR_SIZE,
+    DEFAULT_SCAFFOLD_N_THRESHOLD, DEFAULT_TELOMERE_MOTIF,
+};
+
+/// Templates are not parsed when generating the documentation.
+#[cfg(feature = "generate-man")]
+#[derive(Clone)]
+struct Template;
+
+#[cfg(feature = "generate-man")]
+impl Template {
+    fn parse(_template: &str) -> Result<Self, String> {
+        Ok(Self)
+    }
+}
@@ -0,0 +1,178 @@
+//! Count the reads that contain adapter sequences, to decide whether the reads need to be trimmed.
+
+use crate::without_line_terminators;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The occurrences of an adapter in the reads.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AdapterSummary {
+    pub adapter: String,
+    /// The number of reads that contain the adapter.
+    pub read_count: usize,
+    /// The percentage of reads that contain the adapter.
+    pub read_percent: f64,
+    /// The mean position of the first occurrence in the reads that contain the adapter, starting from 1.
+    /// `None` if no read contains the adapter.
+    pub mean_first_position: Option<f64>,
+}
+
+/// Counts the reads that contain each of a list of adapters.
+#[derive(Debug, Clone)]
+pub(crate) struct AdapterAccumulator {
+    read_count: usize,
+    adapters: Vec<AdapterCounter>,
+}
+
+#[derive(Debug, Clone)]
+struct AdapterCounter {
+    searcher: Horspool,
+    read_count: usize,
+    /// The sum of the offsets of the first occurrences.
+    offset_sum: u64,
+}
+
+impl AdapterAccumulator {
+    /// The adapters must be non-empty uppercase sequences.
+    pub(crate) fn new(adapters: &[String]) -> Self {
+        Self {
+            read_count: 0,
+            adapters: adapters
+                .iter()
+                .map(|adapter| AdapterCounter {
+                    searcher: Horspool::new(adapter.as_bytes()),
+                    read_count: 0,
+                    offset_sum: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Search the adapters in the given sequence, ignoring case, and return the offset of the first occurrence of each,
+    /// to be passed to [`Self::add`].
+    /// Line terminators are ignored, such that multiline sequences can be passed directly.
+    /// Sequences with line terminators are copied into `buffer` without them.
+    pub(crate) fn find(&self, seq: &[u8], buffer: &mut Vec<u8>) -> Vec<Option<usize>> {
+        let seq = without_line_terminators(seq, buffer);
+        self.adapters
+            .iter()
+            .map(|adapter| adapter.searcher.find(seq))
+            .collect()
+    }
+
+    /// Count a read with the given offsets of the first occurrences of the adapters.
+    pub(crate) fn add(&mut self, offsets: &[Option<usize>]) {
+        self.read_count += 1;
+        for (adapter, offset) in self.adapters.iter_mut().zip(offsets) {
+            if let Some(offset) = offset {
+                adapter.read_count += 1;
+                adapter.offset_sum += *offset as u64;
+            }
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        self.read_count += other.read_count;
+        for (adapter, other_adapter) in self.adapters.iter_mut().zip(&other.adapters) {
+            adapter.read_count += other_adapter.read_count;
+            adapter.offset_sum += other_adapter.offset_sum;
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<AdapterSummary> {
+        let read_count = self.read_count;
+        self.adapters
+            .into_iter()
+            .map(|adapter| AdapterSummary {
+                adapter: String::from_utf8(adapter.searcher.pattern).unwrap(),
+                read_count: adapter.read_count,
+                read_percent: if read_count == 0 {
+                    0.0
+                } else {
+                    adapter.read_count as f64 / read_count as f64 * 100.0
+                },
+                mean_first_position: if adapter.read_count == 0 {
+                    None
+                } else {
+                    Some(adapter.offset_sum as f64 / adapter.read_count as f64 + 1.0)
+                },
+            })
+            .collect()
+    }
+}
+
+/// Searches a pattern with the Boyer-Moore-Horspool algorithm, ignoring case.
+#[derive(Debug, Clone)]
+struct Horspool {
+    pattern: Vec<u8>,
+    /// The distance by which the pattern is shifted after a mismatch,
+    /// depending on the character of the text aligned with the last character of the pattern.
+    shifts: [usize; 256],
+}
+
+impl Horspool {
+    /// The pattern must not be empty.
+    fn new(pattern: &[u8]) -> Self {
+        debug_assert!(!pattern.is_empty());
+        let mut shifts = [pattern.len(); 256];
+        for (index, &character) in pattern[..pattern.len() - 1].iter().enumerate() {
+            let shift = pattern.len() - 1 - index;
+            shifts[usize::from(character.to_ascii_uppercase())] = shift;
+            shifts[usize::from(character.to_ascii_lowercase())] = shift;
+        }
+        Self {
+            pattern: pattern.to_vec(),
+            shifts,
+        }
+    }
+
+    /// The offset of the first occurrence of the pattern in the text.
+    fn find(&self, text: &[u8]) -> Option<usize> {
+        let pattern_length = self.pattern.len();
+        let mut offset = 0;
+        while offset + pattern_length <= text.len() {
+            let window = &text[offset..offset + pattern_length];
+            if window.eq_ignore_ascii_case(&self.pattern) {
+                return Some(offset);
+            }
+            offset += self.shifts[usize::from(window[pattern_length - 1])];
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapter::{AdapterAccumulator, Horspool};
+
+    #[test]
+    fn test_horspool() {
+        let searcher = Horspool::new(b"GCAGAGAG");
+        assert_eq!(searcher.find(b"GCATCGCAGAGAGTATACAGTACG"), Some(5));
+        assert_eq!(searcher.find(b"GCAGAGA"), None);
+        assert_eq!(Horspool::new(b"AA").find(b"CAAA"), Some(1));
+        assert_eq!(Horspool::new(b"A").find(b"CCCA"), Some(3));
+        assert_eq!(Horspool::new(b"ACG").find(b"tacgt"), Some(1));
+    }
+
+    #[test]
+    fn test_adapters() {
+        let adapters = vec!["AGATCGGAAGAGC".to_owned(), "CTGTCTCTTATA".to_owned()];
+        let mut accumulator = AdapterAccumulator::new(&adapters);
+        accumulator.add(&accumulator.find(b"ACGTAGATCGGAAGAGCAGATCGGAAGAGC", &mut Vec::new()));
+        let mut other = AdapterAccumulator::new(&adapters);
+        other.add(&other.find(b"agatcgg\naagagcTT", &mut Vec::new()));
+        other.add(&other.find(b"ACGT", &mut Vec::new()));
+        other.add(&other.find(b"ACGTACGTAGATCGGAAGAG", &mut Vec::new()));
+        accumulator.extend(&other);
+
+        let summaries = accumulator.finish();
+        assert_eq!(summaries[0].read_count, 2);
+        assert_eq!(summaries[0].read_percent, 50.0);
+        assert_eq!(summaries[0].mean_first_position, Some(3.0));
+        assert_eq!(summaries[1].adapter, "CTGTCTCTTATA");
+        assert_eq!(summaries[1].read_count, 0);
+        assert_eq!(summaries[1].mean_first_position, None);
+    }
+}
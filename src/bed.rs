@@ -0,0 +1,55 @@
+//! Write the sequences as intervals of a BED file.
+
+use crate::error::FastxStatisticsError;
+use crate::AcceptedRecord;
+use std::io::{self, Write};
+
+/// Writes one BED3 line with the columns `seqid`, `start` and `end` per record while the input is being read.
+/// Each interval spans the whole sequence.
+pub struct BedWriter<W: Write> {
+    output: W,
+}
+
+impl<W: Write> BedWriter<W> {
+    pub fn new(output: W) -> Self {
+        Self { output }
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        writeln!(self.output, "{}\t0\t{}", record.id, record.statistics.len).map_err(write_error)
+    }
+
+    /// Flush the output.
+    pub fn finish(mut self) -> Result<(), FastxStatisticsError> {
+        self.output.flush().map_err(write_error)
+    }
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write BED file", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bed::BedWriter;
+    use crate::{basic_statistics_with_callback, StatisticsOptions};
+
+    #[test]
+    fn test_bed() {
+        let fasta = b">1 desc\nACGT\nAC\n>2\nA\n>3\nACG\n";
+        let mut output = Vec::new();
+        let mut writer = BedWriter::new(&mut output);
+        basic_statistics_with_callback(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions {
+                min_length: Some(2),
+                ..Default::default()
+            },
+            |record| writer.write_record(record),
+        )
+        .unwrap();
+        writer.finish().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\t0\t6\n3\t0\t3\n");
+    }
+}
@@ -0,0 +1,140 @@
+//! Measure the time spent on parsing, computing the statistics and rendering them.
+
+use crate::error::FastxStatisticsError;
+use crate::format::{SequenceFormat, SequenceReader};
+use crate::{StatisticsAccumulator, StatisticsOptions};
+use seq_io::BaseRecord;
+use std::cell::Cell;
+use std::io::{self, BufReader, Read, Write};
+use std::time::{Duration, Instant};
+
+/// The timing of a single pass over the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchIteration {
+    /// The number of bytes of the decompressed input.
+    pub byte_count: u64,
+    pub record_count: usize,
+    /// The time spent reading and parsing the records.
+    pub io: Duration,
+    /// The time spent computing and accumulating the statistics of the records.
+    pub stats: Duration,
+    /// The time spent summarising the accumulated statistics and rendering them as metrics.
+    pub output: Duration,
+}
+
+impl BenchIteration {
+    pub fn total(&self) -> Duration {
+        self.io + self.stats + self.output
+    }
+}
+
+/// Read all records of the input sequentially and measure the time of each step.
+/// If `skip_stats` is set, the records are only parsed.
+/// The format is detected from the first record, unless it is given.
+///
+/// The reading is single-threaded and does not apply any filters, such that the steps can be timed separately.
+pub fn bench_iteration(
+    input: impl Read,
+    format: Option<SequenceFormat>,
+    skip_stats: bool,
+) -> Result<BenchIteration, FastxStatisticsError> {
+    let options = StatisticsOptions::default();
+    let byte_count = Cell::new(0);
+    let mut reader = SequenceReader::new(
+        BufReader::new(ByteCounter {
+            inner: input,
+            count: &byte_count,
+        }),
+        format,
+    );
+    let mut accumulator = StatisticsAccumulator::new(&options);
+    let mut buffer = Vec::new();
+    let mut record_count = 0;
+    let mut io = Duration::ZERO;
+    let mut stats = Duration::ZERO;
+
+    loop {
+        let start = Instant::now();
+        let (record, _) = match reader.next_record(false) {
+            Some(record) => {
+                record.map_err(|err| FastxStatisticsError::parse("Error parsing fastx", err))?
+            }
+            None => {
+                io += start.elapsed();
+                break;
+            }
+        };
+        let parsed = Instant::now();
+        io += parsed - start;
+        record_count += 1;
+        if skip_stats {
+            continue;
+        }
+
+        let statistics =
+            accumulator.record_statistics(record.seq(), record.opt_qual(), &options, &mut buffer);
+        if statistics.sequence.len > 0 {
+            accumulator.add("", record.seq(), &statistics, record.opt_qual());
+        }
+        stats += parsed.elapsed();
+    }
+
+    let start = Instant::now();
+    if !skip_stats {
+        let mut sink = io::sink();
+        for metric in accumulator.finish(&options).metrics() {
+            writeln!(sink, "{}", metric.to_human_string())
+                .map_err(|err| FastxStatisticsError::io("Cannot write statistics", err))?;
+        }
+    }
+    let output = start.elapsed();
+
+    Ok(BenchIteration {
+        byte_count: byte_count.get(),
+        record_count,
+        io,
+        stats,
+        output,
+    })
+}
+
+/// Counts the bytes read from the inner reader.
+struct ByteCounter<'a, R> {
+    inner: R,
+    count: &'a Cell<u64>,
+}
+
+impl<R: Read> Read for ByteCounter<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.count.set(self.count.get() + len as u64);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::bench_iteration;
+    use crate::format::SequenceFormat;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bench_iteration() {
+        let fasta = b">1\nACGT\n>2\nAC\n>3\n\n";
+        let iteration = bench_iteration(fasta.as_slice(), None, false).unwrap();
+        assert_eq!(iteration.record_count, 3);
+        assert_eq!(iteration.byte_count, fasta.len() as u64);
+
+        let iteration = bench_iteration(fasta.as_slice(), None, true).unwrap();
+        assert_eq!(iteration.record_count, 3);
+        assert_eq!(iteration.stats, Duration::ZERO);
+        assert_eq!(iteration.output, iteration.total() - iteration.io);
+
+        // With the format given, the comment line before the first record is skipped.
+        let fasta = b"# comment\n>1\nACGT\n";
+        assert!(bench_iteration(fasta.as_slice(), None, false).is_err());
+        let iteration =
+            bench_iteration(fasta.as_slice(), Some(SequenceFormat::Fasta), false).unwrap();
+        assert_eq!(iteration.record_count, 1);
+    }
+}
@@ -0,0 +1,119 @@
+//! Compute checksums of the sequences of records.
+//!
+//! The hash functions are only available with the `checksums` feature.
+
+use crate::error::FastxStatisticsError;
+use crate::AcceptedRecord;
+use std::io::{self, Write};
+
+/// A hash function for sequence checksums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    #[cfg(feature = "checksums")]
+    Sha256,
+    #[cfg(feature = "checksums")]
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    /// The hexadecimal checksum of the given sequence.
+    ///
+    /// The sequence is converted to uppercase and whitespace such as line terminators is removed before hashing,
+    /// such that soft-masking and line lengths do not change the checksum.
+    #[cfg_attr(not(feature = "checksums"), allow(unused_variables))]
+    pub fn checksum(self, seq: &[u8]) -> String {
+        match self {
+            #[cfg(feature = "checksums")]
+            Self::Sha256 => canonical_digest::<sha2::Sha256>(seq),
+            #[cfg(feature = "checksums")]
+            Self::Md5 => canonical_digest::<md5::Md5>(seq),
+        }
+    }
+}
+
+#[cfg(feature = "checksums")]
+fn canonical_digest<D: sha2::Digest>(seq: &[u8]) -> String
+where
+    sha2::digest::Output<D>: std::fmt::LowerHex,
+{
+    let mut digest = D::new();
+    let mut buffer = [0; 4096];
+    let mut buffer_len = 0;
+    for byte in seq.iter().filter(|byte| !byte.is_ascii_whitespace()) {
+        buffer[buffer_len] = byte.to_ascii_uppercase();
+        buffer_len += 1;
+        if buffer_len == buffer.len() {
+            digest.update(buffer);
+            buffer_len = 0;
+        }
+    }
+    digest.update(&buffer[..buffer_len]);
+    format!("{:x}", digest.finalize())
+}
+
+/// Writes one line with the id and the checksum per record while the input is being read.
+pub struct ChecksumWriter<W: Write> {
+    output: W,
+    algorithm: ChecksumAlgorithm,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    pub fn new(output: W, algorithm: ChecksumAlgorithm) -> Self {
+        Self { output, algorithm }
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        writeln!(
+            self.output,
+            "{}\t{}",
+            record.id,
+            self.algorithm.checksum(record.seq)
+        )
+        .map_err(write_error)
+    }
+
+    /// Flush the output.
+    pub fn finish(mut self) -> Result<(), FastxStatisticsError> {
+        self.output.flush().map_err(write_error)
+    }
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write checksums", err)
+}
+
+#[cfg(all(test, feature = "checksums"))]
+mod tests {
+    use crate::checksum::{ChecksumAlgorithm, ChecksumWriter};
+    use crate::{basic_statistics_with_callback, StatisticsOptions};
+
+    #[test]
+    fn test_checksum() {
+        let sha256 = "d254552eaf2579aa2ecb2a56439c41472f8e7de08ab3f15e898705eada76fc2d";
+        assert_eq!(ChecksumAlgorithm::Sha256.checksum(b"ACGTN"), sha256);
+        assert_eq!(ChecksumAlgorithm::Sha256.checksum(b"AcG\r\nt n\n"), sha256);
+        assert_eq!(
+            ChecksumAlgorithm::Md5.checksum(b""),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+    }
+
+    #[test]
+    fn test_checksum_writer() {
+        let fasta = b">1 desc\nacgt\nN\n>2\nACGTN\n";
+        let mut output = Vec::new();
+        let mut writer = ChecksumWriter::new(&mut output, ChecksumAlgorithm::Md5);
+        basic_statistics_with_callback(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+            |record| writer.write_record(record),
+        )
+        .unwrap();
+        writer.finish().unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].strip_prefix("1\t"), lines[1].strip_prefix("2\t"));
+    }
+}
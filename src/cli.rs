@@ -0,0 +1,675 @@
+//! The definition of the command line arguments.
+//!
+//! This module only depends on clap, clap_complete and the items imported from the crate root,
+//! such that the build script can include it to generate completions and a man page.
+
+use crate::{
+    Template, DEFAULT_MIN_POLY_TAIL_LENGTH, DEFAULT_MIN_TELOMERE_REPEATS, DEFAULT_RESERVOIR_SIZE,
+    DEFAULT_SCAFFOLD_N_THRESHOLD, DEFAULT_TELOMERE_MOTIF,
+};
+use clap::builder::RangedU64ValueParser;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use regex::Regex;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    after_help = "Without a subcommand, the arguments are passed to the `stats` subcommand, e.g. `fastx-statistics reads.fq` is the same as `fastx-statistics stats reads.fq`."
+)]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
+    /// Print a completion script for the given shell to stdout and exit.
+    ///
+    /// To install the completions, add `source <(fastx-statistics --generate-completions bash)` to `~/.bashrc` for bash,
+    /// write the script to a file named `_fastx-statistics` in a directory of `$fpath` for zsh,
+    /// write it to `~/.config/fish/completions/fastx-statistics.fish` for fish,
+    /// or add `fastx-statistics --generate-completions powershell | Out-String | Invoke-Expression` to `$PROFILE` for PowerShell.
+    #[clap(long, value_enum, value_name = "SHELL", exclusive = true)]
+    pub generate_completions: Option<Shell>,
+
+    #[clap(flatten)]
+    pub common: CommonArgs,
+}
+
+// Arguments shared by all subcommands.
+#[derive(Args)]
+pub struct CommonArgs {
+    /// Do not show the progress bar and only log warnings and errors.
+    /// Also, do not print the aggregate statistics to stderr when printing per-sequence statistics.
+    #[clap(long, global = true)]
+    pub quiet: bool,
+
+    /// The minimum level of log messages that are printed to stderr.
+    /// Defaults to `warn` with `--quiet` and to `info` otherwise.
+    #[clap(long, global = true, value_enum, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+
+    /// Parse the inputs in this format instead of detecting it from the first record.
+    /// Lines before the first record of the format are skipped with a warning, e.g. comment lines at the start of a file.
+    #[clap(long = "format", global = true, value_enum, value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+
+    /// Decompress the inputs with this format instead of detecting it from the file extension or the first bytes of the file.
+    #[clap(long, global = true, value_enum, value_name = "COMPRESSION")]
+    pub compression: Option<CompressionArg>,
+}
+
+// The arguments of the `stats` subcommand.
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Fasta or fastq input files (automatically detected unless `--format` is given), optionally compressed with gzip, bzip2 or zstd.
+    /// The compression format is detected from the file extension or the first bytes of the file unless `--compression` is given.
+    /// Pass `-` to read from stdin.
+    /// If multiple files are given, then the statistics of each file are printed before the combined statistics.
+    #[clap(index = 1, required = true)]
+    pub input: Vec<PathBuf>,
+
+    /// The second ends of paired-end reads, whose first ends are given as the single input file.
+    /// The files are read simultaneously, and the statistics of both ends are printed separately.
+    /// The ids of each pair must match up to a `/1` or `/2` suffix, mismatches are reported as warnings.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = &["per_sequence", "output_fasta", "output_fastq", "output_n_regions", "output_bed", "multiqc_json", "nx_curve_output", "lorenz_curve", "cumulative_length_table", "per_cycle_quality", "quast_format", "generate_fai", "checksums", "brief", "per_record_jsonl", "assert_n50", "assert_total_length", "assert_max_n_fraction", "assert_record_count"]
+    )]
+    pub r2: Option<PathBuf>,
+
+    /// Treat the single input file as interleaved paired-end reads, alternating between first and second ends.
+    /// The statistics of both ends are printed separately, like for `--r2`.
+    #[clap(
+        long,
+        conflicts_with_all = &["r2", "per_sequence", "output_fasta", "output_fastq", "output_n_regions", "output_bed", "multiqc_json", "nx_curve_output", "lorenz_curve", "cumulative_length_table", "per_cycle_quality", "quast_format", "generate_fai", "checksums", "brief", "per_record_jsonl", "assert_n50", "assert_total_length", "assert_max_n_fraction", "assert_record_count"]
+    )]
+    pub interleaved: bool,
+
+    /// Filter fasta or fastq records with the given ids (pass multiple times for multiple ids).
+    #[clap(long = "filter-id", value_name = "FILTER_ID")]
+    pub filter_ids: Vec<String>,
+
+    /// Filter fasta or fastq records with ids matching the given regular expression
+    /// (pass multiple times to filter records matching any of multiple expressions).
+    #[clap(long = "filter-id-regex", value_name = "PATTERN", value_parser = Regex::new)]
+    pub filter_id_regexes: Vec<Regex>,
+
+    /// Filter fasta or fastq records with the ids listed in this file, one per line.
+    /// Empty lines and lines starting with `#` are ignored.
+    /// A record is filtered if its id is excluded by any of `--filter-id`, `--filter-id-regex` and this file.
+    #[clap(long, value_name = "FILE")]
+    pub exclude_ids_file: Option<PathBuf>,
+
+    /// Keep only the fasta or fastq records with the ids listed in this file, in the format of `--exclude-ids-file`.
+    /// Records must additionally not be excluded by `--filter-id`, `--filter-id-regex` or `--exclude-ids-file`.
+    #[clap(long, value_name = "FILE")]
+    pub include_ids_file: Option<PathBuf>,
+
+    /// Compare ids case-insensitively in `--filter-id`, `--filter-id-regex`, `--exclude-ids-file` and `--include-ids-file`.
+    /// This does not affect `--filter-description`, use `(?i)` in a pattern with `--regex` for that.
+    #[clap(long)]
+    pub ignore_case: bool,
+
+    /// Filter fasta or fastq records whose whole header line, including the id and the description, contains this text
+    /// (pass multiple times to filter records containing any of multiple texts).
+    /// This is independent of the id filters, records are filtered if any filter excludes them.
+    #[clap(long = "filter-description", value_name = "PATTERN")]
+    pub filter_descriptions: Vec<String>,
+
+    /// Interpret the patterns of `--filter-description` as regular expressions instead of plain text.
+    #[clap(long, requires = "filter_descriptions")]
+    pub regex: bool,
+
+    /// Exclude sequences shorter than this many bases from all statistics.
+    #[clap(long, value_name = "N")]
+    pub min_length: Option<usize>,
+
+    /// Exclude sequences longer than this many bases from all statistics.
+    /// Can be combined with `--min-length` to select a window of lengths.
+    #[clap(long, value_name = "N")]
+    pub max_length: Option<usize>,
+
+    /// Exclude sequences with a GC content below this percentage from all statistics.
+    /// Sequences consisting only of Ns have no GC content and are not excluded by GC filters.
+    #[clap(long, value_name = "PERCENT", value_parser = parse_percentage)]
+    pub min_gc: Option<f64>,
+
+    /// Exclude sequences with a GC content above this percentage from all statistics.
+    /// Sequences consisting only of Ns have no GC content and are not excluded by GC filters.
+    #[clap(long, value_name = "PERCENT", value_parser = parse_percentage)]
+    pub max_gc: Option<f64>,
+
+    /// Exclude sequences in which the fraction of Ns is above this value from all statistics.
+    #[clap(long, value_name = "F", value_parser = parse_fraction)]
+    pub max_n_fraction: Option<f64>,
+
+    /// Exclude sequences with a Shannon entropy of their bases below this value from all statistics.
+    /// The entropy is between 0 for homopolymers and 2 for sequences with equal amounts of A, C, G and T.
+    #[clap(long, value_name = "F")]
+    pub min_complexity: Option<f64>,
+
+    /// Exclude sequences that consist only of Ns from all statistics.
+    /// Otherwise, they are included and a warning is printed if there are any.
+    #[clap(long)]
+    pub exclude_all_n: bool,
+
+    /// Skip this many records at the start of each input, before applying any filters.
+    /// Skipped records are not counted in any statistics.
+    /// Combined with `--limit`, this selects a window of records.
+    /// For paired-end reads, this is the number of pairs.
+    #[clap(long, value_name = "N", default_value_t = 0)]
+    pub skip: usize,
+
+    /// Stop reading each input after this many records after the skipped ones, including filtered records.
+    /// For paired-end reads, this is the number of pairs.
+    #[clap(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Include each record with this probability in the statistics, before applying any filters.
+    /// For paired-end reads, both ends of a pair are sampled together.
+    #[clap(long, value_name = "FRACTION", value_parser = parse_fraction)]
+    pub subsample: Option<f64>,
+
+    /// The seed of the random number generator for `--subsample`.
+    /// Defaults to a fixed seed, such that the sample is the same for each run.
+    #[clap(long, value_name = "SEED", requires = "subsample")]
+    pub seed: Option<u64>,
+
+    /// Check for records with the same id, including filtered records, and warn about each duplicate id.
+    #[clap(long)]
+    pub check_duplicates: bool,
+
+    /// Report groups of records with identical sequences, ignoring case and line breaks.
+    /// Sequences are compared by a 64-bit hash, so rarely, different sequences may be reported as duplicates.
+    #[clap(long)]
+    pub detect_duplicate_sequences: bool,
+
+    /// Compare sequences with the same hash byte by byte for `--detect-duplicate-sequences`.
+    /// This keeps a copy of each distinct sequence in memory, i.e. up to the total length of the input.
+    #[clap(long, requires = "detect_duplicate_sequences")]
+    pub verify_duplicates: bool,
+
+    /// Count the sequences with characters that are not IUPAC nucleotide codes (ACGTURYSWKMBDHVN) or gaps (-), ignoring case,
+    /// e.g. protein sequences or corrupted data.
+    /// With `--log-level debug`, the first such character of each sequence and its position are printed.
+    #[clap(long)]
+    pub check_characters: bool,
+
+    /// Exit with an error on the first invalid record instead of warning about it,
+    /// i.e. on the first empty sequence, on the first fastq record whose quality string differs in length from its sequence,
+    /// on the first duplicate id with `--check-duplicates`, or on the first invalid character with `--check-characters`.
+    #[clap(long)]
+    pub strict: bool,
+
+    /// Additional percentiles for Nx metric to report (pass multiple times for multiple percentiles).
+    /// N50, N75 and N90 are always reported.
+    #[clap(
+        long = "additional-percentile",
+        value_name = "ADDITIONAL_PERCENTILE",
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    pub additional_percentiles: Vec<u8>,
+
+    /// Print the Nx values of the sequences for all percentiles from 1 to 100 after the statistics.
+    #[clap(long)]
+    pub all_percentiles: bool,
+
+    /// Write the Nx values for all percentiles from 1 to 100 to this file as tab-separated data for plotting,
+    /// with the columns `percentile`, `nx_length` and `hoco_nx_length`.
+    /// With `--genome-size`, the columns `ngx_length` and `lgx_count` are added.
+    /// Values that are not available are written as `NA`.
+    #[clap(long, value_name = "FILE")]
+    pub nx_curve_output: Option<PathBuf>,
+
+    /// Write the Lorenz curve of the sequence lengths to this file as tab-separated data,
+    /// i.e. the fraction of the total length in the shortest sequences for each fraction of the sequences
+    /// from 0 to 1 in steps of 0.01, with the columns `cumulative_fraction_sequences` and `cumulative_fraction_bases`.
+    /// The Gini coefficient is twice the area between the curve and the diagonal.
+    #[clap(long, value_name = "FILE")]
+    pub lorenz_curve: Option<PathBuf>,
+
+    /// Do not compute the statistics of the homopolymer-compressed sequences, which saves memory.
+    #[clap(long)]
+    pub no_hoco: bool,
+
+    /// Print the number of sequences, total length and N50 per group of sequences after the statistics,
+    /// where sequences are grouped by the part of their id before the first occurrence of SEPARATOR,
+    /// e.g. `chr1` for `chr1_ctg001` with `_`.
+    #[clap(long, value_name = "SEPARATOR", value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    pub group_by_prefix: Option<String>,
+
+    /// Do not store the lengths of all sequences, but approximate the Nx metrics from a random sample of them,
+    /// which bounds the memory for inputs with very many records.
+    /// The record count, total length, mean, min and max length are still exact.
+    #[clap(
+        long,
+        conflicts_with_all = &["genome_size", "long_read_thresholds", "length_histogram", "all_percentiles", "nx_curve_output", "lorenz_curve", "cumulative_length_table", "brief", "quast_format"]
+    )]
+    pub streaming: bool,
+
+    /// The maximum number of sequence lengths sampled with `--streaming`.
+    #[clap(long, value_name = "N", default_value_t = DEFAULT_RESERVOIR_SIZE, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub reservoir_size: usize,
+
+    /// Report statistics about polyG and polyA tails at the 3' end of the sequences.
+    #[clap(long)]
+    pub detect_poly_tails: bool,
+
+    /// The minimum length of a run of Gs or As at the end of a sequence to be counted as a tail.
+    #[clap(long, value_name = "N", default_value_t = DEFAULT_MIN_POLY_TAIL_LENGTH, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub min_poly_tail_length: usize,
+
+    /// Count the occurrences of this telomere repeat motif and its reverse complement,
+    /// and report the sequences with at least `--min-telomere-repeats` occurrences within 10 kb of either end
+    /// after the statistics.
+    /// Without a value, the vertebrate motif TTAGGG is used.
+    #[clap(
+        long,
+        value_name = "MOTIF",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_TELOMERE_MOTIF,
+        value_parser = parse_motif
+    )]
+    pub telomere_motif: Option<String>,
+
+    /// The minimum number of occurrences of the telomere motif within 10 kb of an end of a sequence to count as a telomere.
+    #[clap(long, value_name = "N", default_value_t = DEFAULT_MIN_TELOMERE_REPEATS, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub min_telomere_repeats: usize,
+
+    /// Count the reads that contain this adapter sequence, e.g. AGATCGGAAGAGC for Illumina TruSeq,
+    /// and the mean position of its first occurrence (pass multiple times for multiple adapters).
+    #[clap(long, value_name = "SEQUENCE", value_parser = parse_motif)]
+    pub adapter: Vec<String>,
+
+    /// The minimum length of a run of Ns at which scaffolds are split into contigs for the contig statistics.
+    #[clap(long, value_name = "N", default_value_t = DEFAULT_SCAFFOLD_N_THRESHOLD, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub scaffold_n_threshold: usize,
+
+    /// The expected genome size in bases.
+    /// If given, the NG50, NG75 and NG90 metrics are reported relative to it.
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub genome_size: Option<u64>,
+
+    /// Report the number and total length of the sequences of at least each of these lengths,
+    /// e.g. to count the ultra-long reads of a nanopore run.
+    /// Without values, the thresholds 10000, 50000, 100000, 500000 and 1000000 are used.
+    #[clap(
+        long,
+        value_name = "THRESHOLDS",
+        value_delimiter = ',',
+        num_args = 0..,
+        default_missing_values = &["10000", "50000", "100000", "500000", "1000000"],
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub long_read_thresholds: Vec<usize>,
+
+    /// Write the number and total length of the sequences of at least each of the `--cumulative-thresholds`
+    /// to this file as tab-separated data, with the columns `min_length`, `read_count`, `total_bases`,
+    /// `fraction_reads` and `fraction_bases`.
+    #[clap(long, value_name = "FILE")]
+    pub cumulative_length_table: Option<PathBuf>,
+
+    /// The minimum lengths of the rows of `--cumulative-length-table`.
+    #[clap(
+        long,
+        value_name = "THRESHOLDS",
+        value_delimiter = ',',
+        default_values = &["0", "500", "1000", "2000", "5000", "10000", "20000", "50000", "100000"],
+        requires = "cumulative_length_table"
+    )]
+    pub cumulative_thresholds: Vec<usize>,
+
+    /// The format in which the statistics are printed.
+    /// For multiple input files, json and yaml output contain an object with the fields `inputs` and `combined`.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub output_format: OutputFormat,
+
+    /// Print one row of statistics per record instead of the aggregate statistics.
+    /// The aggregate statistics are printed to stderr instead.
+    #[clap(long)]
+    pub per_sequence: bool,
+
+    /// Include the Shannon entropy of the bases of each record in the per-sequence statistics.
+    #[clap(long, requires = "per_sequence")]
+    pub complexity: bool,
+
+    /// Print the id and a checksum of the sequence of each record instead of the aggregate statistics.
+    /// The checksum is computed over the sequence converted to uppercase, with whitespace removed.
+    /// The aggregate statistics are printed to stderr instead.
+    /// With `--per-sequence`, the checksum is added as a column to the per-sequence statistics instead.
+    #[clap(long, value_enum, value_name = "ALGORITHM")]
+    pub checksums: Option<ChecksumArg>,
+
+    /// Write one JSON object per record to this file in JSON Lines format.
+    /// If the file is `-`, then the objects are written to stdout, and the statistics are printed to stderr
+    /// unless `--output` is given.
+    #[clap(long, value_name = "FILE")]
+    pub per_record_jsonl: Option<PathBuf>,
+
+    /// Write the statistics to this file instead of stdout.
+    /// With `--per-sequence`, the per-sequence statistics are written to this file.
+    #[clap(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Write the records that are not filtered to this file in fasta format.
+    #[clap(long, value_name = "FILE")]
+    pub output_fasta: Option<PathBuf>,
+
+    /// Write the records that are not filtered to this file in fastq format.
+    /// Requires fastq input.
+    #[clap(long, value_name = "FILE", conflicts_with = "output_fasta")]
+    pub output_fastq: Option<PathBuf>,
+
+    /// The number of threads used to compute the statistics of the sequences.
+    /// The input is parsed in an additional thread.
+    /// Defaults to the number of logical CPUs.
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub threads: Option<u64>,
+
+    /// Write the runs of Ns of the records that are not filtered to this file in BED format.
+    #[clap(long, value_name = "FILE")]
+    pub output_n_regions: Option<PathBuf>,
+
+    /// Write each record that is not filtered to this file as a BED interval spanning the whole sequence.
+    #[clap(long, value_name = "FILE")]
+    pub output_bed: Option<PathBuf>,
+
+    /// Write the statistics of each input to this file as MultiQC custom content for the general statistics table.
+    /// The sample names are the input file names without compression and fasta or fastq extensions.
+    #[clap(long, value_name = "FILE")]
+    pub multiqc_json: Option<PathBuf>,
+
+    /// Write a samtools-compatible index of the fasta input to `INPUT.fai`.
+    /// Records that are filtered are not indexed.
+    /// For compressed input, the offsets refer to the decompressed file.
+    /// Requires a single input file.
+    #[clap(long)]
+    pub generate_fai: bool,
+
+    /// Write the index of `--generate-fai` to this file instead of `INPUT.fai`.
+    #[clap(long, value_name = "FILE", requires = "generate_fai")]
+    pub fai_output: Option<PathBuf>,
+
+    /// Print only the N50 of the sequences as a bare number, without progress bar or log messages.
+    /// For multiple input files, the N50 of the combined statistics is printed.
+    #[clap(
+        long,
+        conflicts_with_all = &["output_format", "per_sequence", "checksums"]
+    )]
+    pub brief: bool,
+
+    /// Print only the given format string, with each `{KEY}` replaced by the value of the metric with that key,
+    /// e.g. `N50={n50}, total={total_length}`. The keys are those of `--output-format tsv`.
+    /// Use `\n` for a newline and `{{` for a literal brace.
+    /// For multiple input files, the combined statistics are used.
+    #[clap(
+        long,
+        value_name = "TEMPLATE",
+        value_parser = Template::parse,
+        conflicts_with_all = &["output_format", "per_sequence", "checksums", "brief"]
+    )]
+    pub template: Option<Template>,
+
+    /// Print only the rows of the `report.tsv` of QUAST 5 that are supported, e.g. `# contigs` and `N50`,
+    /// with one column per input file.
+    /// Unlike QUAST, sequences of any length are counted.
+    #[clap(
+        long,
+        conflicts_with_all = &["output_format", "per_sequence", "checksums", "brief", "template"]
+    )]
+    pub quast_format: bool,
+
+    /// Exit with code 1 if the N50 of the combined statistics is below this threshold.
+    /// With `--streaming`, the approximate N50 is used.
+    #[clap(long, value_name = "THRESHOLD")]
+    pub assert_n50: Option<usize>,
+
+    /// Exit with code 1 if the total length of the combined statistics is below this threshold.
+    #[clap(long, value_name = "THRESHOLD")]
+    pub assert_total_length: Option<u64>,
+
+    /// Exit with code 1 if the fraction of Ns of the combined statistics is above this fraction.
+    #[clap(long, value_name = "FRACTION", value_parser = parse_fraction)]
+    pub assert_max_n_fraction: Option<f64>,
+
+    /// Exit with code 1 if there are fewer records than this after filtering.
+    #[clap(long, value_name = "MIN")]
+    pub assert_record_count: Option<usize>,
+
+    /// For fastq input, print a histogram of the mean read qualities after the statistics.
+    /// Ignored for json and yaml output, which always contain the histogram.
+    #[clap(long)]
+    pub quality_histogram: bool,
+
+    /// For fastq input, write the mean quality at each read position to this file as tab-separated data,
+    /// with the columns `position`, `mean_quality` and `read_count`.
+    /// Positions that are reached by less than 0.1% of the reads are omitted.
+    #[clap(long, value_name = "FILE")]
+    pub per_cycle_quality: Option<PathBuf>,
+
+    /// Print the number of homopolymer runs of each length after the statistics, excluding runs of Ns.
+    /// Ignored for json and yaml output, which always contain the distribution.
+    #[clap(long)]
+    pub homopolymer_distribution: bool,
+
+    /// Print the count and frequency of each of the 16 dinucleotides after the statistics,
+    /// counting the pairs of consecutive bases of each sequence, ignoring pairs with other characters than ACGT.
+    /// Ignored for json and yaml output, which always contain the counts.
+    #[clap(long)]
+    pub dinucleotide_frequency: bool,
+
+    /// Count the distinct canonical k-mers of this length, between 1 and 32, and report the fraction of them
+    /// that occur only once, e.g. 21 as a measure of the complexity or heterozygosity of an assembly.
+    /// The counts of all distinct k-mers are kept in memory.
+    #[clap(long, value_name = "K", value_parser = RangedU64ValueParser::<usize>::new().range(1..=32))]
+    pub kmer_uniqueness: Option<usize>,
+
+    /// Print a histogram of the sequence lengths with the given number of equal-width bins after the statistics.
+    #[clap(long, value_name = "NBINS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub length_histogram: Option<u64>,
+
+    /// Print the ids and lengths of the N longest sequences after the statistics.
+    #[clap(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub top: Option<usize>,
+
+    /// Space the bins of the length histogram logarithmically.
+    #[clap(long, requires = "length_histogram")]
+    pub log_scale: bool,
+}
+
+/// The seed for `--subsample` if none is given.
+pub const DEFAULT_SEED: u64 = 0;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compute statistics of fasta or fastq files.
+    ///
+    /// This is the default if no subcommand is given.
+    Stats(Box<StatsArgs>),
+    /// Compare the statistics of two fasta or fastq files.
+    ///
+    /// Prints each metric with its values for both files and the difference of the second to the first.
+    /// The filters and other options of the `stats` subcommand apply to both files.
+    #[clap(mut_arg("input", |input| input
+        .action(ArgAction::Set)
+        .num_args(2)
+        .value_names(["FIRST", "SECOND"])
+        .help("The two fasta or fastq files, e.g. an assembly before and after polishing")))]
+    Compare(Box<StatsArgs>),
+    /// Write the records that pass the filters to stdout, in the format of the input.
+    ///
+    /// The number of records that passed and failed the filters is printed to stderr.
+    Filter(FilterArgs),
+    /// Write the records sorted by length or by id, in the format of the input.
+    ///
+    /// All records are kept in memory, so the memory usage is about the size of the decompressed input.
+    Sort(SortArgs),
+    /// Check a fasta or fastq file for problems, and print each problem with its line number.
+    ///
+    /// Ids with non-ASCII characters, characters that are not IUPAC nucleotide codes, duplicate ids
+    /// and empty sequences are warnings. Parse errors, e.g. fastq records whose sequence and quality strings
+    /// differ in length, and ids that are not valid utf-8 are errors.
+    /// Exits with code 0 if there are no problems, 1 if there are only warnings, and 2 if there are errors.
+    Validate {
+        /// The fasta or fastq input file, optionally compressed with gzip, bzip2 or zstd.
+        /// Pass `-` to read from stdin.
+        input: PathBuf,
+    },
+    /// Measure the throughput of reading a fasta or fastq file and computing its statistics.
+    ///
+    /// The file is read multiple times, and the mean time spent on parsing, on computing the statistics
+    /// and on rendering them is printed. Unlike the statistics, this runs in a single thread.
+    Bench(BenchArgs),
+}
+
+// The arguments of the `bench` subcommand.
+#[derive(Args)]
+pub struct BenchArgs {
+    /// The fasta or fastq input file, optionally compressed with gzip, bzip2 or zstd.
+    pub input: PathBuf,
+
+    /// The number of times the file is read.
+    #[clap(long, value_name = "N", default_value_t = 3, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub iterations: usize,
+
+    /// Only parse the records without computing their statistics, to measure the parsing overhead.
+    #[clap(long)]
+    pub skip_stats: bool,
+}
+
+// The arguments of the `filter` subcommand.
+#[derive(Args)]
+pub struct FilterArgs {
+    /// The fasta or fastq input file, optionally compressed with gzip, bzip2 or zstd.
+    /// Pass `-` to read from stdin.
+    pub input: PathBuf,
+
+    /// Filter records with the given ids (pass multiple times for multiple ids).
+    #[clap(long = "filter-id", value_name = "FILTER_ID")]
+    pub filter_ids: Vec<String>,
+
+    /// Filter records with the ids listed in this file, one per line.
+    /// Empty lines and lines starting with `#` are ignored.
+    #[clap(long, value_name = "FILE")]
+    pub exclude_ids_file: Option<PathBuf>,
+
+    /// Keep only the records with the ids listed in this file, in the format of `--exclude-ids-file`.
+    #[clap(long, value_name = "FILE")]
+    pub include_ids_file: Option<PathBuf>,
+
+    /// Compare ids case-insensitively in `--filter-id`, `--exclude-ids-file` and `--include-ids-file`.
+    #[clap(long)]
+    pub ignore_case: bool,
+
+    /// Filter sequences shorter than this many bases.
+    #[clap(long, value_name = "N")]
+    pub min_length: Option<usize>,
+
+    /// Filter sequences longer than this many bases.
+    #[clap(long, value_name = "N")]
+    pub max_length: Option<usize>,
+
+    /// Filter sequences in which the fraction of Ns is above this value.
+    #[clap(long, value_name = "F", value_parser = parse_fraction)]
+    pub max_n_fraction: Option<f64>,
+
+    /// Write the records that fail the filters instead.
+    #[clap(long)]
+    pub invert: bool,
+}
+
+// The arguments of the `sort` subcommand.
+#[derive(Args)]
+pub struct SortArgs {
+    /// The fasta or fastq input file, optionally compressed with gzip, bzip2 or zstd.
+    /// Pass `-` to read from stdin.
+    pub input: PathBuf,
+
+    /// The order of the records, by descending length or alphabetically by id.
+    #[clap(long, value_enum, value_name = "KEY", default_value_t = SortKey::Length)]
+    pub sort_by: SortKey,
+
+    /// Invert the order, i.e. sort by ascending length or reverse alphabetically.
+    #[clap(long)]
+    pub reverse: bool,
+
+    /// Write the sorted records to this file instead of stdout.
+    #[clap(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    Length,
+    Name,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable lines of the form `metric: value`.
+    Human,
+    /// A single JSON object.
+    Json,
+    /// One `key\tvalue` line per metric.
+    Tsv,
+    /// A YAML document with the same structure as the json output, preceded by a comment naming the inputs.
+    Yaml,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Fasta,
+    /// Fastq with single-line sequences and quality strings.
+    Fastq,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompressionArg {
+    /// The inputs are not compressed.
+    None,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChecksumArg {
+    Sha256,
+    Md5,
+}
+
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    let percentage: f64 = value.parse().map_err(|err| format!("{err}"))?;
+    if (0.0..=100.0).contains(&percentage) {
+        Ok(percentage)
+    } else {
+        Err("must be between 0 and 100".to_owned())
+    }
+}
+
+fn parse_motif(value: &str) -> Result<String, String> {
+    let motif = value.to_ascii_uppercase();
+    if !motif.is_empty() && motif.bytes().all(|base| b"ACGT".contains(&base)) {
+        Ok(motif)
+    } else {
+        Err("must be a non-empty sequence of A, C, G and T".to_owned())
+    }
+}
+
+fn parse_fraction(value: &str) -> Result<f64, String> {
+    let fraction: f64 = value.parse().map_err(|err| format!("{err}"))?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err("must be between 0 and 1".to_owned())
+    }
+}
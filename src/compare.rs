@@ -0,0 +1,195 @@
+//! Compare the statistics of two inputs metric by metric.
+
+use crate::metrics::{Metric, MetricValue};
+use crate::AssemblySummary;
+
+/// The values of a single metric for two inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricComparison {
+    /// The key of the metric, see [`Metric::key`].
+    pub key: String,
+    /// The label of the metric, see [`Metric::label`].
+    pub label: String,
+    /// The value for the first input, `None` if the metric is not reported for it.
+    pub first: Option<MetricValue>,
+    /// The value for the second input, `None` if the metric is not reported for it.
+    pub second: Option<MetricValue>,
+    /// Whether the second value is better than the first, `None` if neither is better
+    /// or if there is no notion of better for this metric.
+    pub improved: Option<bool>,
+}
+
+impl MetricComparison {
+    /// The difference of the second value to the first, e.g. `+12 (+5.00%)`.
+    /// Returns `None` if one of the values is missing or not a number.
+    pub fn delta(&self) -> Option<String> {
+        let first = numeric_value(self.first.as_ref()?)?;
+        let second = numeric_value(self.second.as_ref()?)?;
+        let absolute = match (self.first.as_ref()?, self.second.as_ref()?) {
+            (
+                MetricValue::Count(_) | MetricValue::Length(_),
+                MetricValue::Count(_) | MetricValue::Length(_),
+            ) => format!("{:+}", second as i128 - first as i128),
+            _ => format!("{:+.2}", second - first),
+        };
+        Some(if first == 0.0 {
+            absolute
+        } else {
+            format!("{absolute} ({:+.2}%)", (second - first) / first * 100.0)
+        })
+    }
+}
+
+/// Compare the metrics of two summaries.
+///
+/// The metrics are returned in the order of the first summary, followed by those metrics that only the second summary reports.
+pub fn compare_summaries(
+    first: &AssemblySummary,
+    second: &AssemblySummary,
+) -> Vec<MetricComparison> {
+    let first_metrics = first.metrics();
+    let mut second_metrics = second.metrics();
+    let same_total_length = first
+        .lengths
+        .as_ref()
+        .map(|lengths| lengths.with_ns.total_length)
+        == second
+            .lengths
+            .as_ref()
+            .map(|lengths| lengths.with_ns.total_length);
+
+    let mut comparisons = Vec::new();
+    for Metric {
+        key, label, value, ..
+    } in first_metrics
+    {
+        let second_value = second_metrics
+            .iter()
+            .position(|metric| metric.key == key)
+            .map(|index| second_metrics.remove(index).value);
+        let improved = match direction(&key) {
+            Some(Direction::Higher) => is_improved(&value, second_value.as_ref(), true),
+            Some(Direction::Lower) => is_improved(&value, second_value.as_ref(), false),
+            // Fewer sequences are only better if they contain the same number of bases.
+            Some(Direction::LowerForSameTotalLength) if same_total_length => {
+                is_improved(&value, second_value.as_ref(), false)
+            }
+            Some(Direction::LowerForSameTotalLength) | None => None,
+        };
+        comparisons.push(MetricComparison {
+            key,
+            label,
+            first: Some(value),
+            second: second_value,
+            improved,
+        });
+    }
+    comparisons.extend(second_metrics.into_iter().map(
+        |Metric {
+             key, label, value, ..
+         }| MetricComparison {
+            key,
+            label,
+            first: None,
+            second: Some(value),
+            improved: None,
+        },
+    ));
+    comparisons
+}
+
+/// Which values of a metric are better.
+enum Direction {
+    Higher,
+    Lower,
+    LowerForSameTotalLength,
+}
+
+/// The direction in which the metric with the given key improves, if there is one.
+fn direction(key: &str) -> Option<Direction> {
+    let key = key.strip_prefix("hoco_").unwrap_or(key);
+    let key = key.strip_prefix("contig_").unwrap_or(key);
+    let key = key.strip_suffix("_without_ns").unwrap_or(key);
+    match key {
+        "record_count" | "count" => Some(Direction::LowerForSameTotalLength),
+        "n_bases" | "n_count" | "n_percent" | "n_fraction" | "ambiguous_count"
+        | "uncertain_count" => Some(Direction::Lower),
+        "non_n_fraction" | "aun" | "aung" | "e_size" | "max_len" | "mean_len" | "median_len" => {
+            Some(Direction::Higher)
+        }
+        _ => {
+            if is_percentile_key(key, "n") || is_percentile_key(key, "ng") {
+                Some(Direction::Higher)
+            } else if is_percentile_key(key, "l") || is_percentile_key(key, "lg") {
+                Some(Direction::Lower)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Returns true if the key consists of the prefix followed by a percentile, e.g. `n50` for the prefix `n`.
+fn is_percentile_key(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix).map_or(false, |percentile| {
+        !percentile.is_empty() && percentile.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+fn is_improved(
+    first: &MetricValue,
+    second: Option<&MetricValue>,
+    higher_is_better: bool,
+) -> Option<bool> {
+    let first = numeric_value(first)?;
+    let second = numeric_value(second?)?;
+    if first == second {
+        None
+    } else {
+        Some((second > first) == higher_is_better)
+    }
+}
+
+fn numeric_value(value: &MetricValue) -> Option<f64> {
+    match value {
+        MetricValue::Count(value) | MetricValue::Length(value) => Some(*value as f64),
+        MetricValue::Float(value)
+        | MetricValue::Percentage(value)
+        | MetricValue::Coefficient(value) => Some(*value),
+        MetricValue::Text(_) | MetricValue::NotAvailable(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compare::compare_summaries;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_compare_summaries() {
+        let options = StatisticsOptions::default();
+        let first = b">1\nACGTNNNNNNNNNNAC\n>2\nAC\n";
+        let second = b">1\nACGTAAAAAAAAAAAC\n>2\nACG\n>3\nA\n";
+        let first = basic_statistics(first.as_slice(), first.len() as u64, &options).unwrap();
+        let second = basic_statistics(second.as_slice(), second.len() as u64, &options).unwrap();
+        let comparisons = compare_summaries(&first, &second);
+        let comparison = |key: &str| {
+            comparisons
+                .iter()
+                .find(|comparison| comparison.key == key)
+                .unwrap()
+        };
+
+        assert_eq!(comparisons[0].key, "record_count");
+        assert_eq!(comparison("record_count").delta().unwrap(), "+1 (+50.00%)");
+        assert_eq!(comparison("record_count").improved, None);
+        assert_eq!(comparison("n_bases").improved, Some(true));
+        assert_eq!(comparison("n_bases").delta().unwrap(), "-10 (-100.00%)");
+        assert_eq!(comparison("n_fraction").improved, Some(true));
+        assert_eq!(comparison("non_n_fraction").improved, Some(true));
+        assert_eq!(comparison("n50").improved, None);
+        assert_eq!(comparison("contig_n50").improved, Some(true));
+        assert_eq!(comparison("min_len").improved, None);
+        assert_eq!(comparison("other_characters").delta(), None);
+    }
+}
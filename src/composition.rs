@@ -0,0 +1,144 @@
+//! Count the characters of sequences.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::ops::AddAssign;
+
+/// The number of occurrences of each base in a sequence, ignoring case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseComposition {
+    pub a: u64,
+    pub c: u64,
+    pub g: u64,
+    pub t: u64,
+    pub n: u64,
+    /// The number of characters that are not one of `ACGTN`, ignoring case.
+    pub other: u64,
+    /// The set of characters counted as `other`.
+    pub other_characters: BTreeSet<u8>,
+}
+
+impl BaseComposition {
+    /// Count the bases of the given sequence.
+    /// Line terminators are ignored, such that multiline fasta sequences with `\n` or `\r\n` line endings can be passed directly.
+    pub fn new(sequence: &[u8]) -> Self {
+        let mut composition = Self::default();
+
+        for byte in sequence.iter().copied() {
+            match byte {
+                b'\n' | b'\r' => {}
+                b'a' | b'A' => composition.a += 1,
+                b'c' | b'C' => composition.c += 1,
+                b'g' | b'G' => composition.g += 1,
+                b't' | b'T' => composition.t += 1,
+                b'n' | b'N' => composition.n += 1,
+                other => {
+                    composition.other += 1;
+                    composition.other_characters.insert(other);
+                }
+            }
+        }
+
+        composition
+    }
+
+    /// The total number of characters.
+    pub fn total(&self) -> u64 {
+        self.a + self.c + self.g + self.t + self.n + self.other
+    }
+
+    /// The percentage of the given number of characters of the total number of characters, 0 if there are no characters.
+    pub fn percent(&self, count: u64) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            0.0
+        } else {
+            count as f64 / total as f64 * 100.0
+        }
+    }
+
+    /// The observed number of CG dinucleotides relative to the number expected from the C and G content,
+    /// i.e. `cpg_count * total / (c * g)`, or `None` if there are no C or no G bases.
+    pub fn cpg_oe_ratio(&self, cpg_count: u64) -> Option<f64> {
+        if self.c == 0 || self.g == 0 {
+            None
+        } else {
+            Some(cpg_count as f64 * self.total() as f64 / (self.c as f64 * self.g as f64))
+        }
+    }
+
+    /// The characters counted as `other` in ascending order, with non-printable characters escaped.
+    pub fn other_characters_string(&self) -> String {
+        self.other_characters
+            .iter()
+            .map(|character| character.escape_ascii().to_string())
+            .collect()
+    }
+}
+
+/// The percentages of the bases of a [`BaseComposition`] of its total number of characters.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BasePercentages {
+    pub a: f64,
+    pub c: f64,
+    pub g: f64,
+    pub t: f64,
+    pub n: f64,
+    pub other: f64,
+}
+
+impl BasePercentages {
+    /// All percentages are 0 if the composition contains no characters.
+    pub fn new(composition: &BaseComposition) -> Self {
+        Self {
+            a: composition.percent(composition.a),
+            c: composition.percent(composition.c),
+            g: composition.percent(composition.g),
+            t: composition.percent(composition.t),
+            n: composition.percent(composition.n),
+            other: composition.percent(composition.other),
+        }
+    }
+}
+
+impl<'a> AddAssign<&'a BaseComposition> for BaseComposition {
+    fn add_assign(&mut self, rhs: &'a BaseComposition) {
+        self.a += rhs.a;
+        self.c += rhs.c;
+        self.g += rhs.g;
+        self.t += rhs.t;
+        self.n += rhs.n;
+        self.other += rhs.other;
+        self.other_characters
+            .extend(rhs.other_characters.iter().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::composition::{BaseComposition, BasePercentages};
+
+    #[test]
+    fn test_composition() {
+        let mut composition = BaseComposition::new(b"ACgtnN\nRYa");
+        assert_eq!(composition.a, 2);
+        assert_eq!(composition.c, 1);
+        assert_eq!(composition.g, 1);
+        assert_eq!(composition.t, 1);
+        assert_eq!(composition.n, 2);
+        assert_eq!(composition.other, 2);
+        assert_eq!(composition.total(), 9);
+
+        composition += &BaseComposition::new(b"K\x01");
+        assert_eq!(composition.other, 4);
+        assert_eq!(composition.other_characters_string(), "\\x01KRY");
+
+        assert_eq!(BaseComposition::new(b"ACGT").cpg_oe_ratio(1), Some(4.0));
+        assert_eq!(BasePercentages::new(&BaseComposition::new(b"AAGN")).a, 50.0);
+        assert_eq!(BasePercentages::new(&BaseComposition::default()).a, 0.0);
+        assert_eq!(BaseComposition::new(b"AAGT").cpg_oe_ratio(0), None);
+    }
+}
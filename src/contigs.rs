@@ -0,0 +1,171 @@
+//! Split scaffolds into contigs at runs of Ns.
+
+use crate::error::FastxStatisticsError;
+use crate::{AcceptedRecord, NxSummary};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+pub use crate::defaults::DEFAULT_SCAFFOLD_N_THRESHOLD;
+
+/// Statistics about the contigs obtained by splitting all sequences at long runs of Ns.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContigSummary {
+    pub contig_count: usize,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub lengths: NxSummary,
+}
+
+impl ContigSummary {
+    /// Compute the statistics of the given non-empty list of contig lengths.
+    /// The list is sorted in the process.
+    pub fn new(contig_lengths: &mut [usize], additional_percentiles: &[u8]) -> Self {
+        contig_lengths.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            contig_count: contig_lengths.len(),
+            lengths: NxSummary::new(contig_lengths, additional_percentiles),
+        }
+    }
+}
+
+/// Split the given sequence at each run of at least `min_run` Ns, and return the lengths of the resulting contigs.
+///
+/// Shorter runs of Ns inside a contig count towards its length, but Ns at the ends of the sequence never belong to a contig.
+/// Line terminators are ignored, such that multiline fasta sequences with `\n` or `\r\n` line endings can be passed directly.
+pub fn split_on_n_runs(sequence: &[u8], min_run: usize) -> Vec<usize> {
+    debug_assert!(min_run > 0);
+    let mut contig_lengths = Vec::new();
+    let mut contig_len = 0;
+    let mut n_run = 0;
+
+    for byte in sequence.iter().copied() {
+        match byte {
+            b'\n' | b'\r' => {}
+            b'n' | b'N' => n_run += 1,
+            _ => {
+                if n_run >= min_run {
+                    if contig_len > 0 {
+                        contig_lengths.push(contig_len);
+                    }
+                    contig_len = 0;
+                } else if contig_len > 0 {
+                    contig_len += n_run;
+                }
+                n_run = 0;
+                contig_len += 1;
+            }
+        }
+    }
+
+    if contig_len > 0 {
+        contig_lengths.push(contig_len);
+    }
+    contig_lengths
+}
+
+/// Call `run_callback` with the start and end of each run of Ns in the given sequence as 0-based half-open interval,
+/// as soon as the run ends. Stops at the first error returned by the callback.
+/// Line terminators are ignored, such that multiline fasta sequences with `\n` or `\r\n` line endings can be passed directly.
+pub fn for_each_n_run(
+    sequence: &[u8],
+    mut run_callback: impl FnMut(usize, usize) -> Result<(), FastxStatisticsError>,
+) -> Result<(), FastxStatisticsError> {
+    let mut run_start = None;
+    let mut position = 0;
+
+    for byte in sequence.iter().copied() {
+        match byte {
+            b'\n' | b'\r' => continue,
+            b'n' | b'N' => {
+                run_start.get_or_insert(position);
+            }
+            _ => {
+                if let Some(start) = run_start.take() {
+                    run_callback(start, position)?;
+                }
+            }
+        }
+        position += 1;
+    }
+
+    if let Some(start) = run_start {
+        run_callback(start, position)?;
+    }
+    Ok(())
+}
+
+/// Write the runs of Ns of the given record as lines of a BED file with the columns `seqid`, `start` and `end`.
+pub fn write_n_regions(
+    mut output: impl Write,
+    record: AcceptedRecord,
+) -> Result<(), FastxStatisticsError> {
+    for_each_n_run(record.seq, |start, end| {
+        writeln!(output, "{}\t{start}\t{end}", record.id)
+            .map_err(|err| FastxStatisticsError::io("Cannot write N regions", err))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contigs::{for_each_n_run, split_on_n_runs, write_n_regions};
+    use crate::tests::FASTA;
+    use crate::{basic_statistics, basic_statistics_with_callback, StatisticsOptions};
+
+    #[test]
+    fn test_split_on_n_runs() {
+        assert_eq!(split_on_n_runs(b"ACGTNNNAC", 3), vec![4, 2]);
+        assert_eq!(split_on_n_runs(b"ACGTNNAC", 3), vec![8]);
+        assert_eq!(split_on_n_runs(b"NNACNNNNGG\nTnNN", 4), vec![2, 3]);
+        assert_eq!(split_on_n_runs(b"NNNN", 1), Vec::<usize>::new());
+        assert_eq!(split_on_n_runs(b"", 1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_for_each_n_run() {
+        let n_runs = |sequence| {
+            let mut runs = Vec::new();
+            for_each_n_run(sequence, |start, end| {
+                runs.push((start, end));
+                Ok(())
+            })
+            .unwrap();
+            runs
+        };
+        assert_eq!(n_runs(b"NNACnN\nNGN"), vec![(0, 2), (4, 7), (8, 9)]);
+        assert_eq!(n_runs(b"ACGT"), Vec::new());
+    }
+
+    #[test]
+    fn test_write_n_regions() {
+        let mut output = Vec::new();
+        basic_statistics_with_callback(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions::default(),
+            |record| write_n_regions(&mut output, record),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1\t8\t13\n2\t13\t14\n4\t10\t14\n"
+        );
+    }
+
+    #[test]
+    fn test_contig_summary() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                scaffold_n_threshold: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let contigs = summary.contigs.unwrap();
+        assert_eq!(contigs.contig_count, 7);
+        assert_eq!(contigs.lengths.total_length, 86);
+        assert_eq!(contigs.lengths.max_len, 29);
+    }
+}
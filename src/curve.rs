@@ -0,0 +1,179 @@
+//! The full Nx curve with related curves and the Lorenz curve of the lengths, as tab-separated data for plotting.
+
+use crate::ngx::compute_all_ngx_lgx;
+use crate::{compute_all_nx, total_length};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// The Nx values and related values for all percentiles from 1 to 100.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContiguityCurve {
+    /// The Nx values, where the first entry is N1.
+    pub nx: Vec<usize>,
+    /// The Nx values of the homopolymer-compressed sequences, `None` if they are not computed.
+    pub hoco_nx: Option<Vec<usize>>,
+    /// The NGx and LGx values, `None` if no genome size is given.
+    /// An entry is `None` if the sequences cover less than the percentile of the genome size.
+    pub ngx_lgx: Option<Vec<Option<(usize, usize)>>>,
+}
+
+impl ContiguityCurve {
+    /// Compute the curves of the given non-empty lists of sequence lengths, which must be sorted in descending order.
+    pub fn new(
+        sorted_sequence_lengths: &[usize],
+        sorted_hoco_lengths: Option<&[usize]>,
+        genome_size: Option<u64>,
+    ) -> Self {
+        let percentiles: Vec<_> = (1..=100).collect();
+        let all_nx =
+            |lengths: &[usize]| compute_all_nx(lengths, total_length(lengths), &percentiles);
+        Self {
+            nx: all_nx(sorted_sequence_lengths),
+            hoco_nx: sorted_hoco_lengths.map(all_nx),
+            ngx_lgx: genome_size.map(|genome_size| {
+                compute_all_ngx_lgx(sorted_sequence_lengths, genome_size, &percentiles)
+            }),
+        }
+    }
+
+    /// Write the curves as a table with a header row and one row per percentile.
+    /// The columns are `percentile`, `nx_length` and `hoco_nx_length`, followed by `ngx_length` and `lgx_count`
+    /// if a genome size is given.
+    /// Values that are not available are written as `NA`.
+    pub fn write_tsv(&self, mut output: impl Write) -> io::Result<()> {
+        write!(output, "percentile\tnx_length\thoco_nx_length")?;
+        if self.ngx_lgx.is_some() {
+            write!(output, "\tngx_length\tlgx_count")?;
+        }
+        writeln!(output)?;
+
+        for (index, nx) in self.nx.iter().enumerate() {
+            let hoco_nx = self.hoco_nx.as_ref().map(|hoco_nx| hoco_nx[index]);
+            write!(output, "{}\t{nx}\t{}", index + 1, or_na(hoco_nx))?;
+            if let Some(ngx_lgx) = &self.ngx_lgx {
+                let ngx_lgx = ngx_lgx[index];
+                write!(
+                    output,
+                    "\t{}\t{}",
+                    or_na(ngx_lgx.map(|(ngx, _)| ngx)),
+                    or_na(ngx_lgx.map(|(_, lgx)| lgx))
+                )?;
+            }
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the Lorenz curve of the given non-empty lengths, which must be sorted in descending order and sum up to `sum`.
+/// The curve contains the fraction of the total length in the shortest sequences for each fraction of the sequences
+/// from 0 to 1 in steps of 0.01, interpolated linearly between whole numbers of sequences.
+/// The area between the curve and the diagonal is half of the [`crate::gini_coefficient`].
+pub fn lorenz_curve(sorted_sequence_lengths: &[usize], sum: u64) -> Vec<f64> {
+    debug_assert!(sum > 0);
+    let count = sorted_sequence_lengths.len();
+    let mut ascending_lengths = sorted_sequence_lengths.iter().rev().copied();
+    // The number and total length of the shortest sequences that are fully covered by the current fraction.
+    let mut covered_count = 0;
+    let mut covered_bases = 0;
+    let mut next_length = ascending_lengths.next();
+    (0..=100)
+        .map(|percent| {
+            let sequences = (count * percent) as f64 / 100.0;
+            while covered_count < sequences.floor() as usize {
+                covered_bases += next_length.unwrap() as u64;
+                covered_count += 1;
+                next_length = ascending_lengths.next();
+            }
+            let partial_bases = next_length.map_or(0.0, |length| {
+                length as f64 * (sequences - covered_count as f64)
+            });
+            (covered_bases as f64 + partial_bases) / sum as f64
+        })
+        .collect()
+}
+
+/// Write the given Lorenz curve as a table with a header row and one row per point,
+/// with the columns `cumulative_fraction_sequences` and `cumulative_fraction_bases`.
+pub fn write_lorenz_curve(curve: &[f64], mut output: impl Write) -> io::Result<()> {
+    writeln!(
+        output,
+        "cumulative_fraction_sequences\tcumulative_fraction_bases"
+    )?;
+    for (percent, fraction) in curve.iter().enumerate() {
+        writeln!(output, "{}\t{fraction}", percent as f64 / 100.0)?;
+    }
+    Ok(())
+}
+
+fn or_na(value: Option<impl Display>) -> String {
+    value.map_or_else(|| "NA".to_owned(), |value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::{lorenz_curve, write_lorenz_curve, ContiguityCurve};
+    use crate::gini_coefficient;
+
+    #[test]
+    fn test_contiguity_curve() {
+        let curve = ContiguityCurve::new(&[6, 4], Some(&[3, 2]), Some(20));
+        assert_eq!(curve.nx[49], 6);
+        assert_eq!(curve.nx[99], 4);
+        let mut output = Vec::new();
+        curve.write_tsv(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 101);
+        assert_eq!(
+            lines[0],
+            "percentile\tnx_length\thoco_nx_length\tngx_length\tlgx_count"
+        );
+        assert_eq!(lines[30], "30\t6\t3\t6\t1");
+        assert_eq!(lines[50], "50\t6\t3\t4\t2");
+        assert_eq!(lines[80], "80\t4\t2\tNA\tNA");
+
+        let mut output = Vec::new();
+        ContiguityCurve::new(&[5], None, None)
+            .write_tsv(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output.lines().take(2).collect::<Vec<_>>(),
+            ["percentile\tnx_length\thoco_nx_length", "1\t5\tNA"]
+        );
+    }
+
+    #[test]
+    fn test_lorenz_curve() {
+        let lengths = [5, 3, 1, 1];
+        let curve = lorenz_curve(&lengths, 10);
+        assert_eq!(curve.len(), 101);
+        assert_eq!(curve[0], 0.0);
+        assert_eq!(curve[25], 0.1);
+        assert_eq!(curve[50], 0.2);
+        assert_eq!(curve[75], 0.5);
+        assert_eq!(curve[100], 1.0);
+        assert!((curve[60] - 0.32).abs() < 1e-12);
+
+        // The points of the curve are exact here, so the trapezoid rule gives the exact area below it.
+        let area: f64 = curve.windows(2).map(|w| (w[0] + w[1]) / 2.0 / 100.0).sum();
+        assert!((1.0 - 2.0 * area - gini_coefficient(&lengths, 10)).abs() < 1e-9);
+
+        let mut output = Vec::new();
+        write_lorenz_curve(&lorenz_curve(&[4], 4), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 102);
+        assert_eq!(
+            lines[0],
+            "cumulative_fraction_sequences\tcumulative_fraction_bases"
+        );
+        assert_eq!(lines[1], "0\t0");
+        assert_eq!(lines[51], "0.5\t0.5");
+        assert_eq!(lines[101], "1\t1");
+    }
+}
@@ -0,0 +1,18 @@
+//! The default values of the options that are shared between the library and the command line arguments.
+//!
+//! This module does not depend on anything, such that the build script can include it together with the argument definitions.
+
+/// The default minimum length of a run of Gs or As at the end of a sequence to be counted as a tail.
+pub const DEFAULT_MIN_POLY_TAIL_LENGTH: usize = 10;
+
+/// The default maximum number of sequence lengths in the sample.
+pub const DEFAULT_RESERVOIR_SIZE: usize = 10_000_000;
+
+/// The default minimum length of a run of Ns that separates two contigs, following the NCBI convention.
+pub const DEFAULT_SCAFFOLD_N_THRESHOLD: usize = 10;
+
+/// The default telomere repeat motif, which is the repeat of vertebrates.
+pub const DEFAULT_TELOMERE_MOTIF: &str = "TTAGGG";
+
+/// The default minimum number of occurrences of the motif at an end of a sequence to count as a telomere.
+pub const DEFAULT_MIN_TELOMERE_REPEATS: usize = 10;
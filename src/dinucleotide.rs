@@ -0,0 +1,99 @@
+//! Count the pairs of consecutive bases of sequences.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// The bases of the dinucleotides, in the order in which they are indexed.
+pub const BASES: [u8; 4] = *b"ACGT";
+
+/// The number of occurrences of each of the 16 dinucleotides.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DinucleotideCounts {
+    /// The counts indexed by `4 * first + second`, where the bases are numbered in the order of [`BASES`],
+    /// i.e. `AA`, `AC`, ..., `TT`.
+    pub counts: [u64; 16],
+}
+
+impl DinucleotideCounts {
+    /// Count the dinucleotides of the given sequence, ignoring case.
+    /// Pairs containing other characters than ACGT are not counted.
+    /// Line terminators are ignored, such that multiline fasta sequences with `\n` or `\r\n` line endings can be passed directly.
+    pub fn new(seq: &[u8]) -> Self {
+        let mut counts = [0; 16];
+        let mut previous = None;
+        for &character in seq {
+            if character == b'\n' || character == b'\r' {
+                continue;
+            }
+
+            let base = base_index(character);
+            if let (Some(previous), Some(base)) = (previous, base) {
+                counts[4 * previous + base] += 1;
+            }
+            previous = base;
+        }
+        Self { counts }
+    }
+
+    /// The number of all dinucleotides.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The dinucleotides with their counts and their frequencies relative to [`Self::total`].
+    pub fn frequencies(&self) -> impl Iterator<Item = (String, u64, f64)> + '_ {
+        let total = self.total();
+        self.counts.iter().enumerate().map(move |(index, &count)| {
+            let dinucleotide = String::from_utf8(vec![BASES[index / 4], BASES[index % 4]]).unwrap();
+            let frequency = if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64
+            };
+            (dinucleotide, count, frequency)
+        })
+    }
+}
+
+impl AddAssign<&DinucleotideCounts> for DinucleotideCounts {
+    fn add_assign(&mut self, other: &DinucleotideCounts) {
+        for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+    }
+}
+
+fn base_index(character: u8) -> Option<usize> {
+    BASES
+        .iter()
+        .position(|&base| base == character.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dinucleotide::DinucleotideCounts;
+
+    #[test]
+    fn test_dinucleotide_counts() {
+        let mut counts = DinucleotideCounts::new(b"ACg\nT\r\nNCGA");
+        counts += &DinucleotideCounts::new(b"TA");
+        let frequencies: Vec<_> = counts
+            .frequencies()
+            .filter(|(_, count, _)| *count > 0)
+            .collect();
+        assert_eq!(
+            frequencies,
+            [
+                ("AC".to_owned(), 1, 1.0 / 6.0),
+                ("CG".to_owned(), 2, 2.0 / 6.0),
+                ("GA".to_owned(), 1, 1.0 / 6.0),
+                ("GT".to_owned(), 1, 1.0 / 6.0),
+                ("TA".to_owned(), 1, 1.0 / 6.0),
+            ]
+        );
+        assert_eq!(counts.frequencies().count(), 16);
+        assert_eq!(DinucleotideCounts::new(b"A").total(), 0);
+    }
+}
@@ -0,0 +1,304 @@
+//! Detect records that share the same id or the same sequence.
+
+use crate::error::FastxStatisticsError;
+use log::warn;
+use rustc_hash::{FxHashMap, FxHasher};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::Hasher;
+
+/// Statistics about the ids of all records, including filtered ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DuplicateIdSummary {
+    pub id_count: usize,
+    pub unique_id_count: usize,
+    /// The number of records whose id was already used by an earlier record.
+    pub duplicate_id_count: usize,
+}
+
+/// Collects the ids of records to detect duplicates.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DuplicateIdAccumulator {
+    ids: HashSet<String>,
+    id_count: usize,
+    /// Return an error for the first duplicate instead of warning about each.
+    strict: bool,
+}
+
+impl DuplicateIdAccumulator {
+    pub(crate) fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            ..Default::default()
+        }
+    }
+
+    /// Add the id of a record, warning about it or returning an error if it is a duplicate.
+    pub(crate) fn add(&mut self, id: &str) -> Result<(), FastxStatisticsError> {
+        self.id_count += 1;
+        if self.ids.contains(id) {
+            self.report_duplicate(id)
+        } else {
+            self.ids.insert(id.to_owned());
+            Ok(())
+        }
+    }
+
+    /// Add all ids of `other`, reporting those that were added to `self` before.
+    pub(crate) fn extend(&mut self, other: &Self) -> Result<(), FastxStatisticsError> {
+        self.id_count += other.id_count;
+        for id in &other.ids {
+            if !self.ids.insert(id.clone()) {
+                self.report_duplicate(id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn report_duplicate(&self, id: &str) -> Result<(), FastxStatisticsError> {
+        if self.strict {
+            Err(FastxStatisticsError::DuplicateId(id.to_owned()))
+        } else {
+            warn!("Duplicate record id {id:?}");
+            Ok(())
+        }
+    }
+
+    pub(crate) fn finish(self) -> DuplicateIdSummary {
+        DuplicateIdSummary {
+            id_count: self.id_count,
+            unique_id_count: self.ids.len(),
+            duplicate_id_count: self.id_count - self.ids.len(),
+        }
+    }
+}
+
+/// Records with identical sequences, ignoring case and line breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DuplicateSequenceSummary {
+    /// Whether the sequences were compared byte by byte.
+    /// If not, the sequences are only known to have the same 64-bit hash,
+    /// so a group may contain sequences that differ, though this is very unlikely.
+    pub verified: bool,
+    /// The ids of the records of each group of at least two identical sequences.
+    /// The ids of each group are in the order of the input, and the groups are ordered by their first record.
+    pub groups: Vec<Vec<String>>,
+    /// The number of groups.
+    pub group_count: usize,
+    /// The number of records whose sequence is identical to that of an earlier record.
+    pub duplicate_count: usize,
+}
+
+/// Records whose sequences have the same hash, and that are identical if the sequences are verified.
+#[derive(Debug, Clone)]
+struct SequenceGroup {
+    /// The index of the first record of the group in the input, for ordering the groups.
+    first_record: usize,
+    ids: Vec<String>,
+    /// The normalised sequence of the records, if the sequences are verified.
+    seq: Option<Vec<u8>>,
+}
+
+/// Collects the ids of the records by the hashes of their sequences to detect duplicates.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DuplicateSequenceAccumulator {
+    /// The groups of records with each hash.
+    /// If the sequences are verified, the groups of a hash have different sequences,
+    /// otherwise there is a single group per hash.
+    groups: FxHashMap<u64, Vec<SequenceGroup>>,
+    record_count: usize,
+    verify: bool,
+}
+
+impl DuplicateSequenceAccumulator {
+    pub(crate) fn new(verify: bool) -> Self {
+        Self {
+            verify,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn add(&mut self, id: &str, seq: &[u8]) {
+        let normalised = || {
+            seq.iter()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .map(u8::to_ascii_uppercase)
+        };
+        let mut hasher = FxHasher::default();
+        for byte in normalised() {
+            hasher.write_u8(byte);
+        }
+
+        let groups = self.groups.entry(hasher.finish()).or_default();
+        // Without verification, any group with the same hash matches.
+        let group = groups.iter_mut().find(|group| {
+            group
+                .seq
+                .as_ref()
+                .map_or(true, |group_seq| group_seq.iter().copied().eq(normalised()))
+        });
+        if let Some(group) = group {
+            group.ids.push(id.to_owned());
+        } else {
+            groups.push(SequenceGroup {
+                first_record: self.record_count,
+                ids: vec![id.to_owned()],
+                seq: if self.verify {
+                    Some(normalised().collect())
+                } else {
+                    None
+                },
+            });
+        }
+        self.record_count += 1;
+    }
+
+    /// Add all records of `other`, which come after those of `self` in the input.
+    pub(crate) fn extend(&mut self, other: &Self) {
+        for (&hash, other_groups) in &other.groups {
+            let groups = self.groups.entry(hash).or_default();
+            for other_group in other_groups {
+                if let Some(group) = groups.iter_mut().find(|group| group.seq == other_group.seq) {
+                    group.ids.extend_from_slice(&other_group.ids);
+                } else {
+                    groups.push(SequenceGroup {
+                        first_record: self.record_count + other_group.first_record,
+                        ..other_group.clone()
+                    });
+                }
+            }
+        }
+        self.record_count += other.record_count;
+    }
+
+    pub(crate) fn finish(self) -> DuplicateSequenceSummary {
+        let mut groups: Vec<_> = self
+            .groups
+            .into_values()
+            .flatten()
+            .filter(|group| group.ids.len() > 1)
+            .collect();
+        groups.sort_unstable_by_key(|group| group.first_record);
+
+        let groups: Vec<_> = groups.into_iter().map(|group| group.ids).collect();
+        DuplicateSequenceSummary {
+            verified: self.verify,
+            duplicate_count: groups.iter().map(|ids| ids.len() - 1).sum(),
+            group_count: groups.len(),
+            groups,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duplicates::{
+        DuplicateIdSummary, DuplicateSequenceAccumulator, DuplicateSequenceSummary,
+    };
+    use crate::error::FastxStatisticsError;
+    use crate::{basic_statistics, multi_input_statistics_with_callback, StatisticsOptions};
+
+    #[test]
+    fn test_duplicate_ids() {
+        let fasta = b">1\nAC\n>2\nA\n>1\nACGT\n>3\nA\n>1\nA\n";
+        let options = StatisticsOptions {
+            check_duplicate_ids: true,
+            min_length: Some(2),
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(
+            summary.duplicate_ids,
+            Some(DuplicateIdSummary {
+                id_count: 5,
+                unique_id_count: 3,
+                duplicate_id_count: 2,
+            })
+        );
+
+        let strict = StatisticsOptions {
+            strict: true,
+            ..options
+        };
+        assert!(matches!(
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &strict),
+            Err(FastxStatisticsError::DuplicateId(id)) if id == "1"
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_ids_across_inputs() {
+        let options = StatisticsOptions {
+            check_duplicate_ids: true,
+            ..Default::default()
+        };
+        let inputs = vec![
+            (b">1\nA\n>2\nA\n".as_slice(), 10.into()),
+            (b">2\nA\n".as_slice(), 5.into()),
+        ];
+        let summary = multi_input_statistics_with_callback(inputs, &options, |_| Ok(())).unwrap();
+        assert_eq!(
+            summary.inputs[1]
+                .duplicate_ids
+                .as_ref()
+                .unwrap()
+                .duplicate_id_count,
+            0
+        );
+        assert_eq!(
+            summary.combined.duplicate_ids.unwrap().duplicate_id_count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_duplicate_sequences() {
+        let fasta = b">1\nACGT\n>2\nAC\n>3\nac\ngt\n>4\nAC\n>5\nACG\n>6\nacgt\n";
+        for verify in [false, true] {
+            let options = StatisticsOptions {
+                check_duplicate_sequences: true,
+                verify_duplicate_sequences: verify,
+                ..Default::default()
+            };
+            let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+            assert_eq!(
+                summary.duplicate_sequences,
+                Some(DuplicateSequenceSummary {
+                    verified: verify,
+                    groups: vec![
+                        vec!["1".to_owned(), "3".to_owned(), "6".to_owned()],
+                        vec!["2".to_owned(), "4".to_owned()],
+                    ],
+                    group_count: 2,
+                    duplicate_count: 3,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_extend_duplicate_sequences() {
+        for verify in [false, true] {
+            let mut accumulator = DuplicateSequenceAccumulator::new(verify);
+            accumulator.add("1", b"AC");
+            accumulator.add("2", b"GT");
+            let mut other = DuplicateSequenceAccumulator::new(verify);
+            other.add("3", b"TT");
+            other.add("4", b"G\nt");
+            other.add("5", b"tT");
+            other.add("6", b"ac");
+            accumulator.extend(&other);
+            assert_eq!(
+                accumulator.finish().groups,
+                vec![
+                    vec!["1".to_owned(), "6".to_owned()],
+                    vec!["2".to_owned(), "4".to_owned()],
+                    vec!["3".to_owned(), "5".to_owned()],
+                ]
+            );
+        }
+    }
+}
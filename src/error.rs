@@ -0,0 +1,96 @@
+//! The error type of this crate.
+
+use std::io;
+use std::path::PathBuf;
+use std::str::Utf8Error;
+use thiserror::Error;
+
+/// An error that occurs while computing statistics or writing them.
+#[derive(Debug, Error)]
+pub enum FastxStatisticsError {
+    /// Reading or writing failed, where the context names the operation, e.g. `Cannot write BED file`.
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    /// The input is not valid fasta or fastq, where the context names the input, e.g. `Error parsing R1 fastx`.
+    #[error("{context}: {source}")]
+    Parse {
+        context: String,
+        #[source]
+        source: seq_io::fastx::Error,
+    },
+    /// A record id is not valid utf-8.
+    #[error("Record id is not utf-8 encoded: {0}")]
+    NonUtf8Id(#[from] Utf8Error),
+    /// An input path does not refer to a file.
+    #[error("Not a file: {0:?}")]
+    NotAFile(PathBuf),
+    /// A record id occurs more than once in strict mode.
+    #[error("Duplicate record id {0:?}")]
+    DuplicateId(String),
+    /// The record with this id has an empty sequence in strict mode.
+    #[error("Record {0:?} has an empty sequence")]
+    EmptySequence(String),
+    /// The fastq record with this id has a quality string whose length differs from the length of its sequence in strict mode.
+    #[error("Record {id:?} has a sequence of length {sequence_length}, but a quality string of length {quality_length}")]
+    UnequalQualityLength {
+        id: String,
+        sequence_length: usize,
+        quality_length: usize,
+    },
+    /// The record with this id contains a character that is not an IUPAC nucleotide code or a gap
+    /// at the given 1-based position of its sequence, in strict mode.
+    #[error("Record {id:?} contains the character {:?} at position {position}, which is not an IUPAC nucleotide code", char::from(*.character))]
+    InvalidCharacter {
+        id: String,
+        character: u8,
+        position: usize,
+    },
+    /// A template for the output is malformed.
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    /// A template for the output refers to a metric that does not exist.
+    #[error("Unknown metric {{{0}}} in template")]
+    UnknownMetric(String),
+    /// A threshold for a metric is violated.
+    /// For minimum thresholds the actual value is below the threshold, and for maximum thresholds it is above it.
+    ///
+    /// The values are `f64` rather than `usize`, since some thresholds are fractions, like that of `--assert-max-n-fraction`.
+    /// Counts and lengths are represented exactly up to 2^53.
+    #[error("Assertion failed: the {metric} is {actual}, violating the threshold {threshold}")]
+    AssertionFailed {
+        metric: String,
+        threshold: f64,
+        actual: f64,
+    },
+    /// Any other error, described by its message, such as invalid arguments or inputs that cannot be processed.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl FastxStatisticsError {
+    /// A [`FastxStatisticsError::Io`] error with the given context.
+    pub fn io(context: impl Into<String>, source: impl Into<io::Error>) -> Self {
+        Self::Io {
+            context: context.into(),
+            source: source.into(),
+        }
+    }
+
+    /// A [`FastxStatisticsError::Parse`] error with the given context.
+    pub fn parse(context: impl Into<String>, source: seq_io::fastx::Error) -> Self {
+        Self::Parse {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+impl From<String> for FastxStatisticsError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
@@ -0,0 +1,148 @@
+//! Write a samtools-compatible index of a fasta file.
+
+use crate::error::FastxStatisticsError;
+use crate::AcceptedRecord;
+use seq_io::BaseRecord;
+use std::io::{self, Write};
+
+/// The position and line structure of the sequence of a record in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceLayout {
+    /// The byte offset of the first base in the decompressed input.
+    pub offset: u64,
+    /// The number of bases per line, `None` if the lines differ in length, except for a shorter last line.
+    pub line_bases: Option<usize>,
+    /// The number of bytes per line, including the line terminator.
+    pub line_bytes: usize,
+}
+
+impl SequenceLayout {
+    /// Compute the layout of the sequence of the given record, relative to the start of the record.
+    pub(crate) fn new(record: &impl BaseRecord) -> Self {
+        let head = record.head();
+        let seq = record.seq();
+        // The reader strips the line terminators from the header and from the end of the sequence,
+        // but both are slices of the same buffer, so the header line length can be computed from their distance.
+        // Empty sequences may not point into the buffer, but they are never indexed anyway.
+        let head_line_bytes = if seq.is_empty() {
+            head.len() + 1
+        } else {
+            seq.as_ptr() as usize - head.as_ptr() as usize
+        };
+        let head_terminator_bytes = head_line_bytes - head.len();
+
+        let mut lines = seq.split(|&byte| byte == b'\n');
+        // Split always returns at least one line.
+        let first_line = lines.next().unwrap();
+        let first_line_bases = first_line.strip_suffix(b"\r").unwrap_or(first_line).len();
+        let mut line_bases = Some(first_line_bases);
+        let mut is_last = false;
+        for line in lines {
+            let bases = line.strip_suffix(b"\r").unwrap_or(line).len();
+            if is_last || bases > first_line_bases {
+                line_bases = None;
+            }
+            // Only the last line may be shorter.
+            is_last = bases < first_line_bases;
+        }
+
+        Self {
+            // Include the `>` at the start of the header line.
+            offset: 1 + head_line_bytes as u64,
+            line_bases,
+            // For single-line sequences, assume the same line terminator as for the header.
+            line_bytes: if first_line.len() == seq.len() {
+                first_line_bases + head_terminator_bytes
+            } else {
+                first_line.len() + 1
+            },
+        }
+    }
+
+    /// Shift the layout by the given byte offset of the record.
+    pub(crate) fn at(self, record_offset: u64) -> Self {
+        Self {
+            offset: self.offset + record_offset,
+            ..self
+        }
+    }
+}
+
+/// Writes the lines of a `.fai` index while the input is being read.
+///
+/// Each line has the columns name, length, byte offset of the sequence, bases per line and bytes per line.
+pub struct FaiWriter<W: Write> {
+    output: W,
+}
+
+impl<W: Write> FaiWriter<W> {
+    pub fn new(output: W) -> Self {
+        Self { output }
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        if record.qual.is_some() {
+            return Err(FastxStatisticsError::Other(format!(
+                "Cannot index record {}, since only fasta files can be indexed",
+                record.id
+            )));
+        }
+        let layout = record.layout;
+        let line_bases = layout.line_bases.ok_or_else(|| {
+            FastxStatisticsError::Other(format!(
+                "Cannot index record {}, since its lines have different lengths",
+                record.id
+            ))
+        })?;
+
+        writeln!(
+            self.output,
+            "{}\t{}\t{}\t{line_bases}\t{}",
+            record.id, record.statistics.len, layout.offset, layout.line_bytes
+        )
+        .map_err(write_error)
+    }
+
+    /// Flush the output.
+    pub fn finish(mut self) -> Result<(), FastxStatisticsError> {
+        self.output.flush().map_err(write_error)
+    }
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write fasta index", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::FastxStatisticsError;
+    use crate::fai::FaiWriter;
+    use crate::{basic_statistics_with_callback, StatisticsOptions};
+
+    fn index(input: &[u8]) -> Result<String, FastxStatisticsError> {
+        let mut output = Vec::new();
+        let mut writer = FaiWriter::new(&mut output);
+        basic_statistics_with_callback(
+            input,
+            input.len() as u64,
+            &StatisticsOptions::default(),
+            |record| writer.write_record(record),
+        )?;
+        writer.finish().unwrap();
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_fai() {
+        assert_eq!(
+            index(b">1 desc\nACGT\nAC\n>2\nACG\n").unwrap(),
+            "1\t6\t8\t4\t5\n2\t3\t19\t3\t4\n"
+        );
+        assert_eq!(
+            index(b">1\r\nACG\r\nA\r\n>2\r\nAC\r\n").unwrap(),
+            "1\t4\t4\t3\t5\n2\t2\t16\t2\t4\n"
+        );
+        assert!(index(b">1\nAC\nACGT\n").is_err());
+        assert!(index(b"@1\nAC\n+\nII\n").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Select the records that pass the filters, or those that fail them.
+
+use crate::error::FastxStatisticsError;
+use crate::format::SequenceReader;
+use crate::input::InputProgress;
+use crate::{
+    create_progress_bar, id_exclusion, record_id, sequence_exclusion, AcceptedRecord,
+    ExcludedCounts, SequenceStatistics, StatisticsOptions,
+};
+use log::info;
+use seq_io::BaseRecord;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The number of records that passed and failed the filters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterSummary {
+    pub passed_count: usize,
+    pub failed_count: usize,
+    /// The number of failed records per filter.
+    pub excluded: ExcludedCounts,
+}
+
+/// Read a file with one record id per line, e.g. for [`StatisticsOptions::exclude_ids`].
+/// Surrounding whitespace is removed, and empty lines and lines starting with `#` are ignored.
+pub fn read_ids(path: &Path) -> Result<HashSet<String>, FastxStatisticsError> {
+    let ids = fs::read_to_string(path)
+        .map_err(|err| FastxStatisticsError::io(format!("Cannot read id file {path:?}"), err))?;
+    Ok(ids
+        .lines()
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && !id.starts_with('#'))
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Call `record_callback` for each record of the input that passes the id and sequence filters of the options,
+/// or for each record that fails them if `invert` is set.
+///
+/// Other options such as `skip`, `limit` and `subsample` are ignored.
+pub fn filter_records(
+    input: impl Read,
+    progress: InputProgress,
+    options: &StatisticsOptions,
+    invert: bool,
+    mut record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<FilterSummary, FastxStatisticsError> {
+    info!("Filtering fasta or fastq file...");
+    let options = &*options.with_lowercase_ids()?;
+    let pb = create_progress_bar([&progress].into_iter(), options);
+
+    let mut reader = SequenceReader::new(BufReader::new(input), options.format);
+    let mut last_update = Instant::now();
+    let mut summary = FilterSummary {
+        passed_count: 0,
+        failed_count: 0,
+        excluded: ExcludedCounts::new(options),
+    };
+
+    while let Some(record) = reader.next_record(true) {
+        let (record, layout) =
+            record.map_err(|err| FastxStatisticsError::parse("Error parsing fastx", err))?;
+        let id = record_id(&record)?;
+        let statistics = SequenceStatistics::new(record.seq());
+        // The sequence filters are only evaluated if the id and description filters pass, like for the statistics.
+        let exclusion = id_exclusion(id, record.head(), options)
+            .or_else(|| sequence_exclusion(&statistics, options));
+        if let Some(exclusion) = exclusion {
+            summary.excluded.count(exclusion);
+            summary.failed_count += 1;
+        } else {
+            summary.passed_count += 1;
+        }
+
+        if exclusion.is_some() == invert {
+            record_callback(AcceptedRecord {
+                id,
+                head: record.head(),
+                seq: record.seq(),
+                qual: record.opt_qual(),
+                layout,
+                statistics: &statistics,
+            })?;
+        }
+
+        let now = Instant::now();
+        if last_update + Duration::from_millis(200) <= now {
+            pb.set_position(progress.position(reader.position().byte()));
+            last_update = now;
+        }
+    }
+
+    pb.finish_and_clear();
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::filter_records;
+    use crate::StatisticsOptions;
+
+    #[test]
+    fn test_filter_records() {
+        let fasta: &[u8] = b">1\nACGT\n>2\nA\n>3\nNNNA\n>4\nACGTAC\n";
+        let options = StatisticsOptions {
+            filter_ids: vec!["4".to_owned()],
+            min_length: Some(2),
+            max_n_fraction: Some(0.5),
+            ..Default::default()
+        };
+        let filter = |invert| {
+            let mut ids = Vec::new();
+            let summary = filter_records(
+                fasta,
+                (fasta.len() as u64).into(),
+                &options,
+                invert,
+                |record| {
+                    ids.push(record.id.to_owned());
+                    Ok(())
+                },
+            )
+            .unwrap();
+            (summary, ids)
+        };
+
+        let (summary, ids) = filter(false);
+        assert_eq!(ids, ["1"]);
+        assert_eq!(summary.passed_count, 1);
+        assert_eq!(summary.failed_count, 3);
+        assert_eq!(summary.excluded.by_id, Some(1));
+        assert_eq!(summary.excluded.too_short, Some(1));
+        assert_eq!(summary.excluded.by_n_fraction, Some(1));
+        assert_eq!(filter(true).1, ["2", "3", "4"]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Read fasta or fastq records, either detecting the format from the first record or in a format given by the user.
+
+use crate::fai::SequenceLayout;
+use log::warn;
+use seq_io::fastx::OwnedRecord;
+use seq_io::{core, fasta, fastq, fastx, BaseRecord, Position};
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read};
+
+/// The format of a sequence input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceFormat {
+    Fasta,
+    /// Fastq with single-line sequences and quality strings.
+    Fastq,
+}
+
+impl SequenceFormat {
+    /// The first byte of the header line of each record.
+    fn start_byte(self) -> u8 {
+        match self {
+            Self::Fasta => b'>',
+            Self::Fastq => b'@',
+        }
+    }
+}
+
+impl Display for SequenceFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fasta => write!(f, "fasta"),
+            Self::Fastq => write!(f, "fastq"),
+        }
+    }
+}
+
+/// A reader of fasta or fastq records.
+pub(crate) enum SequenceReader<R: Read> {
+    Detect(fastx::Reader<R>),
+    Fasta(fasta::Reader<R>),
+    Fastq(fastq::Reader<R>),
+    /// The input ended before the first record, or reading it failed.
+    /// The error is returned by the first call to [`SequenceReader::next_record`].
+    Exhausted {
+        error: Option<fastx::Error>,
+        position: Position,
+    },
+}
+
+impl<R: Read> SequenceReader<R> {
+    /// Create a reader that detects the format from the first record, or that expects the given format.
+    /// With a given format, the lines before the first record of that format are skipped with a warning,
+    /// such that e.g. comment lines at the start of the input do not prevent parsing it.
+    pub(crate) fn new(input: R, format: Option<SequenceFormat>) -> Self {
+        let format = match format {
+            Some(format) => format,
+            None => return Self::Detect(fastx::Reader::new(input)),
+        };
+
+        let mut reader = core::BufReader::new(input);
+        let mut line_count = 0;
+        let mut is_blank = true;
+        let error = match skip_to_record_start(
+            &mut reader,
+            format.start_byte(),
+            &mut line_count,
+            &mut is_blank,
+        ) {
+            Ok(Some(offset)) => {
+                if !is_blank {
+                    warn!("Skipped {line_count} lines before the first {format} record");
+                }
+                return match format {
+                    SequenceFormat::Fasta => {
+                        Self::Fasta(fasta::Reader::from_buf_reader(reader, offset, line_count))
+                    }
+                    SequenceFormat::Fastq => {
+                        Self::Fastq(fastq::Reader::from_buf_reader(reader, offset, line_count))
+                    }
+                };
+            }
+            Ok(None) if is_blank => None,
+            Ok(None) => Some(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("found no {format} record"),
+            )),
+            Err(err) => Some(err),
+        };
+        let mut position = Position::new();
+        position.set_byte(reader.file_offset()).set_line(line_count);
+        Self::Exhausted {
+            error: error.map(Into::into),
+            position,
+        }
+    }
+
+    /// Read the next record, together with the layout of its sequence in the input.
+    /// If `check_lengths` is false, fastq records whose quality string differs in length from the sequence are not an error.
+    pub(crate) fn next_record(
+        &mut self,
+        check_lengths: bool,
+    ) -> Option<Result<(OwnedRecord, SequenceLayout), fastx::Error>> {
+        // Fastq quality strings are single lines, so they are parsed correctly without the check.
+        let (record, layout) = match self {
+            Self::Detect(reader) => {
+                let record = if check_lengths {
+                    reader.next()?
+                } else {
+                    reader.next_unchecked_len()?
+                };
+                match record {
+                    Ok(record) => (record.to_owned_record(), SequenceLayout::new(&record)),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+            Self::Fasta(reader) => match reader.next()? {
+                Ok(record) => (
+                    OwnedRecord {
+                        head: record.head().to_owned(),
+                        seq: record.seq().to_owned(),
+                        qual: None,
+                    },
+                    SequenceLayout::new(&record),
+                ),
+                Err(err) => return Some(Err(err.into())),
+            },
+            Self::Fastq(reader) => {
+                let record = if check_lengths {
+                    reader.next()?
+                } else {
+                    reader.next_unchecked_len()?
+                };
+                match record {
+                    Ok(record) => (
+                        OwnedRecord {
+                            head: record.head().to_owned(),
+                            seq: record.seq().to_owned(),
+                            qual: record.opt_qual().map(<[u8]>::to_owned),
+                        },
+                        SequenceLayout::new(&record),
+                    ),
+                    Err(err) => return Some(Err(err.into())),
+                }
+            }
+            Self::Exhausted { error, .. } => return error.take().map(Err),
+        };
+        Some(Ok((record, layout.at(self.position().byte()))))
+    }
+
+    /// The position of the last record that was read.
+    pub(crate) fn position(&self) -> Position {
+        match self {
+            Self::Detect(reader) => reader.position(),
+            Self::Fasta(reader) => reader.position(),
+            Self::Fastq(reader) => reader.position(),
+            Self::Exhausted { position, .. } => position.clone(),
+        }
+    }
+}
+
+/// Advance the reader to the first line that starts with `start_byte`, and return the offset of that line
+/// in the buffer of the reader, or `None` if there is no such line.
+/// The lines before it are counted in `line_count`, and `is_blank` is cleared if any of them is not empty.
+fn skip_to_record_start<R: Read>(
+    reader: &mut core::BufReader<R>,
+    start_byte: u8,
+    line_count: &mut u64,
+    is_blank: &mut bool,
+) -> io::Result<Option<usize>> {
+    let mut offset = 0;
+    let mut is_line_start = true;
+    loop {
+        if offset == reader.buffer().len() {
+            reader.make_room(offset);
+            offset = 0;
+            reader.fill_buf()?;
+            if reader.buffer().is_empty() {
+                return Ok(None);
+            }
+        }
+
+        let buffer = &reader.buffer()[offset..];
+        if is_line_start && buffer[0] == start_byte {
+            return Ok(Some(offset));
+        }
+        // The line may continue after the buffer.
+        let line_length = buffer
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .unwrap_or(buffer.len());
+        *is_blank &= buffer[..line_length].iter().all(|&byte| byte == b'\r');
+        is_line_start = line_length < buffer.len();
+        if is_line_start {
+            offset += line_length + 1;
+            *line_count += 1;
+        } else {
+            offset += line_length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{SequenceFormat, SequenceReader};
+    use crate::{basic_statistics, StatisticsOptions};
+    use seq_io::BaseRecord;
+
+    #[test]
+    fn test_forced_format() {
+        // The comment line makes the format detection fail.
+        let fastq = b"# comment\n\n@1\nACGT\n+\nIIII\n@2\nAC\n+\n!!\n";
+        assert!(
+            basic_statistics(fastq.as_slice(), fastq.len() as u64, &Default::default()).is_err()
+        );
+
+        let options = StatisticsOptions {
+            format: Some(SequenceFormat::Fastq),
+            ..Default::default()
+        };
+        let summary = basic_statistics(fastq.as_slice(), fastq.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.quality.unwrap().q30_count, 4);
+
+        let options = StatisticsOptions {
+            format: Some(SequenceFormat::Fasta),
+            ..Default::default()
+        };
+        assert!(basic_statistics(fastq.as_slice(), fastq.len() as u64, &options).is_err());
+        assert_eq!(
+            basic_statistics(b"\n\n".as_slice(), 2, &options)
+                .unwrap()
+                .record_count,
+            0
+        );
+    }
+
+    #[test]
+    fn test_skipped_lines() {
+        // The comment lines are longer than the buffer of the reader, and the first one contains a record start in its middle.
+        let mut fasta = vec![b'#'; 100_000];
+        fasta.extend_from_slice(b">x\n");
+        fasta.extend(vec![b'#'; 100_000]);
+        fasta.extend_from_slice(b"\n>1\nAC\nG\n>2\nT\n");
+        let mut reader = SequenceReader::new(fasta.as_slice(), Some(SequenceFormat::Fasta));
+        let (record, layout) = reader.next_record(true).unwrap().unwrap();
+        assert_eq!(record.id(), Ok("1"));
+        assert_eq!(record.seq(), b"AC\nG");
+        assert_eq!(layout.offset, 200_007);
+        assert_eq!(reader.position().line(), 2);
+        let (record, _) = reader.next_record(true).unwrap().unwrap();
+        assert_eq!(record.id(), Ok("2"));
+        assert_eq!(reader.position().line(), 5);
+        assert!(reader.next_record(true).is_none());
+    }
+}
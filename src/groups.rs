@@ -0,0 +1,106 @@
+//! Statistics about groups of sequences that share an id prefix, such as the contigs of a chromosome.
+
+use crate::{nx, total_length};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Statistics about the sequences whose ids share the same prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GroupSummary {
+    /// The part of the ids before the first separator, or the whole id if it does not contain the separator.
+    pub prefix: String,
+    pub record_count: usize,
+    pub total_length: u64,
+    pub n50: usize,
+}
+
+/// Collects the sequence lengths per id prefix.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GroupAccumulator {
+    separator: String,
+    /// The groups in the order of their first sequence.
+    groups: Vec<(String, Vec<usize>)>,
+    indices: HashMap<String, usize>,
+}
+
+impl GroupAccumulator {
+    pub(crate) fn new(separator: String) -> Self {
+        Self {
+            separator,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn add(&mut self, id: &str, len: usize) {
+        let prefix = id
+            .split_once(self.separator.as_str())
+            .map_or(id, |(prefix, _)| prefix);
+        self.add_lengths(prefix, &[len]);
+    }
+
+    fn add_lengths(&mut self, prefix: &str, lengths: &[usize]) {
+        let index = if let Some(&index) = self.indices.get(prefix) {
+            index
+        } else {
+            self.indices.insert(prefix.to_owned(), self.groups.len());
+            self.groups.push((prefix.to_owned(), Vec::new()));
+            self.groups.len() - 1
+        };
+        self.groups[index].1.extend_from_slice(lengths);
+    }
+
+    /// Add all sequences accumulated in `other` to `self`.
+    pub(crate) fn extend(&mut self, other: &Self) {
+        for (prefix, lengths) in &other.groups {
+            self.add_lengths(prefix, lengths);
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<GroupSummary> {
+        self.groups
+            .into_iter()
+            .map(|(prefix, mut lengths)| {
+                lengths.sort_unstable_by(|a, b| b.cmp(a));
+                let total_length = total_length(&lengths);
+                GroupSummary {
+                    prefix,
+                    record_count: lengths.len(),
+                    total_length,
+                    n50: nx(&lengths, total_length, |total| total / 2),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::groups::GroupSummary;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_groups() {
+        let fasta = b">chr2_1\nACGT\n>chr1_1\nAC\n>chr2_2\nA\n>chr2_3\nACG\n>unplaced\nACGTACGT\n";
+        let options = StatisticsOptions {
+            group_separator: Some("_".to_owned()),
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        let group = |prefix: &str, record_count, total_length, n50| GroupSummary {
+            prefix: prefix.to_owned(),
+            record_count,
+            total_length,
+            n50,
+        };
+        assert_eq!(
+            summary.groups.unwrap(),
+            [
+                group("chr2", 3, 8, 4),
+                group("chr1", 1, 2, 2),
+                group("unplaced", 1, 8, 8),
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Histograms of sequence lengths.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A histogram of sequence lengths with bins covering the range from the minimum to the maximum length.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LengthHistogram {
+    pub bins: Vec<HistogramBin>,
+}
+
+/// A bin of a [`LengthHistogram`], counting the lengths in `[start, end)`.
+/// The last bin additionally includes its end.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+impl LengthHistogram {
+    /// Compute a histogram with `bin_count` bins of the given non-empty lengths, which must be sorted in descending order.
+    /// If `log_scale` is set, then the bin edges are spaced logarithmically, with lengths of zero counted in the first bin.
+    pub fn new(sorted_lengths: &[usize], bin_count: usize, log_scale: bool) -> Self {
+        debug_assert!(sorted_lengths.windows(2).all(|w| w[0] >= w[1]));
+        assert!(bin_count > 0, "the histogram needs at least one bin");
+
+        let scale = |len: f64| if log_scale { len.max(1.0).ln() } else { len };
+        let unscale = |value: f64| if log_scale { value.exp() } else { value };
+        let min_len = *sorted_lengths.last().unwrap() as f64;
+        let max_len = *sorted_lengths.first().unwrap() as f64;
+        let min = scale(min_len);
+        let max = scale(max_len);
+        let width = (max - min) / bin_count as f64;
+
+        let mut bins: Vec<_> = (0..bin_count)
+            .map(|index| HistogramBin {
+                start: unscale(min + width * index as f64),
+                end: unscale(min + width * (index + 1) as f64),
+                count: 0,
+            })
+            .collect();
+        // Avoid rounding errors in the outer edges.
+        bins.first_mut().unwrap().start = unscale(min).max(min_len);
+        bins.last_mut().unwrap().end = max_len;
+
+        for len in sorted_lengths.iter().copied() {
+            let index = if width > 0.0 {
+                (((scale(len as f64) - min) / width) as usize).min(bin_count - 1)
+            } else {
+                0
+            };
+            bins[index].count += 1;
+        }
+
+        Self { bins }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::histogram::LengthHistogram;
+
+    #[test]
+    fn test_linear() {
+        let histogram = LengthHistogram::new(&[20, 15, 14, 10], 2, false);
+        assert_eq!(histogram.bins.len(), 2);
+        assert_eq!(histogram.bins[0].start, 10.0);
+        assert_eq!(histogram.bins[0].end, 15.0);
+        assert_eq!(histogram.bins[0].count, 2);
+        assert_eq!(histogram.bins[1].end, 20.0);
+        assert_eq!(histogram.bins[1].count, 2);
+    }
+
+    #[test]
+    fn test_log_scale() {
+        let histogram = LengthHistogram::new(&[1000, 999, 150, 20, 0], 3, true);
+        assert!((histogram.bins[0].end - 10.0).abs() < 1e-9);
+        assert!((histogram.bins[2].start - 100.0).abs() < 1e-9);
+        let counts: Vec<_> = histogram.bins.iter().map(|bin| bin.count).collect();
+        assert_eq!(counts, vec![1, 1, 3]);
+    }
+
+    #[test]
+    fn test_single_length() {
+        let histogram = LengthHistogram::new(&[5, 5], 3, false);
+        let counts: Vec<_> = histogram.bins.iter().map(|bin| bin.count).collect();
+        assert_eq!(counts, vec![2, 0, 0]);
+    }
+}
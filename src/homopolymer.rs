@@ -0,0 +1,85 @@
+//! Statistics about the lengths of homopolymer runs.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Statistics about the lengths of the homopolymer runs of all sequences, excluding runs of Ns.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HomopolymerSummary {
+    pub run_count: usize,
+    pub max_run_length: usize,
+    pub mean_run_length: f64,
+    /// The N50 of the run lengths, i.e. half of the bases are in runs of at least this length.
+    pub run_length_n50: usize,
+    /// The number of runs of each length, indexed by the run length.
+    pub run_length_counts: Vec<usize>,
+}
+
+impl HomopolymerSummary {
+    /// Compute the statistics from the number of runs of each length, indexed by the run length.
+    /// Returns `None` if there are no runs.
+    pub fn new(mut run_length_counts: Vec<usize>) -> Option<Self> {
+        while run_length_counts.last() == Some(&0) {
+            run_length_counts.pop();
+        }
+        if run_length_counts.is_empty() {
+            return None;
+        }
+
+        let run_count: usize = run_length_counts.iter().sum();
+        let base_count: usize = run_length_counts
+            .iter()
+            .enumerate()
+            .map(|(run_length, count)| run_length * count)
+            .sum();
+
+        let mut covered_bases = 0;
+        let mut run_length_n50 = 0;
+        for (run_length, count) in run_length_counts.iter().enumerate().rev() {
+            covered_bases += run_length * count;
+            if covered_bases >= base_count / 2 {
+                run_length_n50 = run_length;
+                break;
+            }
+        }
+
+        Some(Self {
+            run_count,
+            max_run_length: run_length_counts.len() - 1,
+            mean_run_length: base_count as f64 / run_count as f64,
+            run_length_n50,
+            run_length_counts,
+        })
+    }
+}
+
+/// Add the counts of `other` to `counts`, where both are indexed by the run length.
+pub fn add_run_length_counts(counts: &mut Vec<usize>, other: &[usize]) {
+    if counts.len() < other.len() {
+        counts.resize(other.len(), 0);
+    }
+    for (count, other_count) in counts.iter_mut().zip(other) {
+        *count += other_count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::homopolymer::HomopolymerSummary;
+    use crate::SequenceStatistics;
+
+    #[test]
+    fn test_homopolymer_summary() {
+        let statistics = SequenceStatistics::new(b"AAAACaGGNNNNNNTt\nT");
+        assert_eq!(statistics.homopolymer_run_counts, vec![0, 2, 1, 1, 1]);
+
+        let summary = HomopolymerSummary::new(statistics.homopolymer_run_counts).unwrap();
+        assert_eq!(summary.run_count, 5);
+        assert_eq!(summary.max_run_length, 4);
+        assert_eq!(summary.mean_run_length, 11.0 / 5.0);
+        assert_eq!(summary.run_length_n50, 3);
+
+        assert_eq!(HomopolymerSummary::new(vec![0, 0]), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! Open fasta or fastq input files, decompressing them if necessary.
+//!
+//! Gzip (`.gz`), bzip2 (`.bz2`) and zstd (`.zst`) compression is supported.
+//! The compression format is detected from the file extension, or from the magic bytes at the start of the file,
+//! unless it is given explicitly.
+//! The path `-` refers to the standard input.
+
+use crate::error::FastxStatisticsError;
+use bzip2::bufread::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use zstd::zstd_safe;
+
+/// The compression format of an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+
+impl Compression {
+    /// Detect the compression format from the extension of the given path,
+    /// falling back to the magic bytes at the start of the file.
+    pub fn detect(path: &Path, head: &[u8]) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("bz2") => Self::Bzip2,
+            Some("zst") => Self::Zstd,
+            _ => {
+                if head.starts_with(GZIP_MAGIC) {
+                    Self::Gzip
+                } else if head.starts_with(BZIP2_MAGIC) {
+                    Self::Bzip2
+                } else if head.starts_with(ZSTD_MAGIC) {
+                    Self::Zstd
+                } else {
+                    Self::None
+                }
+            }
+        }
+    }
+
+    /// Wrap the given reader such that it outputs the decompressed data.
+    pub fn decompress<'a>(
+        self,
+        reader: impl BufRead + Send + 'a,
+    ) -> io::Result<Box<dyn Read + Send + 'a>> {
+        Ok(match self {
+            Self::None => Box::new(reader),
+            // Use the multi-member decoder, since e.g. bgzip writes files consisting of many gzip members.
+            Self::Gzip => Box::new(MultiGzDecoder::new(reader)),
+            // Likewise, pbzip2 writes files consisting of many bzip2 streams.
+            Self::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+            Self::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+        })
+    }
+
+    /// The decompressed size of the file starting with `head`, if it is stored in the file.
+    ///
+    /// Only zstd stores the decompressed size, and only for the first frame.
+    /// Hence, if a zstd file consists of multiple frames, the reported size is too small.
+    pub fn decompressed_size(self, head: &[u8]) -> Option<u64> {
+        match self {
+            Self::Zstd => match zstd_safe::get_frame_content_size(head) {
+                zstd_safe::CONTENTSIZE_UNKNOWN | zstd_safe::CONTENTSIZE_ERROR => None,
+                size => Some(size),
+            },
+            Self::None | Self::Gzip | Self::Bzip2 => None,
+        }
+    }
+}
+
+/// The progress of reading an input, as displayed by the progress bar.
+///
+/// A plain `u64` converts to the progress of an uncompressed input of that length.
+#[derive(Debug, Clone, Default)]
+pub struct InputProgress {
+    /// The total length of the input in bytes.
+    pub total: u64,
+    /// The number of bytes read from the underlying file, if it differs from the number of parsed bytes.
+    bytes_read: Option<Arc<AtomicU64>>,
+}
+
+impl InputProgress {
+    /// The number of bytes processed so far, given the number of bytes parsed by the fastx reader.
+    pub fn position(&self, parsed_bytes: u64) -> u64 {
+        if let Some(bytes_read) = &self.bytes_read {
+            bytes_read.load(Ordering::Relaxed)
+        } else {
+            parsed_bytes
+        }
+    }
+}
+
+impl From<u64> for InputProgress {
+    fn from(total: u64) -> Self {
+        Self {
+            total,
+            bytes_read: None,
+        }
+    }
+}
+
+/// An opened input file.
+pub struct Input {
+    /// The decompressed content of the file.
+    pub reader: Box<dyn Read + Send>,
+    pub compression: Compression,
+    pub progress: InputProgress,
+}
+
+/// Returns true if the given input path refers to the standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// The extensions that are removed from input file names to derive the sample names, in the order in which they are removed.
+const COMPRESSION_EXTENSIONS: &[&str] = &["gz", "bz2", "zst"];
+const FASTX_EXTENSIONS: &[&str] = &["fa", "fasta", "fna", "fas", "fq", "fastq"];
+
+/// Derive the name of a sample or assembly from the path of its input file,
+/// which is its file name without compression and fasta or fastq extensions.
+pub fn sample_name(path: &Path) -> String {
+    if is_stdin(path) {
+        return "stdin".to_owned();
+    }
+
+    let mut name = path
+        .file_name()
+        .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy())
+        .into_owned();
+    for extensions in [COMPRESSION_EXTENSIONS, FASTX_EXTENSIONS] {
+        if let Some((stem, extension)) = name.rsplit_once('.') {
+            if !stem.is_empty() && extensions.contains(&extension.to_ascii_lowercase().as_str()) {
+                name.truncate(stem.len());
+            }
+        }
+    }
+    name
+}
+
+/// Open the given fasta or fastq file, decompressing it transparently if it is compressed.
+///
+/// If `compression` is `None`, then it is detected with [`Compression::detect`].
+/// If the path is `-`, then the standard input is read instead.
+/// Its length is unknown, hence the total of the progress is zero.
+pub fn open_input(
+    path: &Path,
+    compression: Option<Compression>,
+) -> Result<Input, FastxStatisticsError> {
+    let (file, total): (Box<dyn Read + Send>, _) = if is_stdin(path) {
+        (Box::new(io::stdin()), 0)
+    } else {
+        let file = File::open(path)
+            .map_err(|err| FastxStatisticsError::io("Cannot open input file", err))?;
+        let total = file
+            .metadata()
+            .map_err(|err| FastxStatisticsError::io("Cannot read file metadata", err))?
+            .len();
+        (Box::new(file), total)
+    };
+
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let mut reader = BufReader::new(CountingReader {
+        inner: file,
+        bytes_read: bytes_read.clone(),
+    });
+    let head = reader
+        .fill_buf()
+        .map_err(|err| FastxStatisticsError::io("Cannot read input file", err))?;
+    let compression = compression.unwrap_or_else(|| Compression::detect(path, head));
+    // If the decompressed size is known, then the progress is measured in decompressed bytes,
+    // which is more accurate since the compression ratio may vary over the file.
+    let progress = if let Some(decompressed_size) = compression.decompressed_size(head) {
+        InputProgress::from(decompressed_size)
+    } else {
+        InputProgress {
+            total,
+            bytes_read: Some(bytes_read),
+        }
+    };
+
+    Ok(Input {
+        reader: compression
+            .decompress(reader)
+            .map_err(|err| FastxStatisticsError::io("Cannot decompress input file", err))?,
+        compression,
+        progress,
+    })
+}
+
+/// Counts the bytes read from the inner reader.
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(count as u64, Ordering::Relaxed);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{sample_name, Compression};
+    use crate::tests::FASTA;
+    use crate::{basic_statistics, StatisticsOptions};
+    use bzip2::write::BzEncoder;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use std::path::Path;
+
+    /// Check that the compression is detected from the extension and the magic bytes,
+    /// and that the statistics of the decompressed input match those of the uncompressed input.
+    fn check_compressed(compressed: &[u8], extension: &str, expected: Compression) {
+        assert_eq!(
+            Compression::detect(Path::new(&format!("input.fa.{extension}")), b""),
+            expected
+        );
+        let compression = Compression::detect(Path::new("input.fa"), compressed);
+        assert_eq!(compression, expected);
+
+        let options = StatisticsOptions::default();
+        assert_eq!(
+            basic_statistics(
+                compression.decompress(compressed).unwrap(),
+                compressed.len() as u64,
+                &options
+            )
+            .unwrap(),
+            basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        assert_eq!(
+            Compression::detect(Path::new("input.fa"), FASTA),
+            Compression::None
+        );
+    }
+
+    #[test]
+    fn test_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(FASTA).unwrap();
+        check_compressed(&encoder.finish().unwrap(), "gz", Compression::Gzip);
+    }
+
+    #[test]
+    fn test_bzip2() {
+        let mut encoder = BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(FASTA).unwrap();
+        check_compressed(&encoder.finish().unwrap(), "bz2", Compression::Bzip2);
+    }
+
+    #[test]
+    fn test_zstd() {
+        let compressed = zstd::bulk::compress(FASTA, 0).unwrap();
+        check_compressed(&compressed, "zst", Compression::Zstd);
+        assert_eq!(
+            Compression::Zstd.decompressed_size(&compressed),
+            Some(FASTA.len() as u64)
+        );
+    }
+
+    #[test]
+    fn test_sample_name() {
+        assert_eq!(sample_name(Path::new("data/reads.fastq.gz")), "reads");
+        assert_eq!(sample_name(Path::new("assembly.FA")), "assembly");
+        assert_eq!(sample_name(Path::new("sample.1.txt")), "sample.1.txt");
+        assert_eq!(sample_name(Path::new(".fa")), ".fa");
+        assert_eq!(sample_name(Path::new("-")), "stdin");
+    }
+}
@@ -0,0 +1,164 @@
+//! Count the distinct k-mers of the sequences, where the fraction of k-mers that occur only once measures
+//! the complexity of the sequences.
+
+use log::warn;
+use rustc_hash::FxHashMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// The maximum k, such that a k-mer fits into a `u64` with two bits per base.
+pub const MAX_K: usize = 32;
+
+/// If the map of the k-mer counts grows larger than this many bytes, a warning is logged.
+const LARGE_MAP_BYTES: usize = 1 << 30;
+
+/// Statistics about the canonical k-mers of all sequences.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KmerSummary {
+    pub k: usize,
+    /// The number of k-mers in the sequences, counting each occurrence.
+    pub kmer_count: u64,
+    /// The number of distinct canonical k-mers.
+    pub distinct_count: u64,
+    /// The number of canonical k-mers that occur exactly once.
+    pub unique_count: u64,
+    /// The percentage of the distinct k-mers that occur exactly once.
+    pub unique_percent: f64,
+}
+
+/// Counts the occurrences of each canonical k-mer, i.e. the smaller of a k-mer and its reverse complement
+/// in the two-bit encoding.
+#[derive(Debug, Clone)]
+pub(crate) struct KmerAccumulator {
+    k: usize,
+    kmer_count: u64,
+    counts: FxHashMap<u64, u32>,
+    /// True if the warning about the size of the map was logged.
+    warned: bool,
+}
+
+impl KmerAccumulator {
+    /// The k must be between 1 and [`MAX_K`].
+    pub(crate) fn new(k: usize) -> Self {
+        debug_assert!((1..=MAX_K).contains(&k));
+        Self {
+            k,
+            kmer_count: 0,
+            counts: FxHashMap::default(),
+            warned: false,
+        }
+    }
+
+    /// Compute the canonical k-mers of the given sequence, ignoring case, in the order in which they occur.
+    /// K-mers containing other characters than ACGT are skipped, and line terminators are ignored,
+    /// such that multiline fasta sequences can be passed directly.
+    pub(crate) fn canonical_kmers(&self, seq: &[u8]) -> Vec<u64> {
+        let mut kmers = Vec::with_capacity((seq.len() + 1).saturating_sub(self.k));
+        let shift = 2 * (self.k - 1);
+        let mask = u64::MAX >> (64 - 2 * self.k);
+        let mut forward = 0;
+        let mut reverse = 0;
+        // The number of consecutive bases that end at the current base.
+        let mut length = 0;
+        for &byte in seq {
+            let code = match byte {
+                b'A' | b'a' => 0,
+                b'C' | b'c' => 1,
+                b'G' | b'g' => 2,
+                b'T' | b't' => 3,
+                b'\n' | b'\r' => continue,
+                _ => {
+                    length = 0;
+                    continue;
+                }
+            };
+            forward = (forward << 2 | code) & mask;
+            reverse = reverse >> 2 | (3 - code) << shift;
+            length += 1;
+            if length >= self.k {
+                kmers.push(forward.min(reverse));
+            }
+        }
+        kmers
+    }
+
+    /// Count the canonical k-mers of a sequence, which were computed by [`Self::canonical_kmers`].
+    pub(crate) fn add(&mut self, kmers: &[u64]) {
+        for &kmer in kmers {
+            let count = self.counts.entry(kmer).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+        self.kmer_count += kmers.len() as u64;
+        self.check_size();
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        self.kmer_count += other.kmer_count;
+        for (&kmer, &count) in &other.counts {
+            let total = self.counts.entry(kmer).or_insert(0);
+            *total = total.saturating_add(count);
+        }
+        self.check_size();
+    }
+
+    /// Warn once if the map of the counts is larger than [`LARGE_MAP_BYTES`].
+    fn check_size(&mut self) {
+        // Each entry of the map takes an additional control byte.
+        let bytes = self.counts.capacity() * (mem::size_of::<(u64, u32)>() + 1);
+        if !self.warned && bytes > LARGE_MAP_BYTES {
+            warn!(
+                "The counts of the {}-mers take more than {} MiB of memory",
+                self.k,
+                LARGE_MAP_BYTES >> 20
+            );
+            self.warned = true;
+        }
+    }
+
+    pub(crate) fn finish(self) -> KmerSummary {
+        let distinct_count = self.counts.len() as u64;
+        let unique_count = self.counts.values().filter(|&&count| count == 1).count() as u64;
+        KmerSummary {
+            k: self.k,
+            kmer_count: self.kmer_count,
+            distinct_count,
+            unique_count,
+            unique_percent: if distinct_count == 0 {
+                0.0
+            } else {
+                unique_count as f64 / distinct_count as f64 * 100.0
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kmer::KmerAccumulator;
+
+    #[test]
+    fn test_kmer_accumulator() {
+        let mut accumulator = KmerAccumulator::new(3);
+        // CGT is the reverse complement of ACG.
+        accumulator.add(&accumulator.canonical_kmers(b"ACGT\nNacg"));
+        let mut other = KmerAccumulator::new(3);
+        other.add(&other.canonical_kmers(b"TTTAAA"));
+        other.add(&other.canonical_kmers(b"GGC"));
+        accumulator.extend(&other);
+        let summary = accumulator.finish();
+        // ACG, CGT, ACG, TTT (= AAA), TTA (= TAA), TAA, AAA, GGC
+        assert_eq!(summary.kmer_count, 8);
+        assert_eq!(summary.distinct_count, 4);
+        assert_eq!(summary.unique_count, 1);
+        assert_eq!(summary.unique_percent, 25.0);
+
+        let mut accumulator = KmerAccumulator::new(32);
+        accumulator.add(&accumulator.canonical_kmers(&[b'A'; 33]));
+        accumulator.add(&accumulator.canonical_kmers(b"C"));
+        let summary = accumulator.finish();
+        assert_eq!(summary.kmer_count, 2);
+        assert_eq!(summary.distinct_count, 1);
+    }
+}
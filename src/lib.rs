@@ -0,0 +1,2976 @@
+//! Compute simple statistics for fasta-like files.
+
+use crate::adapter::{AdapterAccumulator, AdapterSummary};
+use crate::composition::{BaseComposition, BasePercentages};
+use crate::contigs::{split_on_n_runs, ContigSummary, DEFAULT_SCAFFOLD_N_THRESHOLD};
+use crate::curve::ContiguityCurve;
+use crate::dinucleotide::DinucleotideCounts;
+use crate::duplicates::{
+    DuplicateIdAccumulator, DuplicateIdSummary, DuplicateSequenceAccumulator,
+    DuplicateSequenceSummary,
+};
+use crate::error::FastxStatisticsError;
+use crate::fai::SequenceLayout;
+use crate::format::{SequenceFormat, SequenceReader};
+use crate::groups::{GroupAccumulator, GroupSummary};
+use crate::histogram::LengthHistogram;
+use crate::homopolymer::{add_run_length_counts, HomopolymerSummary};
+use crate::input::InputProgress;
+use crate::kmer::{KmerAccumulator, KmerSummary};
+use crate::long_reads::{length_thresholds, LengthThresholdSummary};
+use crate::ngx::NgxSummary;
+use crate::poly_tail::{PolyTailAccumulator, PolyTailSummary};
+use crate::quality::{
+    BaseQualityCounts, CycleQuality, PerCycleQualityAccumulator, QualitySummary, ReadQuality,
+};
+use crate::streaming::{StreamingAccumulator, StreamingSummary};
+use crate::telomere::{
+    TelomereAccumulator, TelomereCounts, TelomereSummary, DEFAULT_MIN_TELOMERE_REPEATS,
+};
+use crate::top::{LongSequence, TopSequences};
+use crate::validate::find_invalid_character;
+use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use log::{debug, info, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use seq_io::fastx::OwnedRecord;
+use seq_io::BaseRecord;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+pub mod adapter;
+pub mod bed;
+pub mod bench;
+pub mod checksum;
+pub mod compare;
+pub mod composition;
+pub mod contigs;
+pub mod curve;
+mod defaults;
+pub mod dinucleotide;
+pub mod duplicates;
+pub mod error;
+pub mod fai;
+pub mod filter;
+pub mod format;
+pub mod groups;
+pub mod histogram;
+pub mod homopolymer;
+pub mod input;
+pub mod kmer;
+pub mod long_reads;
+pub mod metrics;
+#[cfg(feature = "serde")]
+pub mod multiqc;
+pub mod ngx;
+pub mod paired;
+pub mod per_sequence;
+pub mod poly_tail;
+pub mod quality;
+pub mod quast;
+pub mod records;
+pub mod sort;
+pub mod streaming;
+pub mod telomere;
+pub mod template;
+pub mod top;
+pub mod validate;
+#[cfg(feature = "serde")]
+pub mod yaml;
+
+/// Statistics about all records of a fasta or fastq file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AssemblySummary {
+    /// The number of records that were not filtered.
+    pub record_count: usize,
+    /// The number of records with an empty sequence, which are excluded from all other statistics.
+    pub empty_sequence_count: usize,
+    /// The number of fastq records whose quality string differs in length from their sequence,
+    /// which are excluded from all other statistics.
+    pub unequal_quality_length_count: usize,
+    /// The number of records whose sequence consists only of Ns, which are included in the statistics.
+    pub all_n_count: usize,
+    /// The percentage of records whose sequence consists only of Ns, 0 if there are no records.
+    pub all_n_percent: f64,
+    /// The number of sequences with characters that are not IUPAC nucleotide codes or gaps,
+    /// which are included in the statistics, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub invalid_character_count: Option<usize>,
+    /// The maximum number of records read per input, if an input had more records than that.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub record_limit: Option<usize>,
+    /// The number of records that were seen and sampled, if the records were subsampled.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub subsample: Option<SubsampleSummary>,
+    /// The number of records that were filtered.
+    pub excluded: ExcludedCounts,
+    /// Statistics about duplicate record ids, if they were checked.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub duplicate_ids: Option<DuplicateIdSummary>,
+    /// The records with identical sequences, if they were checked.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub duplicate_sequences: Option<DuplicateSequenceSummary>,
+    /// Statistics about the sequence lengths, `None` if there are no records.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub lengths: Option<LengthSummary>,
+    /// Statistics about the homopolymer-compressed sequence lengths,
+    /// `None` if there are no records or if [`StatisticsOptions::hoco`] is not set.
+    #[cfg_attr(feature = "serde", serde(rename = "hoco"))]
+    pub hoco_lengths: Option<LengthSummary>,
+    /// Statistics about the sequence lengths with approximate Nx metrics, if [`StatisticsOptions::reservoir_size`] is set
+    /// and there are records. In this case, the exact length statistics are not computed.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub streaming: Option<StreamingSummary>,
+    /// Statistics about the contigs obtained by splitting the sequences at long runs of Ns, `None` if there are no contigs.
+    pub contigs: Option<ContigSummary>,
+    /// The NGx metrics of the sequence lengths, if a genome size was given.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub ngx: Option<NgxSummary>,
+    /// The combined base composition of all sequences.
+    pub base_composition: BaseComposition,
+    /// The percentages of the bases of [`AssemblySummary::base_composition`].
+    pub base_percentages: BasePercentages,
+    /// The number of soft-masked (lowercase) bases of all sequences.
+    pub soft_masked_count: u64,
+    /// The percentage of soft-masked bases, 0 if there are no bases.
+    pub soft_masked_percent: f64,
+    /// The number of IUPAC ambiguity codes other than N of all sequences.
+    pub ambiguous_count: u64,
+    /// The number of uncertain bases of all sequences, i.e. the Ns and the other IUPAC ambiguity codes.
+    pub uncertain_count: u64,
+    /// The number of CG dinucleotides of all sequences, ignoring case.
+    pub cpg_count: u64,
+    /// The CpG observed/expected ratio as computed by [`BaseComposition::cpg_oe_ratio`],
+    /// `None` if there are no C or no G bases.
+    pub cpg_oe_ratio: Option<f64>,
+    /// Statistics about the homopolymer run lengths, `None` if there are no runs other than runs of Ns.
+    pub homopolymers: Option<HomopolymerSummary>,
+    /// Statistics about the quality scores, `None` if there are no fastq records.
+    pub quality: Option<QualitySummary>,
+    /// Statistics about polyG and polyA tails, if requested and there are records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub poly_tails: Option<PolyTailSummary>,
+    /// Statistics about telomere repeats, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub telomeres: Option<TelomereSummary>,
+    /// The occurrences of each of [`StatisticsOptions::adapters`] in the order of the options,
+    /// `None` if there are no adapters.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub adapters: Option<Vec<AdapterSummary>>,
+    /// The mean quality per read position, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub per_cycle_quality: Option<Vec<CycleQuality>>,
+    /// The number of occurrences of each dinucleotide, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub dinucleotides: Option<DinucleotideCounts>,
+    /// Statistics about the canonical k-mers, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub kmers: Option<KmerSummary>,
+    /// A histogram of the sequence lengths, if requested and there are records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub length_histogram: Option<LengthHistogram>,
+    /// The Nx values of the sequences for all percentiles from 1 to 100, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub nx_curve: Option<Vec<usize>>,
+    /// The Nx, hoco Nx, NGx and LGx values for all percentiles from 1 to 100, if requested and there are records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub contiguity_curve: Option<ContiguityCurve>,
+    /// The Lorenz curve of the sequence lengths as computed by [`curve::lorenz_curve`],
+    /// if requested and there are records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub lorenz_curve: Option<Vec<f64>>,
+    /// The sequences reaching each of [`StatisticsOptions::long_read_thresholds`] in ascending order of the thresholds,
+    /// `None` if there are no thresholds or no records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub long_reads: Option<Vec<LengthThresholdSummary>>,
+    /// The sequences reaching each of [`StatisticsOptions::cumulative_length_thresholds`] in ascending order
+    /// of the thresholds, `None` if there are no thresholds or no records.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub cumulative_lengths: Option<Vec<LengthThresholdSummary>>,
+    /// Statistics per id prefix in the order of the first sequence with each prefix, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub groups: Option<Vec<GroupSummary>>,
+    /// The longest sequences ordered by descending length, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub longest_sequences: Option<Vec<LongSequence>>,
+}
+
+/// The number of records considered for random subsampling, and the number of records in the subsample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubsampleSummary {
+    /// The probability of each record to be sampled.
+    pub fraction: f64,
+    pub seen_count: usize,
+    pub sampled_count: usize,
+}
+
+/// The number of records excluded by each filter, `None` for filters that are not used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExcludedCounts {
+    /// Records excluded because of their id.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub by_id: Option<usize>,
+    /// Records excluded because their id matches a regular expression.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub by_id_regex: Option<usize>,
+    /// Records excluded because their id is not among the included ids.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub not_included: Option<usize>,
+    /// Records excluded because their header line matches a description filter.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub by_description: Option<usize>,
+    /// Records excluded because they are shorter than the minimum length.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub too_short: Option<usize>,
+    /// Records excluded because they are longer than the maximum length.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub too_long: Option<usize>,
+    /// Records excluded because their GC content is outside of the allowed range.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub by_gc: Option<usize>,
+    /// Records excluded because their fraction of Ns is above the maximum.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub by_n_fraction: Option<usize>,
+    /// Records excluded because their entropy is below the minimum complexity.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub low_complexity: Option<usize>,
+    /// Records excluded because their sequence consists only of Ns.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub all_n: Option<usize>,
+}
+
+impl ExcludedCounts {
+    /// Initialise the counts of all filters used by the given options with zero.
+    fn new(options: &StatisticsOptions) -> Self {
+        let zero_if = |used: bool| if used { Some(0) } else { None };
+        Self {
+            by_id: zero_if(!options.filter_ids.is_empty() || !options.exclude_ids.is_empty()),
+            by_id_regex: zero_if(!options.filter_id_regexes.is_empty()),
+            not_included: zero_if(options.include_ids.is_some()),
+            by_description: zero_if(!options.filter_descriptions.is_empty()),
+            too_short: zero_if(options.min_length.is_some()),
+            too_long: zero_if(options.max_length.is_some()),
+            by_gc: zero_if(options.min_gc.is_some() || options.max_gc.is_some()),
+            by_n_fraction: zero_if(options.max_n_fraction.is_some()),
+            low_complexity: zero_if(options.min_complexity.is_some()),
+            all_n: zero_if(options.exclude_all_n),
+        }
+    }
+
+    /// Count a record as excluded by the given filter.
+    pub(crate) fn count(&mut self, exclusion: Exclusion) {
+        let count = match exclusion {
+            Exclusion::Id => &mut self.by_id,
+            Exclusion::IdRegex => &mut self.by_id_regex,
+            Exclusion::NotIncluded => &mut self.not_included,
+            Exclusion::Description => &mut self.by_description,
+            Exclusion::TooShort => &mut self.too_short,
+            Exclusion::TooLong => &mut self.too_long,
+            Exclusion::Gc => &mut self.by_gc,
+            Exclusion::NFraction => &mut self.by_n_fraction,
+            Exclusion::LowComplexity => &mut self.low_complexity,
+            Exclusion::AllN => &mut self.all_n,
+        };
+        *count.get_or_insert(0) += 1;
+    }
+
+    fn extend(&mut self, other: &Self) {
+        for (count, other_count) in [
+            (&mut self.by_id, other.by_id),
+            (&mut self.by_id_regex, other.by_id_regex),
+            (&mut self.not_included, other.not_included),
+            (&mut self.by_description, other.by_description),
+            (&mut self.too_short, other.too_short),
+            (&mut self.too_long, other.too_long),
+            (&mut self.by_gc, other.by_gc),
+            (&mut self.by_n_fraction, other.by_n_fraction),
+            (&mut self.low_complexity, other.low_complexity),
+            (&mut self.all_n, other.all_n),
+        ] {
+            if let Some(other_count) = other_count {
+                *count.get_or_insert(0) += other_count;
+            }
+        }
+    }
+}
+
+/// Statistics about a set of sequence lengths, both with and without counting Ns.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LengthSummary {
+    /// The number of N characters.
+    pub n_bases: u64,
+    /// The percentage of N characters of the total length.
+    pub n_percent: f64,
+    /// The percentage of characters other than N of the total length.
+    pub non_n_percent: f64,
+    /// Statistics about the GC content.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub gc: GcSummary,
+    /// Statistics about the lengths including Ns.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub with_ns: NxSummary,
+    /// Statistics about the lengths excluding Ns.
+    pub without_ns: NxSummary,
+    /// The share of the total length contained in the longest sequences, for the percentages in [`TOP_FRACTIONS`].
+    pub top_fractions: Vec<TopFraction>,
+    /// The lengths at the percentages in [`LENGTH_PERCENTILES`] of the sequences ordered by ascending length.
+    pub length_percentiles: Vec<LengthPercentile>,
+    /// The Gini coefficient of the lengths including Ns, see [`gini_coefficient`].
+    pub gini_coefficient: f64,
+}
+
+/// The percentages of the longest sequences for which [`LengthSummary::top_fractions`] are reported.
+pub const TOP_FRACTIONS: [u8; 5] = [1, 5, 10, 25, 50];
+
+/// The share of the total length contained in a top fraction of the sequences by length.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TopFraction {
+    /// The percentage of sequences, counted from the longest.
+    pub sequence_percent: u8,
+    /// The number of sequences in the fraction, which is rounded up such that it contains at least one sequence.
+    pub sequence_count: usize,
+    /// The percentage of the total length contained in these sequences.
+    pub bases_percent: f64,
+}
+
+/// The percentiles of the sequence lengths that are reported in [`LengthSummary::length_percentiles`].
+pub const LENGTH_PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+/// A percentile of the sequence lengths, such as the median length for the 50th percentile.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LengthPercentile {
+    pub percentile: u8,
+    /// The length, interpolated linearly between the two lengths closest to the percentile.
+    pub length: f64,
+}
+
+/// Statistics about the GC content of a set of sequences.
+/// GC percentages are computed relative to the number of non-N characters.
+/// Sequences that consist only of Ns have an undefined GC content, which is counted as 0%.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GcSummary {
+    /// The GC percentage of all sequences combined.
+    pub gc_percent: f64,
+    pub min_gc_percent: f64,
+    pub max_gc_percent: f64,
+    /// The mean of the GC percentages of the individual sequences.
+    pub mean_gc_percent: f64,
+    /// The number of sequences with undefined GC content.
+    pub undefined_gc_count: usize,
+}
+
+/// Nx metrics and extremal values of a set of sequence lengths.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NxSummary {
+    pub total_length: u64,
+    pub n50: usize,
+    pub n75: usize,
+    pub n90: usize,
+    /// The number of sequences needed to cover 50% of the total length.
+    pub l50: usize,
+    /// The number of sequences needed to cover 75% of the total length.
+    pub l75: usize,
+    /// The number of sequences needed to cover 90% of the total length.
+    pub l90: usize,
+    /// The Nx and Lx values for the requested additional percentiles.
+    /// Percentiles that are always reported are omitted here.
+    pub additional_percentiles: Vec<PercentileSummary>,
+    /// The area under the Nx curve.
+    pub aun: f64,
+    /// The E-size, i.e. the expected length of the sequence that contains a randomly chosen base.
+    /// It is computed in the same way as [`NxSummary::aun`], and hence always equal to it.
+    pub e_size: f64,
+    pub mean_len: f64,
+    pub median_len: f64,
+    /// The population standard deviation of the lengths.
+    pub std_dev_len: f64,
+    /// The coefficient of variation of the lengths, as a percentage.
+    pub cv_len: f64,
+    pub max_len: usize,
+    pub min_len: usize,
+}
+
+/// The Nx and Lx values of an additional percentile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PercentileSummary {
+    pub percentile: u8,
+    pub nx: usize,
+    pub lx: usize,
+}
+
+/// A record that was not filtered, together with its statistics.
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptedRecord<'a> {
+    pub id: &'a str,
+    pub head: &'a [u8],
+    /// The sequence, possibly containing line terminators.
+    pub seq: &'a [u8],
+    /// The quality string, `None` for fasta records.
+    pub qual: Option<&'a [u8]>,
+    /// The position and line structure of the sequence in the input.
+    pub layout: SequenceLayout,
+    pub statistics: &'a SequenceStatistics,
+}
+
+/// Options for [`basic_statistics`].
+#[derive(Debug, Clone)]
+pub struct StatisticsOptions {
+    /// Records with these ids are skipped.
+    pub filter_ids: Vec<String>,
+    /// Records with ids matching any of these regular expressions are skipped.
+    pub filter_id_regexes: Vec<Regex>,
+    /// Records with these ids are skipped, like with [`StatisticsOptions::filter_ids`],
+    /// but with constant-time lookups for large numbers of ids.
+    pub exclude_ids: HashSet<String>,
+    /// If given, records with ids not in this set are skipped.
+    pub include_ids: Option<HashSet<String>>,
+    /// Compare the record ids with the id filters case-insensitively.
+    /// The ids of [`StatisticsOptions::filter_ids`], [`StatisticsOptions::exclude_ids`] and
+    /// [`StatisticsOptions::include_ids`] are converted to lowercase once before reading the input,
+    /// and the [`StatisticsOptions::filter_id_regexes`] are compiled case-insensitively.
+    /// The description filters are not affected.
+    pub ignore_case: bool,
+    /// Records whose whole header line, including the id and the description, matches any of these
+    /// regular expressions are skipped.
+    pub filter_descriptions: Vec<Regex>,
+    /// Records shorter than this are skipped.
+    pub min_length: Option<usize>,
+    /// Records longer than this are skipped.
+    pub max_length: Option<usize>,
+    /// Records with a lower GC percentage are skipped.
+    /// Records consisting only of Ns have no GC content and are never skipped by this.
+    pub min_gc: Option<f64>,
+    /// Records with a higher GC percentage are skipped.
+    /// Records consisting only of Ns have no GC content and are never skipped by this.
+    pub max_gc: Option<f64>,
+    /// Records with a higher fraction of Ns are skipped.
+    /// Empty records have no fraction of Ns and are never skipped by this.
+    pub max_n_fraction: Option<f64>,
+    /// Records with a lower entropy as computed by [`SequenceStatistics::entropy`] are skipped.
+    pub min_complexity: Option<f64>,
+    /// Records whose sequence consists only of Ns are skipped.
+    pub exclude_all_n: bool,
+    /// Additional percentiles for the Nx metric.
+    pub additional_percentiles: Vec<u8>,
+    /// If set, compute a histogram of the sequence lengths with this many bins.
+    pub length_histogram_bins: Option<usize>,
+    /// Space the bins of the length histogram logarithmically.
+    pub length_histogram_log_scale: bool,
+    /// The minimum length of a run of Ns at which sequences are split into contigs.
+    pub scaffold_n_threshold: usize,
+    /// If set, report the ids and lengths of this many longest sequences.
+    pub top: Option<usize>,
+    /// If set, compute statistics per group of sequences whose ids share the part before the first occurrence of this separator.
+    pub group_separator: Option<String>,
+    /// The expected genome size, used to compute the NGx metrics.
+    pub genome_size: Option<u64>,
+    /// If set, compute the Nx values for all percentiles from 1 to 100.
+    pub all_percentiles: bool,
+    /// If set, compute the [`ContiguityCurve`] of the sequences.
+    pub contiguity_curve: bool,
+    /// If set, compute the Lorenz curve of the sequence lengths.
+    pub lorenz_curve: bool,
+    /// Count the sequences that are at least as long as each of these thresholds.
+    pub long_read_thresholds: Vec<usize>,
+    /// Count the sequences that are at least as long as each of these thresholds for a cumulative length table.
+    pub cumulative_length_thresholds: Vec<usize>,
+    /// Compute the length statistics of the homopolymer-compressed sequences.
+    pub hoco: bool,
+    /// If set, do not store the lengths of all sequences, but approximate the Nx metrics from a random sample
+    /// of at most this many sequence lengths, see [`AssemblySummary::streaming`].
+    /// The statistics that need all lengths, such as the homopolymer-compressed lengths, the contigs,
+    /// the NGx metrics, the length histogram and the Nx curve, are not computed then.
+    pub reservoir_size: Option<usize>,
+    /// If set, detect polyG and polyA tails of at least this length.
+    pub poly_tail_min_length: Option<usize>,
+    /// If set, count the occurrences of this telomere repeat motif, which must be an uppercase sequence of ACGT.
+    pub telomere_motif: Option<String>,
+    /// The minimum number of occurrences of the telomere motif at an end of a sequence to count as a telomere.
+    pub min_telomere_repeats: usize,
+    /// Count the sequences that contain each of these adapters, which must be uppercase sequences of ACGT.
+    pub adapters: Vec<String>,
+    /// If set, compute the mean quality per read position of fastq records.
+    pub per_cycle_quality: bool,
+    /// If set, count the dinucleotides of the sequences.
+    pub dinucleotides: bool,
+    /// If set, count the distinct canonical k-mers of this length, which must be between 1 and [`kmer::MAX_K`].
+    pub kmer_length: Option<usize>,
+    /// If set, parse the inputs in this format instead of detecting it from the first record.
+    /// The lines before the first record of that format are skipped then with a warning,
+    /// such that e.g. comment lines at the start of an input do not prevent parsing it.
+    pub format: Option<SequenceFormat>,
+    /// Show a progress bar on stderr while reading the input.
+    pub show_progress: bool,
+    /// Skip this many records at the start of each input, before applying any filters or the limit.
+    pub skip: usize,
+    /// Stop reading each input after this many records, including filtered records.
+    pub limit: Option<usize>,
+    /// Include each record with this probability, before applying any filters.
+    pub subsample: Option<f64>,
+    /// The seed of the random number generator used for subsampling.
+    pub seed: u64,
+    /// Check for records with the same id, including filtered records.
+    pub check_duplicate_ids: bool,
+    /// Check for records with identical sequences, ignoring case and line breaks.
+    /// Unlike the id check, this only considers records that were not filtered.
+    pub check_duplicate_sequences: bool,
+    /// Compare sequences with the same hash byte by byte when checking for identical sequences,
+    /// which requires to store a copy of each distinct sequence in memory.
+    pub verify_duplicate_sequences: bool,
+    /// Count the sequences with characters that are not IUPAC nucleotide codes or gaps.
+    pub check_characters: bool,
+    /// Return an error for the first invalid record, e.g. one with a duplicate id, an empty sequence,
+    /// a quality string that differs in length from the sequence or an invalid character
+    /// with [`StatisticsOptions::check_characters`], instead of warning about it.
+    pub strict: bool,
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> Self {
+        Self {
+            filter_ids: Vec::new(),
+            filter_id_regexes: Vec::new(),
+            exclude_ids: HashSet::new(),
+            include_ids: None,
+            ignore_case: false,
+            filter_descriptions: Vec::new(),
+            min_length: None,
+            max_length: None,
+            min_gc: None,
+            max_gc: None,
+            max_n_fraction: None,
+            min_complexity: None,
+            exclude_all_n: false,
+            additional_percentiles: Vec::new(),
+            length_histogram_bins: None,
+            length_histogram_log_scale: false,
+            scaffold_n_threshold: DEFAULT_SCAFFOLD_N_THRESHOLD,
+            top: None,
+            group_separator: None,
+            all_percentiles: false,
+            contiguity_curve: false,
+            lorenz_curve: false,
+            long_read_thresholds: Vec::new(),
+            cumulative_length_thresholds: Vec::new(),
+            hoco: true,
+            reservoir_size: None,
+            poly_tail_min_length: None,
+            telomere_motif: None,
+            min_telomere_repeats: DEFAULT_MIN_TELOMERE_REPEATS,
+            adapters: Vec::new(),
+            per_cycle_quality: false,
+            dinucleotides: false,
+            kmer_length: None,
+            genome_size: None,
+            format: None,
+            show_progress: true,
+            skip: 0,
+            limit: None,
+            subsample: None,
+            seed: 0,
+            check_duplicate_ids: false,
+            check_duplicate_sequences: false,
+            verify_duplicate_sequences: false,
+            check_characters: false,
+            strict: false,
+        }
+    }
+}
+
+impl StatisticsOptions {
+    /// These options with the id filters prepared for [`StatisticsOptions::ignore_case`],
+    /// such that they can be compared with the lowercase record ids.
+    pub(crate) fn with_lowercase_ids(&self) -> Result<Cow<'_, Self>, FastxStatisticsError> {
+        if !self.ignore_case {
+            return Ok(Cow::Borrowed(self));
+        }
+
+        let lowercase = |ids: &HashSet<String>| ids.iter().map(|id| id.to_lowercase()).collect();
+        Ok(Cow::Owned(Self {
+            filter_ids: self.filter_ids.iter().map(|id| id.to_lowercase()).collect(),
+            filter_id_regexes: self
+                .filter_id_regexes
+                .iter()
+                .map(|regex| {
+                    RegexBuilder::new(regex.as_str())
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|err| {
+                            FastxStatisticsError::Other(format!(
+                                "Invalid id regex {:?}: {err}",
+                                regex.as_str()
+                            ))
+                        })
+                })
+                .collect::<Result<_, _>>()?,
+            exclude_ids: lowercase(&self.exclude_ids),
+            include_ids: self.include_ids.as_ref().map(lowercase),
+            ..self.clone()
+        }))
+    }
+}
+
+/// Compute statistics about all records in the given fasta or fastq input.
+///
+/// `progress` is used for the progress bar, and can be the length of the input in bytes.
+pub fn basic_statistics(
+    input: impl Read + Send,
+    progress: impl Into<InputProgress>,
+    options: &StatisticsOptions,
+) -> Result<AssemblySummary, FastxStatisticsError> {
+    basic_statistics_with_callback(input, progress, options, |_| Ok(()))
+}
+
+/// Like [`basic_statistics`], but additionally calls `record_callback` for each record that is not filtered.
+pub fn basic_statistics_with_callback(
+    input: impl Read + Send,
+    progress: impl Into<InputProgress>,
+    options: &StatisticsOptions,
+    record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<AssemblySummary, FastxStatisticsError> {
+    multi_input_statistics_with_callback(vec![(input, progress.into())], options, record_callback)
+        .map(|summary| summary.combined)
+}
+
+/// Statistics about multiple inputs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MultiInputSummary {
+    /// The statistics of each input, in the order of the inputs.
+    pub inputs: Vec<AssemblySummary>,
+    /// The statistics of all inputs combined.
+    pub combined: AssemblySummary,
+}
+
+/// Compute statistics about each of the given fasta or fastq inputs, and about all of them combined.
+///
+/// A single progress bar is shown for all inputs together.
+/// `record_callback` is called for each record that is not filtered.
+pub fn multi_input_statistics_with_callback(
+    inputs: Vec<(impl Read + Send, InputProgress)>,
+    options: &StatisticsOptions,
+    mut record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<MultiInputSummary, FastxStatisticsError> {
+    info!("Reading fasta or fastq file...");
+    let options = &*options.with_lowercase_ids()?;
+    let pb = create_progress_bar(inputs.iter().map(|(_, progress)| progress), options);
+
+    let input_count = inputs.len();
+    let mut combined = StatisticsAccumulator::new(options);
+    let mut input_summaries = Vec::new();
+    let mut position_offset = 0;
+    for (input, progress) in inputs {
+        if input_count == 1 {
+            read_input(
+                input,
+                &progress,
+                position_offset,
+                &pb,
+                options,
+                &mut combined,
+                &mut record_callback,
+            )?;
+        } else {
+            let mut accumulator = StatisticsAccumulator::new(options);
+            read_input(
+                input,
+                &progress,
+                position_offset,
+                &pb,
+                options,
+                &mut accumulator,
+                &mut record_callback,
+            )?;
+            combined.extend(&accumulator)?;
+            input_summaries.push(accumulator.finish(options));
+        }
+        position_offset += progress.total;
+    }
+
+    pb.finish_and_clear();
+    combined.log_warnings();
+
+    let combined = combined.finish(options);
+    if input_count == 1 {
+        input_summaries.push(combined.clone());
+    }
+    Ok(MultiInputSummary {
+        inputs: input_summaries,
+        combined,
+    })
+}
+
+/// Create a progress bar for reading all the given inputs, which is hidden if the options disable it.
+pub(crate) fn create_progress_bar<'a>(
+    progresses: impl Iterator<Item = &'a InputProgress> + Clone,
+    options: &StatisticsOptions,
+) -> ProgressBar {
+    if !options.show_progress {
+        return ProgressBar::hidden();
+    }
+
+    // If the length of any input is unknown, then the total length is unknown.
+    let total = if progresses.clone().any(|progress| progress.total == 0) {
+        0
+    } else {
+        progresses.map(|progress| progress.total).sum()
+    };
+    let pb = ProgressBar::new(total);
+    if total == 0 {
+        // The length of the input is unknown, so only the throughput can be shown.
+        pb.set_style(
+            ProgressStyle::with_template("[{elapsed_precise}] {spinner} {bytes} ({bytes_per_sec})")
+                .unwrap(),
+        );
+    } else {
+        pb.set_style(
+            ProgressStyle::with_template(
+                "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+            )
+            .unwrap()
+            .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
+                write!(w, "{:.0}s", state.eta().as_secs_f64()).unwrap()
+            })
+            .progress_chars("#>-"),
+        );
+    }
+    pb
+}
+
+/// The maximum number of records whose statistics are computed in parallel.
+pub(crate) const CHUNK_RECORD_COUNT: usize = 1000;
+/// The maximum number of sequence bytes whose statistics are computed in parallel,
+/// to bound the memory usage for long sequences.
+pub(crate) const CHUNK_BYTE_COUNT: usize = 64 * 1024 * 1024;
+
+/// The number of chunks that the reader thread may parse ahead of the computation of the statistics.
+const READ_AHEAD_CHUNK_COUNT: usize = 2;
+
+/// A message from the reader thread.
+enum ParsedChunk {
+    /// The next records of the input, and the byte position in the input after them.
+    Records(Vec<(OwnedRecord, SequenceLayout)>, u64),
+    /// The input has more records than the limit.
+    LimitReached,
+    Error(FastxStatisticsError),
+}
+
+/// Read all records of the given input into the accumulator.
+///
+/// The records are parsed in a separate thread, such that parsing and computing the statistics overlap.
+fn read_input(
+    input: impl Read + Send,
+    progress: &InputProgress,
+    position_offset: u64,
+    pb: &ProgressBar,
+    options: &StatisticsOptions,
+    accumulator: &mut StatisticsAccumulator,
+    mut record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<(), FastxStatisticsError> {
+    let (sender, receiver) = crossbeam_channel::bounded(READ_AHEAD_CHUNK_COUNT);
+    let (format, skip, limit) = (options.format, options.skip, options.limit);
+
+    crossbeam_utils::thread::scope(|scope| {
+        scope.spawn(move |_| parse_chunks(input, format, skip, limit, sender));
+
+        let mut last_update = Instant::now();
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        // If this returns early, the receiver is dropped, which stops the reader thread.
+        for parsed_chunk in receiver {
+            let (records, position) = match parsed_chunk {
+                ParsedChunk::Records(records, position) => (records, position),
+                ParsedChunk::LimitReached => {
+                    accumulator.record_limit = options.limit;
+                    continue;
+                }
+                ParsedChunk::Error(err) => return Err(err),
+            };
+
+            let mut chunk = Vec::with_capacity(records.len());
+            for (record, layout) in records {
+                let sampled = is_sampled(options, &mut rng);
+                accumulator.count_sampled(sampled);
+                if !sampled || check_id(record_id(&record)?, record.head(), options, accumulator)? {
+                    continue;
+                }
+                chunk.push((record, layout));
+            }
+            read_chunk(&chunk, options, accumulator, &mut record_callback)?;
+
+            let now = Instant::now();
+            if last_update + Duration::from_millis(200) <= now {
+                pb.set_position(position_offset + progress.position(position));
+                last_update = now;
+            }
+        }
+        Ok(())
+    })
+    .map_err(|_| FastxStatisticsError::Other("The thread reading the input panicked".to_owned()))?
+}
+
+/// Parse the records of the input and send them in chunks, skipping the first `skip` records
+/// and stopping after `limit` records.
+/// Returns early if the receiver is dropped.
+fn parse_chunks(
+    input: impl Read,
+    format: Option<SequenceFormat>,
+    mut skip: usize,
+    limit: Option<usize>,
+    sender: crossbeam_channel::Sender<ParsedChunk>,
+) {
+    let mut fastx_reader = SequenceReader::new(BufReader::new(input), format);
+    let mut chunk = Vec::new();
+    let mut chunk_byte_count = 0;
+    let mut record_count = 0;
+
+    while let Some(record) = fastx_reader.next_record(false) {
+        if skip == 0 && limit.map_or(false, |limit| record_count >= limit) {
+            let position = fastx_reader.position().byte();
+            if !chunk.is_empty() && sender.send(ParsedChunk::Records(chunk, position)).is_err() {
+                return;
+            }
+            let _ = sender.send(ParsedChunk::LimitReached);
+            return;
+        }
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let _ = sender.send(ParsedChunk::Error(FastxStatisticsError::parse(
+                    "Error parsing fastx",
+                    err,
+                )));
+                return;
+            }
+        };
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+
+        record_count += 1;
+        chunk_byte_count += record.0.seq().len();
+        chunk.push(record);
+        if chunk.len() >= CHUNK_RECORD_COUNT || chunk_byte_count >= CHUNK_BYTE_COUNT {
+            let position = fastx_reader.position().byte();
+            if sender
+                .send(ParsedChunk::Records(std::mem::take(&mut chunk), position))
+                .is_err()
+            {
+                return;
+            }
+            chunk_byte_count = 0;
+        }
+    }
+
+    if !chunk.is_empty() {
+        let position = fastx_reader.position().byte();
+        let _ = sender.send(ParsedChunk::Records(chunk, position));
+    }
+}
+
+/// Decide randomly whether the next record is included in the subsample, if the options request subsampling.
+pub(crate) fn is_sampled(options: &StatisticsOptions, rng: &mut StdRng) -> bool {
+    options
+        .subsample
+        .map_or(true, |fraction| rng.gen::<f64>() < fraction)
+}
+
+/// Returns the id of the given record, or an error if it is not valid utf-8.
+pub(crate) fn record_id(record: &impl BaseRecord) -> Result<&str, FastxStatisticsError> {
+    Ok(record.id()?)
+}
+
+/// Check the given record id for duplicates if requested,
+/// and return true if the record is excluded by the id or description filters, counting it as excluded.
+/// `head` is the whole header line of the record.
+pub(crate) fn check_id(
+    id: &str,
+    head: &[u8],
+    options: &StatisticsOptions,
+    accumulator: &mut StatisticsAccumulator,
+) -> Result<bool, FastxStatisticsError> {
+    if let Some(duplicate_ids) = &mut accumulator.duplicate_ids {
+        duplicate_ids.add(id)?;
+    }
+
+    if let Some(exclusion) = id_exclusion(id, head, options) {
+        accumulator.excluded.count(exclusion);
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// The filter that excludes a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Exclusion {
+    Id,
+    IdRegex,
+    NotIncluded,
+    Description,
+    TooShort,
+    TooLong,
+    Gc,
+    NFraction,
+    LowComplexity,
+    AllN,
+}
+
+/// Returns the id or description filter of the options that excludes the record with the given id
+/// and header line, if any.
+pub(crate) fn id_exclusion(
+    id: &str,
+    head: &[u8],
+    options: &StatisticsOptions,
+) -> Option<Exclusion> {
+    let id = if options.ignore_case {
+        Cow::Owned(id.to_lowercase())
+    } else {
+        Cow::Borrowed(id)
+    };
+    let id = id.as_ref();
+    if options.exclude_ids.contains(id)
+        || options.filter_ids.iter().any(|filter_id| filter_id == id)
+    {
+        Some(Exclusion::Id)
+    } else if options
+        .filter_id_regexes
+        .iter()
+        .any(|filter_id_regex| filter_id_regex.is_match(id))
+    {
+        Some(Exclusion::IdRegex)
+    } else if options
+        .include_ids
+        .as_ref()
+        .map_or(false, |include_ids| !include_ids.contains(id))
+    {
+        Some(Exclusion::NotIncluded)
+    } else if options.filter_descriptions.is_empty() {
+        None
+    } else {
+        let head = String::from_utf8_lossy(head);
+        options
+            .filter_descriptions
+            .iter()
+            .any(|filter_description| filter_description.is_match(&head))
+            .then(|| Exclusion::Description)
+    }
+}
+
+/// Returns the sequence filter of the options that excludes the record with the given statistics, if any.
+pub(crate) fn sequence_exclusion(
+    sequence_statistics: &SequenceStatistics,
+    options: &StatisticsOptions,
+) -> Option<Exclusion> {
+    if options
+        .min_length
+        .map_or(false, |min_length| sequence_statistics.len < min_length)
+    {
+        return Some(Exclusion::TooShort);
+    }
+    if options
+        .max_length
+        .map_or(false, |max_length| sequence_statistics.len > max_length)
+    {
+        return Some(Exclusion::TooLong);
+    }
+    if sequence_statistics.len_without_ns > 0 {
+        let gc_percent = sequence_statistics.gc_percent();
+        if options.min_gc.map_or(false, |min_gc| gc_percent < min_gc)
+            || options.max_gc.map_or(false, |max_gc| gc_percent > max_gc)
+        {
+            return Some(Exclusion::Gc);
+        }
+    }
+    if sequence_statistics.len > 0 {
+        let n_fraction = (sequence_statistics.len - sequence_statistics.len_without_ns) as f64
+            / sequence_statistics.len as f64;
+        if options
+            .max_n_fraction
+            .map_or(false, |max_n_fraction| n_fraction > max_n_fraction)
+        {
+            return Some(Exclusion::NFraction);
+        }
+    }
+    if options.min_complexity.map_or(false, |min_complexity| {
+        sequence_statistics.entropy() < min_complexity
+    }) {
+        return Some(Exclusion::LowComplexity);
+    }
+    if options.exclude_all_n
+        && sequence_statistics.len > 0
+        && sequence_statistics.len_without_ns == 0
+    {
+        return Some(Exclusion::AllN);
+    }
+    None
+}
+
+/// Compute the statistics of the records in the chunk in parallel, and then accumulate them in order.
+/// Each record is paired with the layout of its sequence in the input.
+pub(crate) fn read_chunk(
+    chunk: &[(OwnedRecord, SequenceLayout)],
+    options: &StatisticsOptions,
+    accumulator: &mut StatisticsAccumulator,
+    mut record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<(), FastxStatisticsError> {
+    let shared_accumulator = &*accumulator;
+    let chunk_statistics: Vec<_> = chunk
+        .par_iter()
+        .map_init(Vec::new, |buffer, (record, _)| {
+            shared_accumulator.record_statistics(record.seq(), record.opt_qual(), options, buffer)
+        })
+        .collect();
+
+    for ((record, layout), record_statistics) in chunk.iter().zip(&chunk_statistics) {
+        let sequence_statistics = &record_statistics.sequence;
+        if sequence_statistics.len == 0 {
+            if options.strict {
+                return Err(FastxStatisticsError::EmptySequence(
+                    // The id was checked to be valid utf-8 when reading the record.
+                    record.id().unwrap().to_owned(),
+                ));
+            }
+            accumulator.empty_sequence_count += 1;
+            continue;
+        }
+        if let Some(qual) = record.opt_qual() {
+            let quality_length = qual
+                .iter()
+                .filter(|&&byte| byte != b'\n' && byte != b'\r')
+                .count();
+            if quality_length != sequence_statistics.len {
+                // The id was checked to be valid utf-8 when reading the record.
+                let id = record.id().unwrap();
+                if options.strict {
+                    return Err(FastxStatisticsError::UnequalQualityLength {
+                        id: id.to_owned(),
+                        sequence_length: sequence_statistics.len,
+                        quality_length,
+                    });
+                }
+                warn!(
+                    "Record {id:?} has a sequence of length {}, but a quality string of length {quality_length}",
+                    sequence_statistics.len
+                );
+                accumulator.unequal_quality_length_count += 1;
+                continue;
+            }
+        }
+        if let Some(exclusion) = sequence_exclusion(sequence_statistics, options) {
+            accumulator.excluded.count(exclusion);
+            continue;
+        }
+        if let Some(invalid_character_count) = &mut accumulator.invalid_character_count {
+            if let Some((position, character)) = find_invalid_character(record.seq()) {
+                // The id was checked to be valid utf-8 when reading the record.
+                let id = record.id().unwrap();
+                if options.strict {
+                    return Err(FastxStatisticsError::InvalidCharacter {
+                        id: id.to_owned(),
+                        character,
+                        position,
+                    });
+                }
+                debug!(
+                    "Record {id:?} contains the character {:?} at position {position}, which is not an IUPAC nucleotide code",
+                    char::from(character)
+                );
+                *invalid_character_count += 1;
+            }
+        }
+        record_callback(AcceptedRecord {
+            // The id was checked to be valid utf-8 when reading the record.
+            id: record.id().unwrap(),
+            head: record.head(),
+            seq: record.seq(),
+            qual: record.opt_qual(),
+            layout: *layout,
+            statistics: sequence_statistics,
+        })?;
+        accumulator.add(
+            record.id().unwrap(),
+            record.seq(),
+            record_statistics,
+            record.opt_qual(),
+        );
+    }
+
+    Ok(())
+}
+
+/// The statistics of a record that are computed in parallel with those of the other records of a chunk,
+/// before they are accumulated in the order of the input.
+#[derive(Debug, Clone)]
+pub(crate) struct RecordStatistics {
+    pub(crate) sequence: SequenceStatistics,
+    contig_lengths: Vec<usize>,
+    /// `None` for fasta records and empty quality strings.
+    quality: Option<ReadQuality>,
+    /// The lengths of the polyG and polyA tails, `None` if tails are not detected.
+    poly_tail_lengths: Option<[usize; 2]>,
+    /// `None` if the dinucleotides are not counted.
+    dinucleotides: Option<DinucleotideCounts>,
+    /// `None` if no telomere motif is given.
+    telomeres: Option<TelomereCounts>,
+    /// The canonical k-mers in the order in which they occur, `None` if k-mers are not counted.
+    kmers: Option<Vec<u64>>,
+    /// The offset of the first occurrence of each adapter, `None` if no adapters are given.
+    adapter_offsets: Option<Vec<Option<usize>>>,
+}
+
+/// The given sequence without line terminators, which is copied into `buffer` only if it contains any.
+pub(crate) fn without_line_terminators<'a>(seq: &'a [u8], buffer: &'a mut Vec<u8>) -> &'a [u8] {
+    if !seq.iter().any(|&byte| byte == b'\n' || byte == b'\r') {
+        return seq;
+    }
+    buffer.clear();
+    buffer.extend(
+        seq.iter()
+            .copied()
+            .filter(|&byte| byte != b'\n' && byte != b'\r'),
+    );
+    buffer
+}
+
+/// Accumulates the statistics of sequences into an [`AssemblySummary`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatisticsAccumulator {
+    excluded: ExcludedCounts,
+    duplicate_ids: Option<DuplicateIdAccumulator>,
+    duplicate_sequences: Option<DuplicateSequenceAccumulator>,
+    empty_sequence_count: usize,
+    unequal_quality_length_count: usize,
+    all_n_count: usize,
+    invalid_character_count: Option<usize>,
+    record_limit: Option<usize>,
+    subsample: Option<SubsampleSummary>,
+    /// If false, the homopolymer-compressed lengths are not stored.
+    hoco: bool,
+    /// If set, the lengths are sampled instead of stored.
+    streaming: Option<StreamingAccumulator>,
+    sequence_lengths: Vec<usize>,
+    sequence_hoco_lengths: Vec<usize>,
+    sequence_lengths_without_ns: Vec<usize>,
+    sequence_hoco_lengths_without_ns: Vec<usize>,
+    contig_lengths: Vec<usize>,
+    gc: GcAccumulator,
+    hoco_gc: GcAccumulator,
+    base_composition: BaseComposition,
+    soft_masked_count: u64,
+    ambiguous_count: u64,
+    cpg_count: u64,
+    homopolymer_run_counts: Vec<usize>,
+    mean_qualities: Vec<f64>,
+    base_qualities: BaseQualityCounts,
+    error_rates: Vec<f64>,
+    max_one_expected_error_count: usize,
+    per_cycle_quality: Option<PerCycleQualityAccumulator>,
+    dinucleotides: Option<DinucleotideCounts>,
+    kmers: Option<KmerAccumulator>,
+    poly_tails: Option<PolyTailAccumulator>,
+    telomeres: Option<TelomereAccumulator>,
+    adapters: Option<AdapterAccumulator>,
+    groups: Option<GroupAccumulator>,
+    longest_sequences: Option<TopSequences>,
+}
+
+impl StatisticsAccumulator {
+    pub(crate) fn new(options: &StatisticsOptions) -> Self {
+        Self {
+            excluded: ExcludedCounts::new(options),
+            invalid_character_count: if options.check_characters {
+                Some(0)
+            } else {
+                None
+            },
+            longest_sequences: options.top.map(TopSequences::new),
+            poly_tails: options.poly_tail_min_length.map(PolyTailAccumulator::new),
+            telomeres: options
+                .telomere_motif
+                .as_ref()
+                .map(|motif| TelomereAccumulator::new(motif, options.min_telomere_repeats)),
+            adapters: if options.adapters.is_empty() {
+                None
+            } else {
+                Some(AdapterAccumulator::new(&options.adapters))
+            },
+            per_cycle_quality: if options.per_cycle_quality {
+                Some(PerCycleQualityAccumulator::default())
+            } else {
+                None
+            },
+            dinucleotides: if options.dinucleotides {
+                Some(DinucleotideCounts::default())
+            } else {
+                None
+            },
+            kmers: options.kmer_length.map(KmerAccumulator::new),
+            groups: options.group_separator.clone().map(GroupAccumulator::new),
+            subsample: options.subsample.map(|fraction| SubsampleSummary {
+                fraction,
+                seen_count: 0,
+                sampled_count: 0,
+            }),
+            duplicate_ids: if options.check_duplicate_ids {
+                Some(DuplicateIdAccumulator::new(options.strict))
+            } else {
+                None
+            },
+            duplicate_sequences: if options.check_duplicate_sequences {
+                Some(DuplicateSequenceAccumulator::new(
+                    options.verify_duplicate_sequences,
+                ))
+            } else {
+                None
+            },
+            hoco: options.hoco,
+            streaming: options
+                .reservoir_size
+                .map(|reservoir_size| StreamingAccumulator::new(reservoir_size, options.seed)),
+            ..Default::default()
+        }
+    }
+
+    /// Compute the statistics of a record that do not depend on the other records, which can be done in parallel.
+    /// The `buffer` is reused between the records to remove line terminators from multiline sequences.
+    pub(crate) fn record_statistics(
+        &self,
+        seq: &[u8],
+        qual: Option<&[u8]>,
+        options: &StatisticsOptions,
+        buffer: &mut Vec<u8>,
+    ) -> RecordStatistics {
+        RecordStatistics {
+            sequence: SequenceStatistics::new(seq),
+            contig_lengths: split_on_n_runs(seq, options.scaffold_n_threshold),
+            quality: qual.and_then(ReadQuality::new),
+            poly_tail_lengths: self
+                .poly_tails
+                .as_ref()
+                .map(|_| PolyTailAccumulator::tail_lengths(seq)),
+            dinucleotides: self
+                .dinucleotides
+                .as_ref()
+                .map(|_| DinucleotideCounts::new(seq)),
+            telomeres: self
+                .telomeres
+                .as_ref()
+                .map(|telomeres| telomeres.count(seq, buffer)),
+            kmers: self.kmers.as_ref().map(|kmers| kmers.canonical_kmers(seq)),
+            adapter_offsets: self
+                .adapters
+                .as_ref()
+                .map(|adapters| adapters.find(seq, buffer)),
+        }
+    }
+
+    /// Accumulate the statistics of a record, which were computed by [`Self::record_statistics`].
+    pub(crate) fn add(
+        &mut self,
+        id: &str,
+        seq: &[u8],
+        record_statistics: &RecordStatistics,
+        qual: Option<&[u8]>,
+    ) {
+        let sequence_statistics = &record_statistics.sequence;
+        let contig_lengths = &record_statistics.contig_lengths;
+        if let Some(streaming) = &mut self.streaming {
+            streaming.add(
+                sequence_statistics.len,
+                sequence_statistics.len_without_ns,
+                sequence_statistics.gc_count,
+            );
+        } else {
+            self.add_lengths(sequence_statistics, contig_lengths);
+        }
+        if sequence_statistics.len_without_ns == 0 {
+            self.all_n_count += 1;
+        }
+        self.base_composition += &sequence_statistics.composition;
+        self.soft_masked_count += sequence_statistics.soft_masked_len as u64;
+        self.ambiguous_count += sequence_statistics.ambiguous_count as u64;
+        self.cpg_count += sequence_statistics.cpg_count as u64;
+        add_run_length_counts(
+            &mut self.homopolymer_run_counts,
+            &sequence_statistics.homopolymer_run_counts,
+        );
+        if let Some(qual) = qual {
+            if let Some(read_quality) = &record_statistics.quality {
+                self.mean_qualities.push(read_quality.mean_quality);
+                self.base_qualities += &read_quality.base_qualities;
+                self.error_rates.push(read_quality.error_rate());
+                if read_quality.expected_errors <= 1.0 {
+                    self.max_one_expected_error_count += 1;
+                }
+            }
+            if let Some(per_cycle_quality) = &mut self.per_cycle_quality {
+                per_cycle_quality.add(qual);
+            }
+        }
+        if let Some(duplicate_sequences) = &mut self.duplicate_sequences {
+            duplicate_sequences.add(id, seq);
+        }
+        if let (Some(poly_tails), Some(tail_lengths)) =
+            (&mut self.poly_tails, record_statistics.poly_tail_lengths)
+        {
+            poly_tails.add(tail_lengths);
+        }
+        if let (Some(telomeres), Some(counts)) = (&mut self.telomeres, &record_statistics.telomeres)
+        {
+            telomeres.add(id, counts);
+        }
+        if let (Some(adapters), Some(offsets)) =
+            (&mut self.adapters, &record_statistics.adapter_offsets)
+        {
+            adapters.add(offsets);
+        }
+        if let (Some(dinucleotides), Some(record_dinucleotides)) =
+            (&mut self.dinucleotides, &record_statistics.dinucleotides)
+        {
+            *dinucleotides += record_dinucleotides;
+        }
+        if let (Some(kmers), Some(record_kmers)) = (&mut self.kmers, &record_statistics.kmers) {
+            kmers.add(record_kmers);
+        }
+        if let Some(groups) = &mut self.groups {
+            groups.add(id, sequence_statistics.len);
+        }
+        if let Some(longest_sequences) = &mut self.longest_sequences {
+            longest_sequences.add(id, sequence_statistics.len);
+        }
+    }
+
+    /// Store the lengths of a sequence and its contigs, for the statistics that need all lengths.
+    fn add_lengths(&mut self, sequence_statistics: &SequenceStatistics, contig_lengths: &[usize]) {
+        self.sequence_lengths.push(sequence_statistics.len);
+        self.sequence_lengths_without_ns
+            .push(sequence_statistics.len_without_ns);
+        if self.hoco {
+            self.sequence_hoco_lengths
+                .push(sequence_statistics.hoco_len);
+            self.sequence_hoco_lengths_without_ns
+                .push(sequence_statistics.hoco_len_without_ns);
+            self.hoco_gc.add(
+                sequence_statistics.hoco_gc_count,
+                sequence_statistics.hoco_len_without_ns,
+            );
+        }
+        self.contig_lengths.extend_from_slice(contig_lengths);
+        self.gc.add(
+            sequence_statistics.gc_count,
+            sequence_statistics.len_without_ns,
+        );
+    }
+
+    /// Count a record as seen for subsampling, and as sampled if `sampled` is true.
+    pub(crate) fn count_sampled(&mut self, sampled: bool) {
+        if let Some(subsample) = &mut self.subsample {
+            subsample.seen_count += 1;
+            if sampled {
+                subsample.sampled_count += 1;
+            }
+        }
+    }
+
+    /// Add all sequences accumulated in `other` to `self`.
+    fn extend(&mut self, other: &Self) -> Result<(), FastxStatisticsError> {
+        self.excluded.extend(&other.excluded);
+        self.empty_sequence_count += other.empty_sequence_count;
+        self.unequal_quality_length_count += other.unequal_quality_length_count;
+        self.all_n_count += other.all_n_count;
+        if let (Some(invalid_character_count), Some(other_invalid_character_count)) = (
+            &mut self.invalid_character_count,
+            other.invalid_character_count,
+        ) {
+            *invalid_character_count += other_invalid_character_count;
+        }
+        self.record_limit = self.record_limit.or(other.record_limit);
+        if let (Some(subsample), Some(other_subsample)) = (&mut self.subsample, &other.subsample) {
+            subsample.seen_count += other_subsample.seen_count;
+            subsample.sampled_count += other_subsample.sampled_count;
+        }
+        if let (Some(duplicate_ids), Some(other_duplicate_ids)) =
+            (&mut self.duplicate_ids, &other.duplicate_ids)
+        {
+            duplicate_ids.extend(other_duplicate_ids)?;
+        }
+        if let (Some(streaming), Some(other_streaming)) = (&mut self.streaming, &other.streaming) {
+            streaming.extend(other_streaming);
+        }
+        self.sequence_lengths
+            .extend_from_slice(&other.sequence_lengths);
+        self.sequence_hoco_lengths
+            .extend_from_slice(&other.sequence_hoco_lengths);
+        self.sequence_lengths_without_ns
+            .extend_from_slice(&other.sequence_lengths_without_ns);
+        self.sequence_hoco_lengths_without_ns
+            .extend_from_slice(&other.sequence_hoco_lengths_without_ns);
+        self.contig_lengths.extend_from_slice(&other.contig_lengths);
+        self.gc.extend(&other.gc);
+        self.hoco_gc.extend(&other.hoco_gc);
+        self.base_composition += &other.base_composition;
+        self.soft_masked_count += other.soft_masked_count;
+        self.ambiguous_count += other.ambiguous_count;
+        self.cpg_count += other.cpg_count;
+        add_run_length_counts(
+            &mut self.homopolymer_run_counts,
+            &other.homopolymer_run_counts,
+        );
+        self.mean_qualities.extend_from_slice(&other.mean_qualities);
+        self.base_qualities += &other.base_qualities;
+        self.error_rates.extend_from_slice(&other.error_rates);
+        self.max_one_expected_error_count += other.max_one_expected_error_count;
+        if let (Some(per_cycle_quality), Some(other_per_cycle_quality)) =
+            (&mut self.per_cycle_quality, &other.per_cycle_quality)
+        {
+            per_cycle_quality.extend(other_per_cycle_quality);
+        }
+        if let (Some(dinucleotides), Some(other_dinucleotides)) =
+            (&mut self.dinucleotides, &other.dinucleotides)
+        {
+            *dinucleotides += other_dinucleotides;
+        }
+        if let (Some(kmers), Some(other_kmers)) = (&mut self.kmers, &other.kmers) {
+            kmers.extend(other_kmers);
+        }
+        if let (Some(duplicate_sequences), Some(other_duplicate_sequences)) =
+            (&mut self.duplicate_sequences, &other.duplicate_sequences)
+        {
+            duplicate_sequences.extend(other_duplicate_sequences);
+        }
+        if let (Some(poly_tails), Some(other_poly_tails)) =
+            (&mut self.poly_tails, &other.poly_tails)
+        {
+            poly_tails.extend(other_poly_tails);
+        }
+        if let (Some(telomeres), Some(other_telomeres)) = (&mut self.telomeres, &other.telomeres) {
+            telomeres.extend(other_telomeres);
+        }
+        if let (Some(adapters), Some(other_adapters)) = (&mut self.adapters, &other.adapters) {
+            adapters.extend(other_adapters);
+        }
+        if let (Some(groups), Some(other_groups)) = (&mut self.groups, &other.groups) {
+            groups.extend(other_groups);
+        }
+        if let (Some(longest_sequences), Some(other_longest_sequences)) =
+            (&mut self.longest_sequences, &other.longest_sequences)
+        {
+            longest_sequences.extend(other_longest_sequences);
+        }
+        Ok(())
+    }
+
+    /// Warn about empty sequences, about sequences with invalid characters,
+    /// about sequences consisting only of Ns since their GC content is undefined,
+    /// about duplicate ids and about duplicate sequences.
+    pub(crate) fn log_warnings(&self) {
+        if self.empty_sequence_count > 0 {
+            warn!(
+                "{} records have an empty sequence, they are excluded from the statistics",
+                self.empty_sequence_count
+            );
+        }
+        if self.unequal_quality_length_count > 0 {
+            warn!(
+                "{} records have a quality string that differs in length from their sequence, they are excluded from the statistics",
+                self.unequal_quality_length_count
+            );
+        }
+        if let Some(invalid_character_count) =
+            self.invalid_character_count.filter(|&count| count > 0)
+        {
+            warn!(
+                "{invalid_character_count} sequences contain characters that are not IUPAC nucleotide codes"
+            );
+        }
+        if self.all_n_count > 0 {
+            warn!(
+                "{} sequences consist only of Ns, they are included in the statistics and their GC content is reported as 0%",
+                self.all_n_count
+            );
+        }
+        if let Some(duplicate_ids) = &self.duplicate_ids {
+            let summary = duplicate_ids.clone().finish();
+            if summary.duplicate_id_count > 0 {
+                warn!(
+                    "{} records have duplicate ids, only {} of {} ids are unique",
+                    summary.duplicate_id_count, summary.unique_id_count, summary.id_count
+                );
+            }
+        }
+        if let Some(duplicate_sequences) = &self.duplicate_sequences {
+            let summary = duplicate_sequences.clone().finish();
+            if summary.duplicate_count > 0 {
+                warn!(
+                    "{} records have the same sequence as an earlier record{}",
+                    summary.duplicate_count,
+                    if summary.verified {
+                        ""
+                    } else {
+                        " by hash, verify manually"
+                    }
+                );
+            }
+        }
+    }
+
+    pub(crate) fn finish(mut self, options: &StatisticsOptions) -> AssemblySummary {
+        let record_count = self
+            .streaming
+            .as_ref()
+            .map_or(self.sequence_lengths.len(), |streaming| {
+                streaming.sequence_count()
+            });
+        let mut length_histogram = None;
+        let mut ngx = None;
+        let mut nx_curve = None;
+        let mut contiguity_curve = None;
+        let mut lorenz_curve = None;
+        let mut long_reads = None;
+        let mut cumulative_lengths = None;
+        // In streaming mode, no lengths are stored.
+        let (lengths, hoco_lengths) = if !self.sequence_lengths.is_empty() {
+            let lengths = LengthSummary::new(
+                &mut self.sequence_lengths,
+                &mut self.sequence_lengths_without_ns,
+                self.gc.finish(),
+                &options.additional_percentiles,
+            );
+            if options.all_percentiles {
+                let percentiles: Vec<_> = (1..=100).collect();
+                nx_curve = Some(compute_all_nx(
+                    &self.sequence_lengths,
+                    lengths.with_ns.total_length,
+                    &percentiles,
+                ));
+            }
+            if options.lorenz_curve {
+                lorenz_curve = Some(curve::lorenz_curve(
+                    &self.sequence_lengths,
+                    lengths.with_ns.total_length,
+                ));
+            }
+            if !options.long_read_thresholds.is_empty() {
+                long_reads = Some(length_thresholds(
+                    &self.sequence_lengths,
+                    lengths.with_ns.total_length,
+                    &options.long_read_thresholds,
+                ));
+            }
+            if !options.cumulative_length_thresholds.is_empty() {
+                cumulative_lengths = Some(length_thresholds(
+                    &self.sequence_lengths,
+                    lengths.with_ns.total_length,
+                    &options.cumulative_length_thresholds,
+                ));
+            }
+            ngx = options
+                .genome_size
+                .map(|genome_size| NgxSummary::new(&self.sequence_lengths, genome_size));
+            length_histogram = options.length_histogram_bins.map(|bin_count| {
+                LengthHistogram::new(
+                    &self.sequence_lengths,
+                    bin_count,
+                    options.length_histogram_log_scale,
+                )
+            });
+
+            let hoco_lengths = if self.hoco {
+                Some(LengthSummary::new(
+                    &mut self.sequence_hoco_lengths,
+                    &mut self.sequence_hoco_lengths_without_ns,
+                    self.hoco_gc.finish(),
+                    &options.additional_percentiles,
+                ))
+            } else {
+                None
+            };
+            if options.contiguity_curve {
+                contiguity_curve = Some(ContiguityCurve::new(
+                    &self.sequence_lengths,
+                    hoco_lengths
+                        .as_ref()
+                        .map(|_| self.sequence_hoco_lengths.as_slice()),
+                    options.genome_size,
+                ));
+            }
+            (Some(lengths), hoco_lengths)
+        } else {
+            (None, None)
+        };
+
+        let contigs = if self.contig_lengths.is_empty() {
+            None
+        } else {
+            Some(ContigSummary::new(
+                &mut self.contig_lengths,
+                &options.additional_percentiles,
+            ))
+        };
+
+        AssemblySummary {
+            record_count,
+            empty_sequence_count: self.empty_sequence_count,
+            unequal_quality_length_count: self.unequal_quality_length_count,
+            all_n_count: self.all_n_count,
+            all_n_percent: if record_count == 0 {
+                0.0
+            } else {
+                self.all_n_count as f64 / record_count as f64 * 100.0
+            },
+            invalid_character_count: self.invalid_character_count,
+            record_limit: self.record_limit,
+            subsample: self.subsample,
+            excluded: self.excluded,
+            duplicate_ids: self.duplicate_ids.map(DuplicateIdAccumulator::finish),
+            duplicate_sequences: self
+                .duplicate_sequences
+                .map(DuplicateSequenceAccumulator::finish),
+            lengths,
+            hoco_lengths,
+            streaming: self.streaming.and_then(StreamingAccumulator::finish),
+            contigs,
+            ngx,
+            cpg_oe_ratio: self.base_composition.cpg_oe_ratio(self.cpg_count),
+            base_percentages: BasePercentages::new(&self.base_composition),
+            soft_masked_percent: self.base_composition.percent(self.soft_masked_count),
+            uncertain_count: self.base_composition.n + self.ambiguous_count,
+            base_composition: self.base_composition,
+            soft_masked_count: self.soft_masked_count,
+            ambiguous_count: self.ambiguous_count,
+            cpg_count: self.cpg_count,
+            homopolymers: HomopolymerSummary::new(self.homopolymer_run_counts),
+            quality: if self.mean_qualities.is_empty() {
+                None
+            } else {
+                Some(QualitySummary::new(
+                    &mut self.mean_qualities,
+                    &self.base_qualities,
+                    &mut self.error_rates,
+                    self.max_one_expected_error_count,
+                ))
+            },
+            poly_tails: self.poly_tails.and_then(PolyTailAccumulator::finish),
+            telomeres: self.telomeres.map(TelomereAccumulator::finish),
+            adapters: self.adapters.map(AdapterAccumulator::finish),
+            per_cycle_quality: self
+                .per_cycle_quality
+                .map(PerCycleQualityAccumulator::finish),
+            dinucleotides: self.dinucleotides,
+            kmers: self.kmers.map(KmerAccumulator::finish),
+            length_histogram,
+            nx_curve,
+            contiguity_curve,
+            lorenz_curve,
+            long_reads,
+            cumulative_lengths,
+            groups: self.groups.map(GroupAccumulator::finish),
+            longest_sequences: self.longest_sequences.map(TopSequences::finish),
+        }
+    }
+}
+
+impl LengthSummary {
+    /// Compute the statistics of the given non-empty lists of sequence lengths.
+    /// The lists are sorted in the process.
+    pub fn new(
+        sequence_lengths: &mut [usize],
+        sequence_lengths_without_ns: &mut [usize],
+        gc: GcSummary,
+        additional_percentiles: &[u8],
+    ) -> Self {
+        sequence_lengths.sort_unstable_by(|a, b| b.cmp(a));
+        sequence_lengths_without_ns.sort_unstable_by(|a, b| b.cmp(a));
+        let with_ns = NxSummary::new(sequence_lengths, additional_percentiles);
+        let without_ns = NxSummary::new(sequence_lengths_without_ns, additional_percentiles);
+
+        let top_fractions = top_fractions(sequence_lengths, with_ns.total_length);
+        let length_percentiles = LENGTH_PERCENTILES
+            .iter()
+            .map(|&percentile| LengthPercentile {
+                percentile,
+                length: length_percentile(sequence_lengths, percentile),
+            })
+            .collect();
+        let gini_coefficient = gini_coefficient(sequence_lengths, with_ns.total_length);
+
+        let n_bases = with_ns.total_length - without_ns.total_length;
+        // The total length is never zero, since empty sequences are excluded.
+        let n_percent = n_bases as f64 / with_ns.total_length as f64 * 100.0;
+
+        Self {
+            n_bases,
+            n_percent,
+            non_n_percent: 100.0 - n_percent,
+            gc,
+            with_ns,
+            without_ns,
+            top_fractions,
+            length_percentiles,
+            gini_coefficient,
+        }
+    }
+}
+
+/// Compute the [`TOP_FRACTIONS`] of the given non-empty lengths, which must be sorted in descending order and sum up to `sum`.
+fn top_fractions(sorted_sequence_lengths: &[usize], sum: u64) -> Vec<TopFraction> {
+    let mut covered_bases = 0;
+    let mut sequence_count = 0;
+    TOP_FRACTIONS
+        .iter()
+        .map(|&sequence_percent| {
+            let required_sequence_count =
+                ((sorted_sequence_lengths.len() as u64 * u64::from(sequence_percent) + 99) / 100)
+                    as usize;
+            covered_bases +=
+                total_length(&sorted_sequence_lengths[sequence_count..required_sequence_count]);
+            sequence_count = required_sequence_count;
+            TopFraction {
+                sequence_percent,
+                sequence_count,
+                bases_percent: if sum == 0 {
+                    0.0
+                } else {
+                    covered_bases as f64 / sum as f64 * 100.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Accumulates the GC content of sequences into a [`GcSummary`].
+#[derive(Debug, Clone, Default)]
+struct GcAccumulator {
+    gc_count: u64,
+    len_without_ns: u64,
+    min_gc_percent: f64,
+    max_gc_percent: f64,
+    gc_percent_sum: f64,
+    sequence_count: usize,
+    undefined_gc_count: usize,
+}
+
+impl GcAccumulator {
+    fn add(&mut self, gc_count: usize, len_without_ns: usize) {
+        let gc_percent = gc_percent(gc_count as u64, len_without_ns as u64);
+        if self.sequence_count == 0 {
+            self.min_gc_percent = gc_percent;
+            self.max_gc_percent = gc_percent;
+        } else {
+            self.min_gc_percent = self.min_gc_percent.min(gc_percent);
+            self.max_gc_percent = self.max_gc_percent.max(gc_percent);
+        }
+
+        self.gc_count += gc_count as u64;
+        self.len_without_ns += len_without_ns as u64;
+        self.gc_percent_sum += gc_percent;
+        self.sequence_count += 1;
+        if len_without_ns == 0 {
+            self.undefined_gc_count += 1;
+        }
+    }
+
+    /// Add all sequences accumulated in `other` to `self`.
+    fn extend(&mut self, other: &Self) {
+        if other.sequence_count == 0 {
+            return;
+        }
+        if self.sequence_count == 0 {
+            self.min_gc_percent = other.min_gc_percent;
+            self.max_gc_percent = other.max_gc_percent;
+        } else {
+            self.min_gc_percent = self.min_gc_percent.min(other.min_gc_percent);
+            self.max_gc_percent = self.max_gc_percent.max(other.max_gc_percent);
+        }
+
+        self.gc_count += other.gc_count;
+        self.len_without_ns += other.len_without_ns;
+        self.gc_percent_sum += other.gc_percent_sum;
+        self.sequence_count += other.sequence_count;
+        self.undefined_gc_count += other.undefined_gc_count;
+    }
+
+    fn finish(self) -> GcSummary {
+        GcSummary {
+            gc_percent: gc_percent(self.gc_count, self.len_without_ns),
+            min_gc_percent: self.min_gc_percent,
+            max_gc_percent: self.max_gc_percent,
+            mean_gc_percent: self.gc_percent_sum / self.sequence_count as f64,
+            undefined_gc_count: self.undefined_gc_count,
+        }
+    }
+}
+
+/// Compute the GC percentage relative to the number of non-N characters, or 0% if there are none.
+pub fn gc_percent(gc_count: u64, len_without_ns: u64) -> f64 {
+    if len_without_ns == 0 {
+        0.0
+    } else {
+        gc_count as f64 / len_without_ns as f64 * 100.0
+    }
+}
+
+impl NxSummary {
+    /// Compute the statistics of the given non-empty list of sequence lengths,
+    /// which must be sorted in descending order.
+    pub fn new(sorted_sequence_lengths: &[usize], additional_percentiles: &[u8]) -> Self {
+        let length = total_length(sorted_sequence_lengths);
+        let (n50, l50) = nx_lx(sorted_sequence_lengths, length, |l| l / 2);
+        let (n75, l75) = nx_lx(sorted_sequence_lengths, length, |l| percentage_of(l, 75));
+        let (n90, l90) = nx_lx(sorted_sequence_lengths, length, |l| percentage_of(l, 90));
+
+        let additional_percentiles = additional_percentiles
+            .iter()
+            .copied()
+            .filter(|percentile| ![50, 75, 90].contains(percentile))
+            .map(|percentile| {
+                let (nx, lx) = nx_lx(sorted_sequence_lengths, length, |l| {
+                    percentage_of(l, percentile)
+                });
+                PercentileSummary { percentile, nx, lx }
+            })
+            .collect();
+
+        let mean_len = length as f64 / sorted_sequence_lengths.len() as f64;
+        let std_dev_len = std_dev(sorted_sequence_lengths, mean_len);
+        let aun = aun(sorted_sequence_lengths, length);
+
+        Self {
+            total_length: length,
+            n50,
+            n75,
+            n90,
+            l50,
+            l75,
+            l90,
+            additional_percentiles,
+            aun,
+            e_size: aun,
+            mean_len,
+            median_len: median(sorted_sequence_lengths),
+            std_dev_len,
+            cv_len: if mean_len == 0.0 {
+                0.0
+            } else {
+                std_dev_len / mean_len * 100.0
+            },
+            max_len: *sorted_sequence_lengths.first().unwrap(),
+            min_len: *sorted_sequence_lengths.last().unwrap(),
+        }
+    }
+}
+
+/// The sum of the given lengths, which may exceed `usize::MAX` on 32-bit targets.
+pub fn total_length(lengths: &[usize]) -> u64 {
+    lengths.iter().map(|&len| len as u64).sum()
+}
+
+/// The given percentage of the given number of bases, rounded down.
+/// The intermediate product is computed with 128 bits, such that it does not overflow for any number of bases.
+pub fn percentage_of(bases: u64, percentage: u8) -> u64 {
+    (u128::from(bases) * u128::from(percentage) / 100) as u64
+}
+
+/// Compute the Nx metric of the given lengths, which must be sorted in descending order and sum up to `sum`.
+/// The `percentile` function maps the total length to the number of bases that need to be covered.
+pub fn nx(lengths: &[usize], sum: u64, percentile: impl FnOnce(u64) -> u64) -> usize {
+    nx_lx(lengths, sum, percentile).0
+}
+
+/// Compute the Nx and Lx metrics of the given lengths, which must be sorted in descending order and sum up to `sum`.
+/// The Lx metric is the number of sequences needed to cover the required number of bases.
+/// The `percentile` function maps the total length to the number of bases that need to be covered.
+pub fn nx_lx(lengths: &[usize], sum: u64, percentile: impl FnOnce(u64) -> u64) -> (usize, usize) {
+    debug_assert!(!lengths.is_empty());
+    debug_assert!(lengths.windows(2).all(|w| w[0] >= w[1]));
+    debug_assert_eq!(total_length(lengths), sum);
+
+    let required_covered_bases = percentile(sum);
+    debug_assert!(required_covered_bases <= sum);
+
+    let mut sum = 0;
+    for (index, len) in lengths.iter().copied().enumerate() {
+        sum += len as u64;
+        if sum >= required_covered_bases {
+            return (len, index + 1);
+        }
+    }
+
+    // The loop returns at the latest for the last length, since the lengths sum up to at least the required bases.
+    // Callers never pass an empty list, since the summaries are only computed if there are sequences.
+    unreachable!()
+}
+
+/// Compute the Nx metrics of the given lengths for multiple percentiles in a single pass.
+/// The lengths must be sorted in descending order and sum up to `sum`, and the percentiles must be sorted in ascending order.
+pub fn compute_all_nx(sorted: &[usize], sum: u64, percentiles: &[u8]) -> Vec<usize> {
+    debug_assert!(!sorted.is_empty());
+    debug_assert!(sorted.windows(2).all(|w| w[0] >= w[1]));
+    debug_assert_eq!(total_length(sorted), sum);
+    debug_assert!(percentiles.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut nxs = Vec::with_capacity(percentiles.len());
+    let mut lengths = sorted.iter().copied();
+    let mut nx = None;
+    let mut covered_bases = 0;
+    for &percentile in percentiles {
+        let required_covered_bases = percentage_of(sum, percentile);
+        // Like in `nx_lx`, at least one length is needed, even if no bases need to be covered.
+        while nx.is_none() || covered_bases < required_covered_bases {
+            // The lengths sum up to at least the required bases, so they do not run out before the threshold is crossed.
+            let len = lengths.next().unwrap();
+            covered_bases += len as u64;
+            nx = Some(len);
+        }
+        nxs.push(nx.unwrap());
+    }
+    nxs
+}
+
+/// Compute the median of the given non-empty sorted lengths.
+/// For an even number of lengths, this is the mean of the two middle lengths.
+pub fn median(sorted_lengths: &[usize]) -> f64 {
+    let middle = sorted_lengths.len() / 2;
+    if sorted_lengths.len() % 2 == 1 {
+        sorted_lengths[middle] as f64
+    } else {
+        (sorted_lengths[middle - 1] as f64 + sorted_lengths[middle] as f64) / 2.0
+    }
+}
+
+/// Compute the given percentile of the given non-empty lengths, which must be sorted in descending order.
+/// The percentile refers to the lengths in ascending order, and is interpolated linearly between adjacent lengths.
+pub fn length_percentile(sorted_lengths: &[usize], percentile: u8) -> f64 {
+    let position = ((sorted_lengths.len() - 1) * usize::from(percentile)) as f64 / 100.0;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let last = sorted_lengths.len() - 1;
+    let lower_len = sorted_lengths[last - lower] as f64;
+    let upper_len = sorted_lengths[last - upper] as f64;
+    lower_len + (upper_len - lower_len) * (position - lower as f64)
+}
+
+/// Compute the Gini coefficient of the given non-empty lengths, which must be sorted in descending order and sum up to `sum`.
+/// It is 0 if all lengths are equal, and approaches 1 if a single sequence contains almost all bases.
+pub fn gini_coefficient(sorted_lengths: &[usize], sum: u64) -> f64 {
+    if sum == 0 {
+        return 0.0;
+    }
+    let count = sorted_lengths.len() as u128;
+    // The lengths are weighted by their 1-based rank in ascending order.
+    let weighted_sum: u128 = sorted_lengths
+        .iter()
+        .zip((1..=count).rev())
+        .map(|(&len, rank)| rank * len as u128)
+        .sum();
+    let sum = u128::from(sum);
+    (2 * weighted_sum - (count + 1) * sum) as f64 / (count * sum) as f64
+}
+
+/// Compute the population standard deviation of the given non-empty lengths with the given mean.
+pub fn std_dev(lengths: &[usize], mean: f64) -> f64 {
+    let squared_deviation_sum: f64 = lengths
+        .iter()
+        .map(|len| {
+            let deviation = *len as f64 - mean;
+            deviation * deviation
+        })
+        .sum();
+    (squared_deviation_sum / lengths.len() as f64).sqrt()
+}
+
+/// Compute the area under the Nx curve of the given lengths, which must be sorted in descending order and sum up to `total_length`.
+/// This is the sum of the squared lengths divided by the total length.
+pub fn aun(sorted_lengths: &[usize], total_length: u64) -> f64 {
+    debug_assert!(sorted_lengths.windows(2).all(|w| w[0] >= w[1]));
+    debug_assert_eq!(self::total_length(sorted_lengths), total_length);
+
+    if total_length == 0 {
+        return 0.0;
+    }
+
+    let squared_sum: u128 = sorted_lengths
+        .iter()
+        .map(|len| (*len as u128) * (*len as u128))
+        .sum();
+    squared_sum as f64 / total_length as f64
+}
+
+/// Statistics about a single sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequenceStatistics {
+    /// The length of the sequence.
+    pub len: usize,
+    /// The length of the homopolymer-compressed sequence.
+    pub hoco_len: usize,
+    /// The length of the sequence without Ns.
+    pub len_without_ns: usize,
+    /// The length of the homopolymer-compressed sequence without Ns.
+    pub hoco_len_without_ns: usize,
+    /// The number of G and C characters, ignoring case.
+    pub gc_count: usize,
+    /// The number of G and C characters in the homopolymer-compressed sequence, ignoring case.
+    pub hoco_gc_count: usize,
+    /// The number of lowercase a, c, g and t characters, which mark soft-masked bases.
+    pub soft_masked_len: usize,
+    /// The number of IUPAC ambiguity codes other than N, ignoring case.
+    pub ambiguous_count: usize,
+    /// The number of homopolymer runs of each length, indexed by the run length, excluding runs of Ns.
+    pub homopolymer_run_counts: Vec<usize>,
+    /// The number of A, C, G and T characters, ignoring case.
+    pub base_counts: [usize; 4],
+    /// The number of C characters followed by a G character, ignoring case.
+    pub cpg_count: usize,
+    /// The number of occurrences of each base.
+    pub composition: BaseComposition,
+}
+
+impl SequenceStatistics {
+    /// Compute the statistics of the given sequence.
+    /// Line terminators are ignored, such that multiline fasta sequences with `\n` or `\r\n` line endings can be passed directly.
+    pub fn new(sequence: &[u8]) -> Self {
+        if sequence.is_empty() {
+            return Self {
+                len: 0,
+                hoco_len: 0,
+                len_without_ns: 0,
+                hoco_len_without_ns: 0,
+                gc_count: 0,
+                hoco_gc_count: 0,
+                soft_masked_len: 0,
+                ambiguous_count: 0,
+                homopolymer_run_counts: Vec::new(),
+                base_counts: [0; 4],
+                cpg_count: 0,
+                composition: BaseComposition::default(),
+            };
+        }
+
+        let is_n = |b| b == b'n' || b == b'N';
+        let is_gc = |b| matches!(b, b'g' | b'G' | b'c' | b'C');
+        let base_index = |b: u8| match b.to_ascii_uppercase() {
+            b'A' => Some(0),
+            b'C' => Some(1),
+            b'G' => Some(2),
+            b'T' => Some(3),
+            _ => None,
+        };
+        let is_soft_masked = |b| matches!(b, b'a' | b'c' | b'g' | b't');
+        let is_ambiguous = |b: u8| {
+            matches!(
+                b.to_ascii_uppercase(),
+                b'W' | b'S' | b'M' | b'K' | b'R' | b'Y' | b'B' | b'D' | b'H' | b'V'
+            )
+        };
+        let mut len = 1;
+        let mut hoco_len = 1;
+        let mut last_byte = *sequence.first().unwrap();
+        let mut ns = if is_n(last_byte) { 1 } else { 0 };
+        let mut hoco_ns = ns;
+        let mut base_counts = [0; 4];
+        if let Some(index) = base_index(last_byte) {
+            base_counts[index] += 1;
+        }
+        let mut hoco_gc_count = if is_gc(last_byte) { 1 } else { 0 };
+        let mut soft_masked_len = if is_soft_masked(last_byte) { 1 } else { 0 };
+        let mut ambiguous_count = if is_ambiguous(last_byte) { 1 } else { 0 };
+        let mut cpg_count = 0;
+        let mut previous_byte = last_byte;
+        let mut homopolymer_run_counts = Vec::new();
+        let mut run_length = 1;
+        let mut count_run = |run_byte, run_length: usize| {
+            if !is_n(run_byte) {
+                if homopolymer_run_counts.len() <= run_length {
+                    homopolymer_run_counts.resize(run_length + 1, 0);
+                }
+                homopolymer_run_counts[run_length] += 1;
+            }
+        };
+
+        for byte in sequence.iter().skip(1).copied() {
+            if byte == b'\n' || byte == b'\r' {
+                continue;
+            }
+
+            len += 1;
+            if is_n(byte) {
+                ns += 1;
+            }
+            if let Some(index) = base_index(byte) {
+                base_counts[index] += 1;
+            }
+            if is_soft_masked(byte) {
+                soft_masked_len += 1;
+            }
+            if is_ambiguous(byte) {
+                ambiguous_count += 1;
+            }
+            if previous_byte.eq_ignore_ascii_case(&b'C') && byte.eq_ignore_ascii_case(&b'G') {
+                cpg_count += 1;
+            }
+            previous_byte = byte;
+
+            // Soft-masking does not interrupt a homopolymer.
+            if byte.eq_ignore_ascii_case(&last_byte) {
+                run_length += 1;
+            } else {
+                count_run(last_byte, run_length);
+                run_length = 1;
+                last_byte = byte;
+                hoco_len += 1;
+
+                if is_n(last_byte) {
+                    hoco_ns += 1;
+                }
+                if is_gc(last_byte) {
+                    hoco_gc_count += 1;
+                }
+            }
+        }
+        count_run(last_byte, run_length);
+
+        Self {
+            len,
+            hoco_len,
+            len_without_ns: len - ns,
+            hoco_len_without_ns: hoco_len - hoco_ns,
+            gc_count: base_counts[1] + base_counts[2],
+            hoco_gc_count,
+            soft_masked_len,
+            ambiguous_count,
+            homopolymer_run_counts,
+            base_counts,
+            cpg_count,
+            composition: BaseComposition::new(sequence),
+        }
+    }
+
+    /// The GC percentage of the sequence, relative to the number of non-N characters.
+    pub fn gc_percent(&self) -> f64 {
+        gc_percent(self.gc_count as u64, self.len_without_ns as u64)
+    }
+
+    /// The GC percentage of the homopolymer-compressed sequence, relative to the number of non-N characters.
+    pub fn hoco_gc_percent(&self) -> f64 {
+        gc_percent(self.hoco_gc_count as u64, self.hoco_len_without_ns as u64)
+    }
+
+    /// The Shannon entropy of the distribution of A, C, G and T in bits, between 0 and 2.
+    /// Other characters are ignored, and the entropy of a sequence without any of these bases is 0.
+    pub fn entropy(&self) -> f64 {
+        let total: usize = self.base_counts.iter().sum();
+        self.base_counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::composition::BaseComposition;
+    use crate::error::FastxStatisticsError;
+    use crate::input::InputProgress;
+    use crate::{
+        aun, basic_statistics, basic_statistics_with_callback, compute_all_nx, gini_coefficient,
+        length_percentile, median, multi_input_statistics_with_callback, nx, std_dev, NxSummary,
+        PercentileSummary, SequenceStatistics, StatisticsOptions, CHUNK_RECORD_COUNT,
+    };
+    use regex::Regex;
+    use std::collections::HashSet;
+
+    pub(crate) const FASTA: &[u8] = b">1\nAAAGCGCTNNNNNTTCGAGGA\n>2\nGTGCTAGCGGGCC\nNCCCTTTTTTTTTTTT\n>3\nACGCTTATG\n>4\nGCTAACTGAGNNNNAAATTTCGGG\n>5\nAAAGGGCCTTCC\n";
+
+    #[test]
+    fn test() {
+        let options = StatisticsOptions {
+            additional_percentiles: vec![25, 90],
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+
+        assert_eq!(summary.record_count, 5);
+        let lengths = summary.lengths.unwrap();
+        assert_eq!(lengths.n_bases, 10);
+        assert_eq!(
+            lengths.with_ns,
+            NxSummary {
+                total_length: 95,
+                n50: 24,
+                n75: 21,
+                n90: 12,
+                l50: 2,
+                l75: 3,
+                l90: 4,
+                additional_percentiles: vec![PercentileSummary {
+                    percentile: 25,
+                    nx: 29,
+                    lx: 1,
+                }],
+                aun: 2083.0 / 95.0,
+                e_size: 2083.0 / 95.0,
+                mean_len: 19.0,
+                median_len: 21.0,
+                std_dev_len: std_dev(&[29, 24, 21, 12, 9], 19.0),
+                cv_len: std_dev(&[29, 24, 21, 12, 9], 19.0) / 19.0 * 100.0,
+                max_len: 29,
+                min_len: 9,
+            }
+        );
+        assert_eq!(
+            lengths.without_ns,
+            NxSummary {
+                total_length: 85,
+                n50: 20,
+                n75: 16,
+                n90: 12,
+                l50: 2,
+                l75: 3,
+                l90: 4,
+                additional_percentiles: vec![PercentileSummary {
+                    percentile: 25,
+                    nx: 28,
+                    lx: 1,
+                }],
+                aun: 1665.0 / 85.0,
+                e_size: 1665.0 / 85.0,
+                mean_len: 17.0,
+                median_len: 16.0,
+                std_dev_len: std_dev(&[28, 20, 16, 12, 9], 17.0),
+                cv_len: std_dev(&[28, 20, 16, 12, 9], 17.0) / 17.0 * 100.0,
+                max_len: 28,
+                min_len: 9,
+            }
+        );
+        let top_fractions: Vec<_> = lengths
+            .top_fractions
+            .iter()
+            .map(|top_fraction| (top_fraction.sequence_count, top_fraction.bases_percent))
+            .collect();
+        assert_eq!(
+            top_fractions,
+            vec![
+                (1, 29.0 / 95.0 * 100.0),
+                (1, 29.0 / 95.0 * 100.0),
+                (1, 29.0 / 95.0 * 100.0),
+                (2, 53.0 / 95.0 * 100.0),
+                (3, 74.0 / 95.0 * 100.0),
+            ]
+        );
+
+        let hoco_lengths = summary.hoco_lengths.unwrap();
+        assert_eq!(hoco_lengths.n_bases, 3);
+        assert_eq!(
+            hoco_lengths.with_ns,
+            NxSummary {
+                total_length: 53,
+                n50: 13,
+                n75: 13,
+                n90: 8,
+                l50: 2,
+                l75: 3,
+                l90: 4,
+                additional_percentiles: vec![PercentileSummary {
+                    percentile: 25,
+                    nx: 14,
+                    lx: 1,
+                }],
+                aun: 623.0 / 53.0,
+                e_size: 623.0 / 53.0,
+                mean_len: 10.6,
+                median_len: 13.0,
+                std_dev_len: std_dev(&[14, 13, 13, 8, 5], 10.6),
+                cv_len: std_dev(&[14, 13, 13, 8, 5], 10.6) / 10.6 * 100.0,
+                max_len: 14,
+                min_len: 5,
+            }
+        );
+        assert_eq!(
+            hoco_lengths.without_ns,
+            NxSummary {
+                total_length: 50,
+                n50: 12,
+                n75: 12,
+                n90: 8,
+                l50: 2,
+                l75: 3,
+                l90: 4,
+                additional_percentiles: vec![PercentileSummary {
+                    percentile: 25,
+                    nx: 13,
+                    lx: 1,
+                }],
+                aun: 546.0 / 50.0,
+                e_size: 546.0 / 50.0,
+                mean_len: 10.0,
+                median_len: 12.0,
+                std_dev_len: std_dev(&[13, 12, 12, 8, 5], 10.0),
+                cv_len: std_dev(&[13, 12, 12, 8, 5], 10.0) / 10.0 * 100.0,
+                max_len: 13,
+                min_len: 5,
+            }
+        );
+        assert_eq!(
+            hoco_lengths.top_fractions[4].bases_percent,
+            40.0 / 53.0 * 100.0
+        );
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[5]), 5.0);
+        assert_eq!(median(&[5, 3, 2]), 3.0);
+        assert_eq!(median(&[5, 4, 1, 1]), 2.5);
+    }
+
+    #[test]
+    fn test_length_percentile() {
+        let lengths: Vec<_> = (0..=10).rev().map(|len| len * 10).collect();
+        let percentiles: Vec<_> = [10, 25, 50, 75, 90]
+            .iter()
+            .map(|&percentile| length_percentile(&lengths, percentile))
+            .collect();
+        assert_eq!(percentiles, [10.0, 25.0, 50.0, 75.0, 90.0]);
+        assert_eq!(length_percentile(&[7], 90), 7.0);
+        assert_eq!(length_percentile(&[5, 4, 1, 1], 50), median(&[5, 4, 1, 1]));
+    }
+
+    #[test]
+    fn test_gini_coefficient() {
+        assert_eq!(gini_coefficient(&[5, 5, 5], 15), 0.0);
+        assert_eq!(gini_coefficient(&[7], 7), 0.0);
+        assert_eq!(gini_coefficient(&[4, 0, 0, 0], 4), 0.75);
+        // 2 * (1 * 1 + 2 * 2 + 3 * 3) - 4 * 6 = 4
+        assert_eq!(gini_coefficient(&[3, 2, 1], 6), 4.0 / 18.0);
+        assert_eq!(gini_coefficient(&[0, 0], 0), 0.0);
+    }
+
+    #[test]
+    fn test_std_dev() {
+        assert_eq!(std_dev(&[9, 7, 5, 5, 4, 4, 4, 2], 5.0), 2.0);
+        assert_eq!(std_dev(&[3, 3], 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_aun() {
+        assert_eq!(aun(&[], 0), 0.0);
+        assert_eq!(aun(&[4, 2, 2], 8), 3.0);
+        assert_eq!(aun(&[29, 24, 21, 12, 9], 95), 2083.0 / 95.0);
+    }
+
+    #[test]
+    fn test_compute_all_nx() {
+        let lengths = [29, 24, 21, 12, 9];
+        let percentiles: Vec<_> = (0..=100).collect();
+        let nxs = compute_all_nx(&lengths, 95, &percentiles);
+        for (percentile, nx_value) in percentiles.into_iter().zip(nxs) {
+            assert_eq!(
+                nx_value,
+                nx(&lengths, 95, |l| l * u64::from(percentile) / 100)
+            );
+        }
+        assert_eq!(compute_all_nx(&lengths, 95, &[50, 75, 90]), [24, 21, 12]);
+    }
+
+    #[test]
+    fn test_large_total_length() {
+        // The total length exceeds `u32::MAX`, so it would overflow a 32-bit `usize`.
+        let max = u32::MAX as usize;
+        let summary = NxSummary::new(&[max, max, 10], &[100]);
+        assert_eq!(summary.total_length, 2 * u64::from(u32::MAX) + 10);
+        assert_eq!((summary.n50, summary.l50), (max, 2));
+        assert_eq!((summary.n90, summary.l90), (max, 2));
+        assert_eq!(
+            summary.additional_percentiles,
+            [PercentileSummary {
+                percentile: 100,
+                nx: 10,
+                lx: 3,
+            }]
+        );
+        assert_eq!(
+            compute_all_nx(&[max, max, 10], summary.total_length, &[50, 100]),
+            [max, 10]
+        );
+    }
+
+    #[test]
+    fn test_gc() {
+        let summary =
+            basic_statistics(FASTA, FASTA.len() as u64, &StatisticsOptions::default()).unwrap();
+        let gc = summary.lengths.unwrap().gc;
+        assert!((gc.gc_percent - 41.0 / 85.0 * 100.0).abs() < 1e-9);
+        assert!((gc.min_gc_percent - 4.0 / 9.0 * 100.0).abs() < 1e-9);
+        assert!((gc.max_gc_percent - 7.0 / 12.0 * 100.0).abs() < 1e-9);
+        assert_eq!(gc.undefined_gc_count, 0);
+
+        let fasta = b">1\nNNNN\n>2\nggAT\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let gc = summary.lengths.unwrap().gc;
+        assert_eq!(gc.gc_percent, 50.0);
+        assert_eq!(gc.min_gc_percent, 0.0);
+        assert_eq!(gc.mean_gc_percent, 25.0);
+        assert_eq!(gc.undefined_gc_count, 1);
+        let hoco_gc = summary.hoco_lengths.unwrap().gc;
+        assert!((hoco_gc.gc_percent - 100.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_filter_ids() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                filter_ids: vec!["2".to_owned()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 4);
+        assert_eq!(summary.excluded.by_id, Some(1));
+        assert_eq!(summary.excluded.too_short, None);
+        assert_eq!(summary.lengths.unwrap().with_ns.total_length, 66);
+    }
+
+    #[test]
+    fn test_filter_id_regexes() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                filter_ids: vec!["1".to_owned()],
+                filter_id_regexes: vec![Regex::new("^[12]$").unwrap(), Regex::new("5").unwrap()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.excluded.by_id, Some(1));
+        assert_eq!(summary.excluded.by_id_regex, Some(2));
+    }
+
+    #[test]
+    fn test_id_sets() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                filter_ids: vec!["1".to_owned()],
+                exclude_ids: HashSet::from(["2".to_owned()]),
+                include_ids: Some(HashSet::from([
+                    "1".to_owned(),
+                    "2".to_owned(),
+                    "3".to_owned(),
+                ])),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 1);
+        assert_eq!(summary.excluded.by_id, Some(2));
+        assert_eq!(summary.excluded.not_included, Some(2));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let fasta = b">Chr1\nACGT\n>CHR2\nAC\n>chr3 Chromosome\nA\n>Chr4\nA\n>chr5\nA\n";
+        let options = StatisticsOptions {
+            filter_ids: vec!["CHR1".to_owned()],
+            filter_id_regexes: vec![Regex::new("^Chr2$").unwrap()],
+            exclude_ids: ["cHr5".to_owned()].into_iter().collect(),
+            filter_descriptions: vec![Regex::new("chromosome").unwrap()],
+            ignore_case: true,
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.excluded.by_id, Some(2));
+        assert_eq!(summary.excluded.by_id_regex, Some(1));
+        assert_eq!(summary.excluded.by_description, Some(0));
+
+        let options = StatisticsOptions {
+            include_ids: Some(["CHR3".to_owned()].into_iter().collect()),
+            ..options
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 1);
+        assert_eq!(summary.excluded.not_included, Some(1));
+
+        let options = StatisticsOptions {
+            ignore_case: false,
+            include_ids: None,
+            ..options
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 5);
+    }
+
+    #[test]
+    fn test_filter_descriptions() {
+        let fasta = b">1 chromosome 1\nACGT\n>2 hypothetical protein\nAC\n>3\nA\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions {
+                filter_descriptions: vec![
+                    Regex::new("hypothetical").unwrap(),
+                    Regex::new("^3$").unwrap(),
+                ],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 1);
+        assert_eq!(summary.excluded.by_description, Some(2));
+    }
+
+    #[test]
+    fn test_min_length() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                min_length: Some(21),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.excluded.too_short, Some(2));
+        assert_eq!(summary.lengths.unwrap().with_ns.total_length, 74);
+        assert_eq!(summary.hoco_lengths.unwrap().with_ns.min_len, 13);
+    }
+
+    #[test]
+    fn test_no_hoco() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                hoco: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 5);
+        assert!(summary.lengths.is_some());
+        assert_eq!(summary.hoco_lengths, None);
+    }
+
+    #[test]
+    fn test_gc_filter() {
+        let fasta = b">1\nAAAA\n>2\nACGT\n>3\nGGGC\n>4\nNNNN\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions {
+                min_gc: Some(30.0),
+                max_gc: Some(70.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.excluded.by_gc, Some(2));
+        assert_eq!(summary.lengths.unwrap().n_bases, 4);
+    }
+
+    #[test]
+    fn test_n_fraction_filter() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                max_n_fraction: Some(0.2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 4);
+        assert_eq!(summary.excluded.by_n_fraction, Some(1));
+        assert_eq!(summary.lengths.unwrap().n_bases, 5);
+    }
+
+    #[test]
+    fn test_all_n() {
+        let fasta = b">1\nNNNN\n>2\nACGT\n>3\nnn\n";
+        let summary =
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &Default::default()).unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.all_n_count, 2);
+
+        let options = StatisticsOptions {
+            exclude_all_n: true,
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 1);
+        assert_eq!(summary.all_n_count, 0);
+        assert_eq!(summary.excluded.all_n, Some(2));
+        assert_eq!(summary.lengths.unwrap().with_ns.total_length, 4);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let crlf_fasta = String::from_utf8(FASTA.to_vec())
+            .unwrap()
+            .replace('\n', "\r\n");
+        let options = StatisticsOptions {
+            scaffold_n_threshold: 4,
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        let crlf_summary =
+            basic_statistics(crlf_fasta.as_bytes(), crlf_fasta.len() as u64, &options).unwrap();
+        assert_eq!(crlf_summary, summary);
+        assert_eq!(crlf_summary.base_composition.other, 0);
+    }
+
+    #[test]
+    fn test_complexity_filter() {
+        let fasta = b">1\nAAAAAAAA\n>2\nACGTACGT\n>3\nAAAAAAAT\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions {
+                min_complexity: Some(0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.excluded.low_complexity, Some(1));
+    }
+
+    #[test]
+    fn test_length_window() {
+        let summary = basic_statistics(
+            FASTA,
+            FASTA.len() as u64,
+            &StatisticsOptions {
+                min_length: Some(10),
+                max_length: Some(24),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.excluded.too_short, Some(1));
+        assert_eq!(summary.excluded.too_long, Some(1));
+        assert_eq!(summary.lengths.unwrap().with_ns.total_length, 57);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialise() {
+        let summary =
+            basic_statistics(FASTA, FASTA.len() as u64, &StatisticsOptions::default()).unwrap();
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["record_count"], 5);
+        assert_eq!(json["n_bases"], 10);
+        assert_eq!(json["n50"], 24);
+        assert_eq!(json["e_size"], 2083.0 / 95.0);
+        assert_eq!(json["without_ns"]["n50"], 20);
+        assert_eq!(json["hoco"]["n50"], 13);
+
+        let deserialised: crate::AssemblySummary = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialised, summary);
+    }
+
+    #[test]
+    fn test_multiple_inputs() {
+        let split = FASTA.windows(2).position(|window| window == b">4").unwrap();
+        let (first, second) = FASTA.split_at(split);
+        let options = StatisticsOptions::default();
+        let summary = multi_input_statistics_with_callback(
+            vec![
+                (first, InputProgress::from(first.len() as u64)),
+                (second, InputProgress::from(second.len() as u64)),
+            ],
+            &options,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        assert_eq!(summary.inputs.len(), 2);
+        assert_eq!(
+            summary.inputs[0],
+            basic_statistics(first, first.len() as u64, &options).unwrap()
+        );
+        assert_eq!(summary.inputs[1].record_count, 2);
+        assert_eq!(
+            summary.combined,
+            basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_callback_error_stops_reading() {
+        let fasta = b">1\nACGT\n".repeat(10 * CHUNK_RECORD_COUNT);
+        let mut callback_count = 0;
+        let result = basic_statistics_with_callback(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+            |_| {
+                callback_count += 1;
+                Err(FastxStatisticsError::Other("stop".to_owned()))
+            },
+        );
+        assert!(matches!(result, Err(FastxStatisticsError::Other(message)) if message == "stop"));
+        assert_eq!(callback_count, 1);
+    }
+
+    #[test]
+    fn test_thread_count() {
+        // Use enough records to span multiple chunks.
+        let mut fasta = Vec::new();
+        for index in 0..2500 {
+            fasta.extend_from_slice(format!(">{index}\n").as_bytes());
+            fasta.extend(
+                b"ACGGTTTNNAC"
+                    .iter()
+                    .cycle()
+                    .skip(index % 7)
+                    .take(index % 113),
+            );
+            fasta.push(b'\n');
+        }
+        let statistics = |thread_count| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(thread_count)
+                .build()
+                .unwrap()
+                .install(|| {
+                    basic_statistics(
+                        fasta.as_slice(),
+                        fasta.len() as u64,
+                        &StatisticsOptions::default(),
+                    )
+                    .unwrap()
+                })
+        };
+
+        let single_threaded = statistics(1);
+        assert_eq!(
+            single_threaded.record_count + single_threaded.empty_sequence_count,
+            2500
+        );
+        assert_eq!(statistics(4), single_threaded);
+    }
+
+    #[test]
+    fn test_empty() {
+        let summary = basic_statistics(b"".as_slice(), 0, &StatisticsOptions::default()).unwrap();
+        assert_eq!(summary.record_count, 0);
+        assert_eq!(summary.lengths, None);
+        assert_eq!(summary.hoco_lengths, None);
+    }
+
+    #[test]
+    fn test_limit() {
+        let options = StatisticsOptions {
+            limit: Some(3),
+            filter_ids: vec!["2".to_owned()],
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.record_limit, Some(3));
+
+        let options = StatisticsOptions {
+            limit: Some(5),
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 5);
+        assert_eq!(summary.record_limit, None);
+    }
+
+    #[test]
+    fn test_skip() {
+        let options = StatisticsOptions {
+            skip: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let mut ids = Vec::new();
+        let summary =
+            basic_statistics_with_callback(FASTA, FASTA.len() as u64, &options, |record| {
+                ids.push(record.id.to_owned());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(ids, ["2", "3"]);
+    }
+
+    #[test]
+    fn test_subsample() {
+        let options = StatisticsOptions {
+            subsample: Some(0.5),
+            seed: 42,
+            filter_ids: vec!["1".to_owned()],
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        let subsample = summary.subsample.as_ref().unwrap();
+        assert_eq!(subsample.seen_count, 5);
+        assert!(subsample.sampled_count <= 5);
+        assert!(summary.record_count <= subsample.sampled_count);
+        assert_eq!(
+            basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap(),
+            summary
+        );
+
+        let options = StatisticsOptions {
+            subsample: Some(1.0),
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 5);
+        assert_eq!(summary.subsample.unwrap().sampled_count, 5);
+    }
+
+    #[test]
+    fn test_empty_sequences() {
+        let fasta = b">1\nACGT\n>2\n\n>3\nAC\n";
+        let summary =
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &Default::default()).unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.empty_sequence_count, 1);
+        assert_eq!(summary.lengths.unwrap().with_ns.min_len, 2);
+
+        let strict = StatisticsOptions {
+            strict: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &strict),
+            Err(FastxStatisticsError::EmptySequence(id)) if id == "2"
+        ));
+    }
+
+    #[test]
+    fn test_unequal_quality_length() {
+        let fastq = b"@1\nACGT\n+\nIIII\n@2\nACGT\n+\nII\n@3\nAC\n+\nIIII\n@4\nAC\n+\nII\n";
+        let summary =
+            basic_statistics(fastq.as_slice(), fastq.len() as u64, &Default::default()).unwrap();
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.unequal_quality_length_count, 2);
+        assert_eq!(summary.lengths.unwrap().with_ns.total_length, 6);
+
+        let strict = StatisticsOptions {
+            strict: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            basic_statistics(fastq.as_slice(), fastq.len() as u64, &strict),
+            Err(FastxStatisticsError::UnequalQualityLength {
+                id,
+                sequence_length: 4,
+                quality_length: 2,
+            }) if id == "2"
+        ));
+    }
+
+    #[test]
+    fn test_check_characters() {
+        let fasta = b">1\nACGT\nRYN-\n>2\nMKVL\n>3\nac\nE\n";
+        let summary =
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &Default::default()).unwrap();
+        assert_eq!(summary.invalid_character_count, None);
+
+        let options = StatisticsOptions {
+            check_characters: true,
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.invalid_character_count, Some(2));
+
+        let strict = StatisticsOptions {
+            strict: true,
+            ..options
+        };
+        assert!(matches!(
+            basic_statistics(fasta.as_slice(), fasta.len() as u64, &strict),
+            Err(FastxStatisticsError::InvalidCharacter {
+                id,
+                character: b'L',
+                position: 4,
+            }) if id == "2"
+        ));
+    }
+
+    #[test]
+    fn test_sequence_statistics() {
+        assert_eq!(
+            SequenceStatistics::new(b"AAGNNT\nTg"),
+            SequenceStatistics {
+                len: 8,
+                hoco_len: 5,
+                len_without_ns: 6,
+                hoco_len_without_ns: 4,
+                gc_count: 2,
+                hoco_gc_count: 2,
+                soft_masked_len: 1,
+                ambiguous_count: 0,
+                homopolymer_run_counts: vec![0, 2, 2],
+                base_counts: [2, 0, 2, 2],
+                cpg_count: 0,
+                composition: BaseComposition::new(b"AAGNNTTg"),
+            }
+        );
+        assert_eq!(
+            SequenceStatistics::new(b"AaacGtTTN"),
+            SequenceStatistics {
+                len: 9,
+                hoco_len: 5,
+                len_without_ns: 8,
+                hoco_len_without_ns: 4,
+                gc_count: 2,
+                hoco_gc_count: 2,
+                soft_masked_len: 4,
+                ambiguous_count: 0,
+                homopolymer_run_counts: vec![0, 2, 0, 2],
+                base_counts: [3, 1, 1, 3],
+                cpg_count: 1,
+                composition: BaseComposition::new(b"AaacGtTTN"),
+            }
+        );
+        assert_eq!(SequenceStatistics::new(b"ARyNwSUb").ambiguous_count, 5);
+        assert_eq!(SequenceStatistics::new(b"CGc\r\ngNCG").cpg_count, 3);
+        assert_eq!(SequenceStatistics::new(b"AAAA").entropy(), 0.0);
+        assert_eq!(SequenceStatistics::new(b"ACgtNacGT").entropy(), 2.0);
+        assert_eq!(SequenceStatistics::new(b"AANTT").entropy(), 1.0);
+        assert_eq!(SequenceStatistics::new(b"NN").entropy(), 0.0);
+    }
+}
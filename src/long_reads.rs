@@ -0,0 +1,135 @@
+//! Count the sequences that reach given length thresholds, e.g. the ultra-long reads of a nanopore run.
+
+use crate::total_length;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// The sequences that are at least as long as a threshold.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LengthThresholdSummary {
+    pub threshold: usize,
+    /// The number of sequences of at least the threshold length.
+    pub count: usize,
+    /// The percentage of all sequences that are at least the threshold length.
+    pub count_percent: f64,
+    /// The total length of the sequences of at least the threshold length.
+    pub total_length: u64,
+    /// The percentage of the total length of all sequences in sequences of at least the threshold length.
+    pub length_percent: f64,
+}
+
+/// Count the sequences of at least each of the thresholds, which are reported in ascending order.
+/// The lengths must be non-empty and sorted in descending order, and sum up to `sum`.
+pub fn length_thresholds(
+    sorted_sequence_lengths: &[usize],
+    sum: u64,
+    thresholds: &[usize],
+) -> Vec<LengthThresholdSummary> {
+    debug_assert!(!sorted_sequence_lengths.is_empty());
+    debug_assert!(sorted_sequence_lengths.windows(2).all(|w| w[0] >= w[1]));
+
+    let mut thresholds = thresholds.to_vec();
+    thresholds.sort_unstable();
+    thresholds.dedup();
+
+    // Visit the thresholds from the largest, such that the lengths of the sequences reaching a threshold
+    // can be added to those reaching the next larger one.
+    let mut summaries = Vec::with_capacity(thresholds.len());
+    let mut count = 0;
+    let mut covered_bases = 0;
+    for &threshold in thresholds.iter().rev() {
+        let threshold_count = sorted_sequence_lengths.partition_point(|&len| len >= threshold);
+        covered_bases += total_length(&sorted_sequence_lengths[count..threshold_count]);
+        count = threshold_count;
+        summaries.push(LengthThresholdSummary {
+            threshold,
+            count,
+            count_percent: count as f64 / sorted_sequence_lengths.len() as f64 * 100.0,
+            total_length: covered_bases,
+            length_percent: if sum == 0 {
+                0.0
+            } else {
+                covered_bases as f64 / sum as f64 * 100.0
+            },
+        });
+    }
+    summaries.reverse();
+    summaries
+}
+
+/// Write the given summaries as tab-separated data with a header,
+/// as in the yield by minimum length tables of nanopore run reports.
+/// The fractions are between 0 and 1, relative to the given number of sequences and their total length.
+pub fn write_cumulative_length_table(
+    summaries: &[LengthThresholdSummary],
+    sequence_count: usize,
+    total_length: u64,
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(
+        output,
+        "min_length\tread_count\ttotal_bases\tfraction_reads\tfraction_bases"
+    )?;
+    for summary in summaries {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{}",
+            summary.threshold,
+            summary.count,
+            summary.total_length,
+            summary.count as f64 / sequence_count as f64,
+            if total_length == 0 {
+                0.0
+            } else {
+                summary.total_length as f64 / total_length as f64
+            }
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::long_reads::{length_thresholds, write_cumulative_length_table};
+
+    #[test]
+    fn test_length_thresholds() {
+        let summaries = length_thresholds(&[50, 20, 20, 10], 100, &[20, 1000, 10, 20]);
+        let values: Vec<_> = summaries
+            .iter()
+            .map(|summary| {
+                (
+                    summary.threshold,
+                    summary.count,
+                    summary.count_percent,
+                    summary.total_length,
+                    summary.length_percent,
+                )
+            })
+            .collect();
+        assert_eq!(
+            values,
+            [
+                (10, 4, 100.0, 100, 100.0),
+                (20, 3, 75.0, 90, 90.0),
+                (1000, 0, 0.0, 0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_cumulative_length_table() {
+        let summaries = length_thresholds(&[50, 30, 10, 10], 100, &[0, 25, 100]);
+        let mut output = Vec::new();
+        write_cumulative_length_table(&summaries, 4, 100, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "min_length\tread_count\ttotal_bases\tfraction_reads\tfraction_bases\n\
+             0\t4\t100\t1\t1\n\
+             25\t2\t80\t0.5\t0.8\n\
+             100\t0\t0\t0\t0\n"
+        );
+    }
+}
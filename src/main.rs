@@ -1,40 +1,121 @@
-use clap::Parser;
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
-use log::{info, LevelFilter};
-use seq_io::fastx::Reader;
-use seq_io::BaseRecord;
+use clap::{CommandFactory, Parser};
+use cli::{
+    BenchArgs, ChecksumArg, Cli, Command, CommonArgs, CompressionArg, FilterArgs, InputFormat,
+    LogLevel, OutputFormat, SortArgs, SortKey, StatsArgs, DEFAULT_SEED,
+};
+use console::{style, Term};
+use fastx_statistics::bed::BedWriter;
+use fastx_statistics::bench::{bench_iteration, BenchIteration};
+use fastx_statistics::checksum::{ChecksumAlgorithm, ChecksumWriter};
+use fastx_statistics::compare::compare_summaries;
+use fastx_statistics::contigs::{write_n_regions, DEFAULT_SCAFFOLD_N_THRESHOLD};
+use fastx_statistics::curve::write_lorenz_curve;
+use fastx_statistics::dinucleotide::DinucleotideCounts;
+use fastx_statistics::error::FastxStatisticsError;
+use fastx_statistics::fai::FaiWriter;
+use fastx_statistics::filter::{filter_records, read_ids};
+use fastx_statistics::format::SequenceFormat;
+use fastx_statistics::groups::GroupSummary;
+use fastx_statistics::histogram::LengthHistogram;
+use fastx_statistics::input::{is_stdin, open_input, sample_name, Compression, Input};
+use fastx_statistics::long_reads::write_cumulative_length_table;
+use fastx_statistics::metrics::{Metric, MetricValue};
+use fastx_statistics::multiqc::multiqc_report;
+use fastx_statistics::paired::{interleaved_statistics, paired_statistics, PairedSummary};
+use fastx_statistics::per_sequence::{JsonLinesWriter, PerSequenceFormat, PerSequenceWriter};
+use fastx_statistics::poly_tail::DEFAULT_MIN_POLY_TAIL_LENGTH;
+use fastx_statistics::quality::write_per_cycle_quality;
+use fastx_statistics::quast::write_quast_report;
+use fastx_statistics::records::{RecordFormat, RecordWriter};
+use fastx_statistics::sort::{sort_records, SortOrder};
+use fastx_statistics::streaming::DEFAULT_RESERVOIR_SIZE;
+use fastx_statistics::telomere::{
+    TelomereSequence, DEFAULT_MIN_TELOMERE_REPEATS, DEFAULT_TELOMERE_MOTIF,
+};
+use fastx_statistics::template::Template;
+use fastx_statistics::top::LongSequence;
+use fastx_statistics::validate::{validate, Severity};
+use fastx_statistics::yaml::write_yaml;
+use fastx_statistics::{
+    basic_statistics, multi_input_statistics_with_callback, AssemblySummary, MultiInputSummary,
+    StatisticsOptions,
+};
+use log::{info, warn, LevelFilter};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::json;
 use simplelog::{ColorChoice, CombinedLogger, Config, TermLogger, TerminalMode};
-use std::fmt::Write;
+use std::env;
+use std::ffi::OsString;
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
-#[derive(Parser)]
-struct Cli {
-    /// Fasta or fastq input file (automatically detected).
-    #[clap(index = 1)]
-    input: PathBuf,
+mod cli;
 
-    /// Filter fasta or fastq records with the given ids (pass multiple times for multiple ids).
-    #[clap(long = "filter-id", value_name = "FILTER_ID")]
-    filter_ids: Vec<String>,
+impl From<LogLevel> for LevelFilter {
+    fn from(log_level: LogLevel) -> Self {
+        match log_level {
+            LogLevel::Trace => LevelFilter::Trace,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Error => LevelFilter::Error,
+        }
+    }
+}
 
-    /// Additional percentiles for Nx metric to report (pass multiple times for multiple percentiles).
-    /// N50 and N75 are always reported.
-    #[clap(long = "additional-percentile", value_name = "ADDITIONAL_PERCENTILE")]
-    additional_percentiles: Vec<u8>,
+impl InputFormat {
+    fn format(self) -> SequenceFormat {
+        match self {
+            InputFormat::Fasta => SequenceFormat::Fasta,
+            InputFormat::Fastq => SequenceFormat::Fastq,
+        }
+    }
+}
+
+impl CompressionArg {
+    fn compression(self) -> Compression {
+        match self {
+            CompressionArg::None => Compression::None,
+            CompressionArg::Gzip => Compression::Gzip,
+            CompressionArg::Bzip2 => Compression::Bzip2,
+            CompressionArg::Zstd => Compression::Zstd,
+        }
+    }
+}
+
+impl SortKey {
+    fn order(self) -> SortOrder {
+        match self {
+            SortKey::Length => SortOrder::Length,
+            SortKey::Name => SortOrder::Name,
+        }
+    }
+}
+
+impl ChecksumArg {
+    #[cfg(feature = "checksums")]
+    fn algorithm(self) -> Result<ChecksumAlgorithm, FastxStatisticsError> {
+        Ok(match self {
+            ChecksumArg::Sha256 => ChecksumAlgorithm::Sha256,
+            ChecksumArg::Md5 => ChecksumAlgorithm::Md5,
+        })
+    }
+
+    #[cfg(not(feature = "checksums"))]
+    fn algorithm(self) -> Result<ChecksumAlgorithm, FastxStatisticsError> {
+        Err(FastxStatisticsError::Other("Checksums are not supported, since fastx-statistics was built without the checksums feature".to_owned()))
+    }
 }
 
 pub fn initialise_logging(log_level: LevelFilter) {
     CombinedLogger::init(vec![TermLogger::new(
-        if cfg!(debug_assertions) {
-            LevelFilter::Trace
-        } else {
-            log_level
-        },
+        log_level,
         Config::default(),
-        TerminalMode::Mixed,
+        TerminalMode::Stderr,
         ColorChoice::Auto,
     )])
     .unwrap();
@@ -42,238 +123,1232 @@ pub fn initialise_logging(log_level: LevelFilter) {
     info!("Logging initialised successfully");
 }
 
-fn main() -> Result<(), String> {
-    let cli = Cli::parse();
-    initialise_logging(LevelFilter::Info);
-
-    if !cli.input.is_file() {
-        return Err(format!("Not a file: {:?}", cli.input));
-    }
-
-    let input_file =
-        File::open(&cli.input).map_err(|err| format!("Cannot open input file: {}", err))?;
-    let input_len = input_file
-        .metadata()
-        .map_err(|err| format!("Cannot read file metadata: {}", err))?
-        .len();
-    basic_statistics(
-        input_file,
-        input_len,
-        &cli.filter_ids,
-        &cli.additional_percentiles,
-    )
-}
-
-fn basic_statistics(
-    input: impl Read,
-    input_len: u64,
-    filter_ids: &[String],
-    additional_percentiles: &[u8],
-) -> Result<(), String> {
-    let mut fastx_reader = Reader::new(BufReader::new(input));
-
-    let mut sequence_lengths = Vec::new();
-    let mut sequence_hoco_lengths = Vec::new();
-    let mut sequence_lengths_without_ns = Vec::new();
-    let mut sequence_hoco_lengths_without_ns = Vec::new();
-
-    info!("Reading fasta or fastq file...");
-    let pb = ProgressBar::new(input_len);
-    pb.set_style(
-        ProgressStyle::with_template(
-            "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+fn main() {
+    let args = args_with_default_subcommand(env::args_os().collect());
+    if let Err(err) = run(Cli::parse_from(args)) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+/// Insert the `stats` subcommand into the arguments if no subcommand is given,
+/// such that e.g. `fastx-statistics reads.fq` is the same as `fastx-statistics stats reads.fq`.
+fn args_with_default_subcommand(mut args: Vec<OsString>) -> Vec<OsString> {
+    let mut command = Cli::command();
+    command.build();
+    if let Some(index) = default_subcommand_index(&command, &args) {
+        args.insert(index, "stats".into());
+    }
+    args
+}
+
+/// The index of the first argument after the leading global options,
+/// or `None` if it is a subcommand or if an option of the top level like `--help` is given.
+fn default_subcommand_index(command: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        let arg = arg.to_string_lossy();
+        if arg == "-" || !arg.starts_with('-') {
+            return if command.find_subcommand(arg.as_ref()).is_some() {
+                None
+            } else {
+                Some(index)
+            };
+        }
+
+        let (option, has_inline_value) = if let Some(long) = arg.strip_prefix("--") {
+            let name = long.split('=').next().unwrap();
+            (
+                command
+                    .get_arguments()
+                    .find(|option| option.get_long() == Some(name)),
+                long.contains('='),
+            )
+        } else {
+            let short = arg.chars().nth(1);
+            (
+                command
+                    .get_arguments()
+                    .find(|option| option.get_short() == short),
+                arg.chars().count() > 2,
+            )
+        };
+        match option {
+            Some(option) if option.is_global_set() => {
+                index += if option.get_action().takes_values() && !has_inline_value {
+                    2
+                } else {
+                    1
+                };
+            }
+            Some(_) => return None,
+            // Options of the `stats` subcommand.
+            None => return Some(index),
+        }
+    }
+    Some(index)
+}
+
+fn run(cli: Cli) -> Result<(), FastxStatisticsError> {
+    if let Some(shell) = cli.generate_completions {
+        clap_complete::generate(
+            shell,
+            &mut Cli::command(),
+            "fastx-statistics",
+            &mut io::stdout(),
+        );
+        return Ok(());
+    }
+
+    let (args, compare) = match &cli.command {
+        Some(Command::Stats(args)) => (args.as_ref(), false),
+        Some(Command::Compare(args)) => (args.as_ref(), true),
+        Some(Command::Filter(args)) => {
+            initialise_logging(cli.common.log_level_filter(false));
+            return filter(args, &cli.common);
+        }
+        Some(Command::Sort(args)) => {
+            initialise_logging(cli.common.log_level_filter(false));
+            return sort(args, &cli.common);
+        }
+        Some(Command::Validate { input }) => {
+            initialise_logging(cli.common.log_level_filter(false));
+            let severity = validate_input(input, &cli.common)?;
+            process::exit(match severity {
+                None => 0,
+                Some(Severity::Warning) => 1,
+                Some(Severity::Error) => 2,
+            });
+        }
+        Some(Command::Bench(args)) => {
+            initialise_logging(cli.common.log_level_filter(false));
+            return bench(args, &cli.common);
+        }
+        None => {
+            return Err(FastxStatisticsError::Other(
+                "No subcommand given".to_owned(),
+            ))
+        }
+    };
+
+    initialise_logging(cli.common.log_level_filter(args.brief));
+    statistics(args, &cli.common, compare)
+}
+
+impl CommonArgs {
+    /// The minimum level of log messages, where `brief` disables logging unless a level is given explicitly.
+    fn log_level_filter(&self, brief: bool) -> LevelFilter {
+        if let Some(log_level) = self.log_level {
+            log_level.into()
+        } else if brief {
+            LevelFilter::Off
+        } else if self.quiet {
+            LevelFilter::Warn
+        } else if cfg!(debug_assertions) {
+            LevelFilter::Trace
+        } else {
+            LevelFilter::Info
+        }
+    }
+
+    /// The format given by `--format`, or `None` if it is detected.
+    fn format(&self) -> Option<SequenceFormat> {
+        self.input_format.map(InputFormat::format)
+    }
+
+    /// Open the given input with the compression given by `--compression`, or with the detected one otherwise.
+    fn open_input(&self, path: &Path) -> Result<Input, FastxStatisticsError> {
+        open_input(path, self.compression.map(CompressionArg::compression))
+    }
+}
+
+/// Write the records of the input that pass the filters, or those that fail them with `--invert`.
+fn filter(args: &FilterArgs, common: &CommonArgs) -> Result<(), FastxStatisticsError> {
+    if !is_stdin(&args.input) && !args.input.is_file() {
+        return Err(FastxStatisticsError::NotAFile(args.input.clone()));
+    }
+
+    let input = common.open_input(&args.input)?;
+    let options = StatisticsOptions {
+        filter_ids: args.filter_ids.clone(),
+        exclude_ids: args
+            .exclude_ids_file
+            .as_deref()
+            .map(read_ids)
+            .transpose()?
+            .unwrap_or_default(),
+        include_ids: args.include_ids_file.as_deref().map(read_ids).transpose()?,
+        ignore_case: args.ignore_case,
+        min_length: args.min_length,
+        max_length: args.max_length,
+        max_n_fraction: args.max_n_fraction,
+        format: common.format(),
+        show_progress: !common.quiet,
+        ..Default::default()
+    };
+    let mut writer = RecordWriter::new(BufWriter::new(io::stdout()), RecordFormat::SameAsInput);
+    let summary = filter_records(
+        input.reader,
+        input.progress,
+        &options,
+        args.invert,
+        |record| writer.write_record(record),
+    )?;
+    writer.finish()?;
+
+    if !common.quiet {
+        eprintln!("passed: {}", summary.passed_count);
+        eprintln!("failed: {}", summary.failed_count);
+    }
+    Ok(())
+}
+
+/// Write the records of the input sorted by length or by id.
+fn sort(args: &SortArgs, common: &CommonArgs) -> Result<(), FastxStatisticsError> {
+    if !is_stdin(&args.input) && !args.input.is_file() {
+        return Err(FastxStatisticsError::NotAFile(args.input.clone()));
+    }
+
+    let input = common.open_input(&args.input)?;
+    let options = StatisticsOptions {
+        format: common.format(),
+        show_progress: !common.quiet,
+        ..Default::default()
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(create_file(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = RecordWriter::new(BufWriter::new(output), RecordFormat::SameAsInput);
+    sort_records(
+        input.reader,
+        input.progress,
+        &options,
+        args.sort_by.order(),
+        args.reverse,
+        |record| writer.write_record(record),
+    )?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Read the input repeatedly and print its throughput and the time spent on each step.
+fn bench(args: &BenchArgs, common: &CommonArgs) -> Result<(), FastxStatisticsError> {
+    if !args.input.is_file() {
+        return Err(FastxStatisticsError::NotAFile(args.input.clone()));
+    }
+
+    let mut iterations = Vec::new();
+    for iteration in 1..=args.iterations {
+        info!("Iteration {iteration} of {}...", args.iterations);
+        let input = common.open_input(&args.input)?;
+        iterations.push(bench_iteration(
+            input.reader,
+            common.format(),
+            args.skip_stats,
+        )?);
+    }
+
+    let mut output = BufWriter::new(io::stdout());
+    write_bench_results(&iterations, &mut output)
+        .and_then(|()| output.flush())
+        .map_err(|err| FastxStatisticsError::io("Cannot write benchmark results", err))
+}
+
+fn write_bench_results(iterations: &[BenchIteration], mut output: impl Write) -> io::Result<()> {
+    let throughput = |per_second: fn(&BenchIteration) -> f64| {
+        let values: Vec<_> = iterations.iter().map(per_second).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        format!("mean {mean:.2}, min {min:.2}, max {max:.2}")
+    };
+    let mean_millis = |duration: fn(&BenchIteration) -> Duration| {
+        iterations
+            .iter()
+            .map(|iteration| duration(iteration).as_secs_f64() * 1000.0)
+            .sum::<f64>()
+            / iterations.len() as f64
+    };
+
+    writeln!(output, "iterations: {}", iterations.len())?;
+    writeln!(output, "records: {}", iterations[0].record_count)?;
+    writeln!(
+        output,
+        "MB/s: {}",
+        throughput(|iteration| iteration.byte_count as f64 / 1e6 / iteration.total().as_secs_f64())
+    )?;
+    writeln!(
+        output,
+        "records/s: {}",
+        throughput(|iteration| iteration.record_count as f64 / iteration.total().as_secs_f64())
+    )?;
+    writeln!(
+        output,
+        "I/O: {:.1} ms",
+        mean_millis(|iteration| iteration.io)
+    )?;
+    writeln!(
+        output,
+        "stats computation: {:.1} ms",
+        mean_millis(|iteration| iteration.stats)
+    )?;
+    writeln!(
+        output,
+        "output: {:.1} ms",
+        mean_millis(|iteration| iteration.output)
+    )?;
+
+    Ok(())
+}
+
+/// Print the problems found in the input, and return the severity of the most severe one.
+fn validate_input(
+    input: &Path,
+    common: &CommonArgs,
+) -> Result<Option<Severity>, FastxStatisticsError> {
+    if !is_stdin(input) && !input.is_file() {
+        return Err(FastxStatisticsError::NotAFile(input.to_owned()));
+    }
+
+    let input = common.open_input(input)?;
+    let options = StatisticsOptions {
+        format: common.format(),
+        show_progress: !common.quiet,
+        ..Default::default()
+    };
+    let report = validate(input.reader, input.progress, &options);
+
+    let mut output = BufWriter::new(io::stdout());
+    for issue in &report.issues {
+        if let Some(line) = issue.line {
+            write!(output, "line {line}: ")
+        } else {
+            write!(output, "unknown line: ")
+        }
+        .and_then(|()| writeln!(output, "{}: {}", issue.severity, issue.message))
+        .map_err(|err| FastxStatisticsError::io("Cannot write validation report", err))?;
+    }
+    writeln!(output, "records: {}", report.record_count)
+        .and_then(|()| writeln!(output, "warnings: {}", report.count(Severity::Warning)))
+        .and_then(|()| writeln!(output, "errors: {}", report.count(Severity::Error)))
+        .and_then(|()| output.flush())
+        .map_err(|err| FastxStatisticsError::io("Cannot write validation report", err))?;
+
+    Ok(report.severity())
+}
+
+/// Compute and print the statistics of the inputs, or compare the statistics of the two inputs if `compare` is true.
+fn statistics(
+    args: &StatsArgs,
+    common: &CommonArgs,
+    compare: bool,
+) -> Result<(), FastxStatisticsError> {
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build_global()
+            .map_err(|err| {
+                FastxStatisticsError::Other(format!("Cannot create thread pool: {err}"))
+            })?;
+    }
+
+    if args.subsample.is_some() && args.seed.is_none() {
+        warn!("No --seed given, using the fixed seed {DEFAULT_SEED}, so the sample is the same for each run");
+    }
+    if args.r2.is_some() && args.input.len() != 1 {
+        return Err(FastxStatisticsError::Other(
+            "--r2 requires exactly one input file".to_owned(),
+        ));
+    }
+    if compare && (args.r2.is_some() || args.interleaved) {
+        return Err(FastxStatisticsError::Other(
+            "compare does not support --r2 and --interleaved".to_owned(),
+        ));
+    }
+    if args.interleaved && args.input.len() != 1 {
+        return Err(FastxStatisticsError::Other(
+            "--interleaved requires exactly one input file".to_owned(),
+        ));
+    }
+    let fai_output = if args.generate_fai {
+        if args.input.len() != 1 {
+            return Err(FastxStatisticsError::Other(
+                "--generate-fai requires exactly one input file".to_owned(),
+            ));
+        }
+        if let Some(fai_output) = &args.fai_output {
+            Some(fai_output.clone())
+        } else if is_stdin(&args.input[0]) {
+            return Err(FastxStatisticsError::Other(
+                "--generate-fai requires --fai-output when reading from stdin".to_owned(),
+            ));
+        } else {
+            let mut path = args.input[0].clone().into_os_string();
+            path.push(".fai");
+            Some(PathBuf::from(path))
+        }
+    } else {
+        None
+    };
+
+    for input in args.input.iter().chain(&args.r2) {
+        if !is_stdin(input) && !input.is_file() {
+            return Err(FastxStatisticsError::NotAFile(input.to_owned()));
+        }
+    }
+
+    let options = StatisticsOptions {
+        filter_ids: args.filter_ids.clone(),
+        filter_id_regexes: args.filter_id_regexes.clone(),
+        exclude_ids: args
+            .exclude_ids_file
+            .as_deref()
+            .map(read_ids)
+            .transpose()?
+            .unwrap_or_default(),
+        include_ids: args.include_ids_file.as_deref().map(read_ids).transpose()?,
+        ignore_case: args.ignore_case,
+        filter_descriptions: args
+            .filter_descriptions
+            .iter()
+            .map(|pattern| {
+                if args.regex {
+                    Regex::new(pattern)
+                } else {
+                    Regex::new(&regex::escape(pattern))
+                }
+                .map_err(|err| {
+                    FastxStatisticsError::Other(format!(
+                        "Invalid --filter-description pattern {pattern:?}: {err}"
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        min_length: args.min_length,
+        max_length: args.max_length,
+        min_gc: args.min_gc,
+        max_gc: args.max_gc,
+        max_n_fraction: args.max_n_fraction,
+        min_complexity: args.min_complexity,
+        exclude_all_n: args.exclude_all_n,
+        additional_percentiles: args.additional_percentiles.clone(),
+        length_histogram_bins: args.length_histogram.map(|bins| bins as usize),
+        length_histogram_log_scale: args.log_scale,
+        scaffold_n_threshold: args.scaffold_n_threshold,
+        top: args.top,
+        genome_size: args.genome_size,
+        long_read_thresholds: args.long_read_thresholds.clone(),
+        cumulative_length_thresholds: if args.cumulative_length_table.is_some() {
+            args.cumulative_thresholds.clone()
+        } else {
+            Vec::new()
+        },
+        all_percentiles: args.all_percentiles,
+        contiguity_curve: args.nx_curve_output.is_some(),
+        lorenz_curve: args.lorenz_curve.is_some(),
+        hoco: !args.no_hoco,
+        group_separator: args.group_by_prefix.clone(),
+        reservoir_size: if args.streaming {
+            Some(args.reservoir_size)
+        } else {
+            None
+        },
+        per_cycle_quality: args.per_cycle_quality.is_some(),
+        dinucleotides: args.dinucleotide_frequency,
+        kmer_length: args.kmer_uniqueness,
+        telomere_motif: args.telomere_motif.clone(),
+        min_telomere_repeats: args.min_telomere_repeats,
+        adapters: args.adapter.clone(),
+        poly_tail_min_length: if args.detect_poly_tails {
+            Some(args.min_poly_tail_length)
+        } else {
+            None
+        },
+        format: common.format(),
+        show_progress: !common.quiet && !args.brief,
+        skip: args.skip,
+        limit: args.limit,
+        subsample: args.subsample,
+        seed: args.seed.unwrap_or(DEFAULT_SEED),
+        check_duplicate_ids: args.check_duplicates,
+        check_duplicate_sequences: args.detect_duplicate_sequences,
+        verify_duplicate_sequences: args.verify_duplicates,
+        check_characters: args.check_characters,
+        strict: args.strict,
+    };
+
+    let (output, output_is_terminal): (Box<dyn Write>, _) = if let Some(output) = &args.output {
+        (Box::new(BufWriter::new(create_file(output)?)), false)
+    } else {
+        (
+            Box::new(BufWriter::new(io::stdout())),
+            Term::stdout().is_term(),
         )
-        .unwrap()
-        .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
-            write!(w, "{:.0}s", state.eta().as_secs_f64()).unwrap()
+    };
+    let mut output = Some(output);
+
+    if compare {
+        let statistics = |path: &Path| {
+            let input = common.open_input(path)?;
+            basic_statistics(input.reader, input.progress, &options)
+        };
+        let [first, second] = [&args.input[0], &args.input[1]];
+        let first_summary = statistics(first)?;
+        let second_summary = statistics(second)?;
+        let mut output = output.unwrap();
+        write_comparison(
+            [(first, &first_summary), (second, &second_summary)],
+            args,
+            &mut output,
+            output_is_terminal,
+        )?;
+        return flush_output(output);
+    }
+
+    let inputs = args
+        .input
+        .iter()
+        .map(|input| {
+            common
+                .open_input(input)
+                .map(|input| (input.reader, input.progress))
         })
-        .progress_chars("#>-"),
-    );
-    let mut last_update = Instant::now();
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(r2) = &args.r2 {
+        let (r1, r1_progress) = inputs.into_iter().next().unwrap();
+        let r2 = common.open_input(r2)?;
+        let summary = paired_statistics(r1, r1_progress, r2.reader, r2.progress, &options)?;
+        let mut output = output.unwrap();
+        write_paired_summary(&summary, args, &mut output, output_is_terminal)?;
+        return flush_output(output);
+    }
+    if args.interleaved {
+        let (input, progress) = inputs.into_iter().next().unwrap();
+        let summary = interleaved_statistics(input, progress, &options)?;
+        let mut output = output.unwrap();
+        write_paired_summary(&summary, args, &mut output, output_is_terminal)?;
+        return flush_output(output);
+    }
+
+    let checksum = args.checksums.map(ChecksumArg::algorithm).transpose()?;
+    let mut per_sequence_writer = if args.per_sequence {
+        let format = match args.output_format {
+            OutputFormat::Json => PerSequenceFormat::Json,
+            OutputFormat::Human | OutputFormat::Tsv | OutputFormat::Yaml => PerSequenceFormat::Tsv,
+        };
+        Some(PerSequenceWriter::new(
+            output.take().unwrap(),
+            format,
+            args.complexity,
+            checksum,
+        )?)
+    } else {
+        None
+    };
+    let mut checksum_writer = match (checksum, args.per_sequence) {
+        (Some(algorithm), false) => Some(ChecksumWriter::new(output.take().unwrap(), algorithm)),
+        _ => None,
+    };
+    let records_output = match (&args.output_fasta, &args.output_fastq) {
+        (Some(path), _) => Some((path, RecordFormat::Fasta)),
+        (_, Some(path)) => Some((path, RecordFormat::Fastq)),
+        (None, None) => None,
+    };
+    let mut record_writer = if let Some((path, format)) = records_output {
+        Some(RecordWriter::new(
+            BufWriter::new(create_file(path)?),
+            format,
+        ))
+    } else {
+        None
+    };
+
+    let mut n_region_writer = if let Some(path) = &args.output_n_regions {
+        Some(BufWriter::new(create_file(path)?))
+    } else {
+        None
+    };
+
+    let mut bed_writer = if let Some(path) = &args.output_bed {
+        Some(BedWriter::new(BufWriter::new(create_file(path)?)))
+    } else {
+        None
+    };
 
-    while let Some(record) = fastx_reader.next() {
-        let record = record.map_err(|err| format!("Error parsing fastx: {}", err))?;
-        if filter_ids.contains(
-            &record
-                .id()
-                .map_err(|err| format!("Record id is not utf-8 encoded: {err}"))?
-                .to_owned(),
-        ) {
-            continue;
+    let per_record_jsonl_to_stdout = args.output.is_none()
+        && args
+            .per_record_jsonl
+            .as_ref()
+            .map_or(false, |path| path == Path::new("-"));
+    let mut jsonl_writer = if let Some(path) = &args.per_record_jsonl {
+        let output: Box<dyn Write> = if path == Path::new("-") {
+            Box::new(BufWriter::new(io::stdout()))
+        } else {
+            Box::new(BufWriter::new(create_file(path)?))
+        };
+        Some(JsonLinesWriter::new(output))
+    } else {
+        None
+    };
+
+    let mut fai_writer = if let Some(path) = &fai_output {
+        Some(FaiWriter::new(BufWriter::new(create_file(path)?)))
+    } else {
+        None
+    };
+
+    let summary = multi_input_statistics_with_callback(inputs, &options, |record| {
+        if let Some(per_sequence_writer) = &mut per_sequence_writer {
+            per_sequence_writer.write_record(record)?;
+        }
+        if let Some(checksum_writer) = &mut checksum_writer {
+            checksum_writer.write_record(record)?;
         }
+        if let Some(record_writer) = &mut record_writer {
+            record_writer.write_record(record)?;
+        }
+        if let Some(n_region_writer) = &mut n_region_writer {
+            write_n_regions(n_region_writer, record)?;
+        }
+        if let Some(bed_writer) = &mut bed_writer {
+            bed_writer.write_record(record)?;
+        }
+        if let Some(jsonl_writer) = &mut jsonl_writer {
+            jsonl_writer.write_record(record)?;
+        }
+        if let Some(fai_writer) = &mut fai_writer {
+            fai_writer.write_record(record)?;
+        }
+        Ok(())
+    })?;
 
-        let sequence_statistics = SequenceStatistics::new(record.seq());
+    if let Some(quality) = &summary.combined.quality {
+        if quality.is_phred64_suspected() {
+            warn!(
+                "The quality bytes range from {:?} to {:?}, which suggests Phred+64 encoded qualities, but they are interpreted as Phred+33",
+                char::from(quality.min_quality_byte),
+                char::from(quality.max_quality_byte)
+            );
+        }
+    }
+    if let Some(per_sequence_writer) = per_sequence_writer {
+        per_sequence_writer.finish()?;
+    }
+    if let Some(checksum_writer) = checksum_writer {
+        checksum_writer.finish()?;
+    }
+    if let Some(mut n_region_writer) = n_region_writer {
+        n_region_writer
+            .flush()
+            .map_err(|err| FastxStatisticsError::io("Cannot write N regions", err))?;
+    }
+    if let Some(bed_writer) = bed_writer {
+        bed_writer.finish()?;
+    }
+    if let Some(jsonl_writer) = jsonl_writer {
+        jsonl_writer.finish()?;
+    }
+    if let Some(fai_writer) = fai_writer {
+        fai_writer.finish()?;
+    }
+    if let (Some(record_writer), Some((path, _))) = (record_writer, records_output) {
+        let record_count = record_writer.finish()?;
+        info!("Wrote {record_count} records to {path:?}");
+    }
 
-        sequence_lengths.push(sequence_statistics.len);
-        sequence_hoco_lengths.push(sequence_statistics.hoco_len);
-        sequence_lengths_without_ns.push(sequence_statistics.len_without_ns);
-        sequence_hoco_lengths_without_ns.push(sequence_statistics.hoco_len_without_ns);
+    if let Some(path) = &args.nx_curve_output {
+        let mut output = BufWriter::new(create_file(path)?);
+        if let Some(contiguity_curve) = &summary.combined.contiguity_curve {
+            contiguity_curve.write_tsv(&mut output)
+        } else {
+            // Without records there is no curve, but the file still has a header.
+            let ngx_columns = if args.genome_size.is_some() {
+                "\tngx_length\tlgx_count"
+            } else {
+                ""
+            };
+            writeln!(output, "percentile\tnx_length\thoco_nx_length{ngx_columns}")
+        }
+        .and_then(|()| output.flush())
+        .map_err(|err| FastxStatisticsError::io("Cannot write Nx curve", err))?;
+    }
+    if let Some(path) = &args.lorenz_curve {
+        let mut output = BufWriter::new(create_file(path)?);
+        // Without records, only the header is written.
+        let lorenz_curve = summary.combined.lorenz_curve.as_deref().unwrap_or(&[]);
+        write_lorenz_curve(lorenz_curve, &mut output)
+            .and_then(|()| output.flush())
+            .map_err(|err| FastxStatisticsError::io("Cannot write Lorenz curve", err))?;
+    }
+    if let Some(path) = &args.per_cycle_quality {
+        let mut output = BufWriter::new(create_file(path)?);
+        let cycles = summary.combined.per_cycle_quality.as_deref().unwrap_or(&[]);
+        write_per_cycle_quality(cycles, &mut output)
+            .and_then(|()| output.flush())
+            .map_err(|err| FastxStatisticsError::io("Cannot write per-cycle quality", err))?;
+    }
+    if let Some(path) = &args.cumulative_length_table {
+        let mut output = BufWriter::new(create_file(path)?);
+        let combined = &summary.combined;
+        // Without records, only the header is written.
+        let cumulative_lengths = combined.cumulative_lengths.as_deref().unwrap_or(&[]);
+        let total_length = combined
+            .lengths
+            .as_ref()
+            .map_or(0, |lengths| lengths.with_ns.total_length);
+        write_cumulative_length_table(
+            cumulative_lengths,
+            combined.record_count,
+            total_length,
+            &mut output,
+        )
+        .and_then(|()| output.flush())
+        .map_err(|err| FastxStatisticsError::io("Cannot write cumulative length table", err))?;
+    }
+    if let Some(path) = &args.multiqc_json {
+        let samples: Vec<_> = args
+            .input
+            .iter()
+            .map(|path| sample_name(path))
+            .zip(&summary.inputs)
+            .collect();
+        let mut output = BufWriter::new(create_file(path)?);
+        serde_json::to_writer_pretty(&mut output, &multiqc_report(&samples))
+            .map_err(|err| FastxStatisticsError::io("Cannot write MultiQC report", err))?;
+        writeln!(output)
+            .and_then(|()| output.flush())
+            .map_err(|err| FastxStatisticsError::io("Cannot write MultiQC report", err))?;
+    }
 
-        let now = Instant::now();
-        if last_update + Duration::from_millis(200) <= now {
-            pb.set_position(fastx_reader.position().byte());
-            last_update = now;
+    if args.per_sequence || args.checksums.is_some() || per_record_jsonl_to_stdout {
+        if !common.quiet {
+            write_summaries(&summary, args, io::stderr(), Term::stderr().is_term())?;
         }
+    } else if let Some(template) = &args.template {
+        let mut output = output.unwrap();
+        writeln!(output, "{}", template.render(&summary.combined.metrics())?)
+            .map_err(write_error)?;
+        flush_output(output)?;
+    } else if args.quast_format {
+        let assemblies: Vec<_> = args
+            .input
+            .iter()
+            .map(|path| sample_name(path))
+            .zip(&summary.inputs)
+            .collect();
+        let mut output = output.unwrap();
+        write_quast_report(&assemblies, &mut output).map_err(write_error)?;
+        flush_output(output)?;
+    } else if args.brief {
+        let lengths = summary.combined.lengths.as_ref().ok_or_else(|| {
+            FastxStatisticsError::Other(
+                "Cannot compute the N50, since there are no sequences".to_owned(),
+            )
+        })?;
+        let mut output = output.unwrap();
+        writeln!(output, "{}", lengths.with_ns.n50).map_err(write_error)?;
+        flush_output(output)?;
+    } else {
+        let mut output = output.unwrap();
+        write_summaries(&summary, args, &mut output, output_is_terminal)?;
+        flush_output(output)?;
     }
 
-    pb.finish_and_clear();
+    let failed_assertions = check_assertions(&summary.combined, args);
+    if !failed_assertions.is_empty() {
+        for failed_assertion in failed_assertions {
+            eprintln!("{failed_assertion}");
+        }
+        process::exit(1);
+    }
 
-    let count = sequence_lengths.len();
+    Ok(())
+}
 
-    println!("# records: {count}");
-    if count > 0 {
-        print_sequence_statistics(
-            &mut sequence_lengths,
-            &mut sequence_lengths_without_ns,
-            additional_percentiles,
-            "",
-        );
-        print_sequence_statistics(
-            &mut sequence_hoco_lengths,
-            &mut sequence_hoco_lengths_without_ns,
-            additional_percentiles,
-            "hoco ",
+/// Check the thresholds given by the `--assert-*` arguments, and return an error for each that is violated.
+fn check_assertions(summary: &AssemblySummary, args: &StatsArgs) -> Vec<FastxStatisticsError> {
+    // The N50, total length and N count, which are approximated for the N50 in streaming mode.
+    let lengths = if let Some(lengths) = &summary.lengths {
+        Some((
+            lengths.with_ns.n50 as u64,
+            lengths.with_ns.total_length,
+            lengths.n_bases,
+        ))
+    } else {
+        summary.streaming.as_ref().map(|streaming| {
+            (
+                streaming.approximate_n50 as u64,
+                streaming.total_length,
+                streaming.n_bases,
+            )
+        })
+    };
+    // Without sequences, the N50 and total length are zero, and there are no Ns.
+    let (n50, total_length, n_bases) = lengths.unwrap_or((0, 0, 0));
+    let n_fraction = if total_length > 0 {
+        n_bases as f64 / total_length as f64
+    } else {
+        0.0
+    };
+
+    let minimums = [
+        (
+            "record count",
+            args.assert_record_count.map(|count| count as u64),
+            summary.record_count as u64,
+        ),
+        ("N50", args.assert_n50.map(|n50| n50 as u64), n50),
+        ("total length", args.assert_total_length, total_length),
+    ];
+    let mut failed_assertions: Vec<_> = minimums
+        .into_iter()
+        .filter_map(|(metric, threshold, actual)| {
+            let threshold = threshold?;
+            (actual < threshold).then(|| FastxStatisticsError::AssertionFailed {
+                metric: metric.to_owned(),
+                threshold: threshold as f64,
+                actual: actual as f64,
+            })
+        })
+        .collect();
+    if let Some(max_n_fraction) = args.assert_max_n_fraction {
+        if n_fraction > max_n_fraction {
+            failed_assertions.push(FastxStatisticsError::AssertionFailed {
+                metric: "N fraction".to_owned(),
+                threshold: max_n_fraction,
+                actual: n_fraction,
+            });
+        }
+    }
+    failed_assertions
+}
+
+fn create_file(path: &Path) -> Result<File, FastxStatisticsError> {
+    File::create(path)
+        .map_err(|err| FastxStatisticsError::io(format!("Cannot create output file {path:?}"), err))
+}
+
+fn flush_output(mut output: impl Write) -> Result<(), FastxStatisticsError> {
+    output.flush().map_err(write_error)
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write statistics", err)
+}
+
+/// Write the statistics of each input followed by the combined statistics,
+/// or only the statistics of the single input if there is only one.
+fn write_summaries(
+    summary: &MultiInputSummary,
+    args: &StatsArgs,
+    mut output: impl Write,
+    is_terminal: bool,
+) -> Result<(), FastxStatisticsError> {
+    if summary.inputs.len() == 1 {
+        return write_summary(&summary.combined, args, output, is_terminal);
+    }
+
+    if is_structured(args.output_format) {
+        let inputs: Vec<_> = args
+            .input
+            .iter()
+            .zip(&summary.inputs)
+            .map(|(path, statistics)| json!({ "input": path, "statistics": statistics }))
+            .collect();
+        return write_structured(
+            &json!({ "inputs": inputs, "combined": summary.combined }),
+            args,
+            output,
         );
     }
 
-    Ok(())
+    for (path, statistics) in args.input.iter().zip(&summary.inputs) {
+        writeln!(output, "=== {} ===", path.display()).map_err(write_error)?;
+        write_summary(statistics, args, &mut output, is_terminal)?;
+    }
+    writeln!(output, "=== combined ===").map_err(write_error)?;
+    write_summary(&summary.combined, args, &mut output, is_terminal)
+}
+
+/// Write the statistics of both ends of paired-end reads, followed by the pair concordance.
+fn write_paired_summary(
+    summary: &PairedSummary,
+    args: &StatsArgs,
+    mut output: impl Write,
+    is_terminal: bool,
+) -> Result<(), FastxStatisticsError> {
+    if is_structured(args.output_format) {
+        return write_structured(summary, args, output);
+    }
+
+    writeln!(output, "=== R1 ===").map_err(write_error)?;
+    write_summary(&summary.r1, args, &mut output, is_terminal)?;
+    writeln!(output, "=== R2 ===").map_err(write_error)?;
+    write_summary(&summary.r2, args, &mut output, is_terminal)?;
+    writeln!(output, "=== pairs ===").map_err(write_error)?;
+    write_metrics(summary.pair_metrics(), args.output_format, output)
 }
 
-fn print_sequence_statistics(
-    sequence_lengths: &mut [usize],
-    sequence_lengths_without_ns: &mut [usize],
-    additional_percentiles: &[u8],
-    prefix: &str,
-) {
-    sequence_lengths.sort_unstable_by(|a, b| b.cmp(a));
-    sequence_lengths_without_ns.sort_unstable_by(|a, b| b.cmp(a));
-    let length = sequence_lengths.iter().sum();
-    let length_without_ns = sequence_lengths_without_ns.iter().sum();
-    let ns = length - length_without_ns;
-
-    println!("{prefix}# Ns: {ns}");
-    print_nx(sequence_lengths, length, additional_percentiles, prefix, "");
-    print_nx(
-        sequence_lengths_without_ns,
-        length_without_ns,
-        additional_percentiles,
-        prefix,
-        " without Ns",
-    );
-}
-
-fn print_nx(
-    sorted_sequence_lengths: &[usize],
-    length: usize,
-    additional_percentiles: &[u8],
-    prefix: &str,
-    suffix: &str,
-) {
-    let n50 = nx(sorted_sequence_lengths, length, |l| l / 2);
-    let n75 = nx(sorted_sequence_lengths, length, |l| {
-        l.checked_mul(3).unwrap() / 4
-    });
-
-    println!("{prefix}total length{suffix}: {length}");
-    println!("{prefix}N50{suffix}: {n50}");
-    println!("{prefix}N75{suffix}: {n75}");
-
-    for additional_percentile in additional_percentiles.iter().copied() {
-        let nx = nx(sorted_sequence_lengths, length, |l| {
-            ((l as u128) * u128::from(additional_percentile) / 100) as usize
-        });
-        println!("{prefix}N{additional_percentile}{suffix}: {nx}");
-    }
-
-    println!(
-        "{prefix}max len{suffix}: {}",
-        sorted_sequence_lengths.first().unwrap()
-    );
-    println!(
-        "{prefix}min len{suffix}: {}",
-        sorted_sequence_lengths.last().unwrap()
-    );
-}
-
-fn nx(lengths: &[usize], sum: usize, percentile: impl FnOnce(usize) -> usize) -> usize {
-    debug_assert!(lengths.windows(2).all(|w| w[0] >= w[1]));
-    debug_assert_eq!(lengths.iter().sum::<usize>(), sum);
-
-    let required_covered_bases = percentile(sum);
-    debug_assert!(required_covered_bases <= sum);
-
-    let mut sum = 0;
-    for len in lengths.iter().copied() {
-        sum += len;
-        if sum >= required_covered_bases {
-            return len;
-        }
-    }
-
-    unreachable!()
-}
-
-struct SequenceStatistics {
-    len: usize,
-    hoco_len: usize,
-    len_without_ns: usize,
-    hoco_len_without_ns: usize,
-}
-
-impl SequenceStatistics {
-    fn new(sequence: &[u8]) -> Self {
-        if sequence.is_empty() {
-            return Self {
-                len: 0,
-                hoco_len: 0,
-                len_without_ns: 0,
-                hoco_len_without_ns: 0,
+/// Write the metrics of two inputs side by side with their differences.
+/// Improvements of the second input over the first are highlighted on terminals.
+fn write_comparison(
+    summaries: [(&PathBuf, &AssemblySummary); 2],
+    args: &StatsArgs,
+    mut output: impl Write,
+    is_terminal: bool,
+) -> Result<(), FastxStatisticsError> {
+    let [(first_path, first), (second_path, second)] = summaries;
+    if is_structured(args.output_format) {
+        return write_structured(
+            &json!({
+                "first": { "input": first_path, "statistics": first },
+                "second": { "input": second_path, "statistics": second },
+            }),
+            args,
+            output,
+        );
+    }
+
+    let comparisons = compare_summaries(first, second);
+    let human = args.output_format == OutputFormat::Human;
+    let rows: Vec<_> = comparisons
+        .iter()
+        .map(|comparison| {
+            let value = |value: &Option<MetricValue>| match value {
+                Some(value) if human => value.to_human_string(),
+                Some(value) => value.to_string(),
+                None => String::new(),
             };
+            [
+                if human {
+                    comparison.label.clone()
+                } else {
+                    comparison.key.clone()
+                },
+                value(&comparison.first),
+                value(&comparison.second),
+                comparison.delta().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let header = [
+        "metric".to_owned(),
+        first_path.display().to_string(),
+        second_path.display().to_string(),
+        "delta".to_owned(),
+    ];
+
+    let mut widths = [0; 3];
+    if human {
+        for row in rows.iter().chain([&header]) {
+            for (width, column) in widths.iter_mut().zip(row) {
+                *width = (*width).max(column.chars().count());
+            }
+        }
+    }
+    for (row, improved) in [(&header, None)].into_iter().chain(
+        rows.iter()
+            .zip(comparisons.iter().map(|comparison| comparison.improved)),
+    ) {
+        let line = if human {
+            format!(
+                "{:w0$}  {:w1$}  {:w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+            )
+        } else {
+            row.join("\t")
+        };
+        if is_terminal && improved == Some(true) {
+            writeln!(output, "{}", style(line.trim_end()).green())
+        } else {
+            writeln!(output, "{}", line.trim_end())
         }
+        .map_err(write_error)?;
+    }
 
-        let is_n = |b| b == b'n' || b == b'N';
-        let mut len = 1;
-        let mut hoco_len = 1;
-        let mut last_byte = *sequence.first().unwrap();
-        let mut ns = if is_n(last_byte) { 1 } else { 0 };
-        let mut hoco_ns = ns;
+    Ok(())
+}
 
-        for byte in sequence.iter().skip(1).copied() {
-            if byte == b'\n' {
-                continue;
-            }
+/// Returns true if the output format serialises the whole summary instead of printing metrics line by line.
+fn is_structured(output_format: OutputFormat) -> bool {
+    matches!(output_format, OutputFormat::Json | OutputFormat::Yaml)
+}
 
-            len += 1;
-            if is_n(byte) {
-                ns += 1;
+/// Write the value as json or yaml, depending on the output format.
+/// Yaml output starts with a comment naming the tool version and the inputs.
+fn write_structured(
+    value: &impl Serialize,
+    args: &StatsArgs,
+    mut output: impl Write,
+) -> Result<(), FastxStatisticsError> {
+    match args.output_format {
+        OutputFormat::Yaml => {
+            writeln!(
+                output,
+                "# {} {}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )
+            .map_err(write_error)?;
+            for path in args.input.iter().chain(&args.r2) {
+                writeln!(output, "# input: {}", path.display()).map_err(write_error)?;
             }
+            write_yaml(value, output).map_err(write_error)
+        }
+        _ => {
+            serde_json::to_writer_pretty(&mut output, value).map_err(write_error)?;
+            writeln!(output).map_err(write_error)
+        }
+    }
+}
 
-            if byte != last_byte {
-                last_byte = byte;
-                hoco_len += 1;
-
-                if is_n(last_byte) {
-                    hoco_ns += 1;
+/// Write the given metrics in a line-based format.
+fn write_metrics(
+    metrics: Vec<Metric>,
+    output_format: OutputFormat,
+    mut output: impl Write,
+) -> Result<(), FastxStatisticsError> {
+    let mut is_first = true;
+    for metric in metrics {
+        match output_format {
+            OutputFormat::Human | OutputFormat::Json | OutputFormat::Yaml => {
+                let human_string = metric.to_human_string();
+                if human_string.is_empty() {
+                    continue;
                 }
+                let separator = match (is_first, metric.same_line) {
+                    (true, _) => "",
+                    (false, true) => "   ",
+                    (false, false) => "\n",
+                };
+                write!(output, "{separator}{human_string}")
+            }
+            OutputFormat::Tsv => writeln!(output, "{}\t{}", metric.key, metric.value),
+        }
+        .map_err(write_error)?;
+        is_first = false;
+    }
+    if output_format != OutputFormat::Tsv && !is_first {
+        writeln!(output).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn write_summary(
+    summary: &AssemblySummary,
+    args: &StatsArgs,
+    mut output: impl Write,
+    is_terminal: bool,
+) -> Result<(), FastxStatisticsError> {
+    match args.output_format {
+        OutputFormat::Human | OutputFormat::Tsv => {
+            if let (OutputFormat::Human, Some(record_limit)) =
+                (args.output_format, summary.record_limit)
+            {
+                writeln!(output, "(first {record_limit} records only)").map_err(write_error)?;
             }
+            write_metrics(summary.metrics(), args.output_format, &mut output)?;
         }
+        OutputFormat::Json | OutputFormat::Yaml => return write_structured(summary, args, output),
+    }
 
-        Self {
-            len,
-            hoco_len,
-            len_without_ns: len - ns,
-            hoco_len_without_ns: hoco_len - hoco_ns,
+    if let Some(nx_curve) = &summary.nx_curve {
+        write_nx_curve(nx_curve, &mut output).map_err(write_error)?;
+    }
+    if args.quality_histogram {
+        if let Some(quality) = &summary.quality {
+            write_quality_histogram(&quality.mean_quality_histogram, &mut output, is_terminal)
+                .map_err(write_error)?;
         }
     }
+    if let Some(length_histogram) = &summary.length_histogram {
+        write_length_histogram(length_histogram, &mut output, is_terminal).map_err(write_error)?;
+    }
+    if args.homopolymer_distribution {
+        if let Some(homopolymers) = &summary.homopolymers {
+            write_homopolymer_distribution(&homopolymers.run_length_counts, &mut output)
+                .map_err(write_error)?;
+        }
+    }
+    if let Some(dinucleotides) = &summary.dinucleotides {
+        write_dinucleotide_frequency(dinucleotides, &mut output).map_err(write_error)?;
+    }
+    if let Some(longest_sequences) = &summary.longest_sequences {
+        write_longest_sequences(longest_sequences, &mut output).map_err(write_error)?;
+    }
+    if let Some(telomeres) = &summary.telomeres {
+        write_telomere_sequences(&telomeres.sequences, &mut output).map_err(write_error)?;
+    }
+    if let Some(groups) = &summary.groups {
+        write_groups(groups, &mut output).map_err(write_error)?;
+    }
+    if let Some(duplicate_sequences) = &summary.duplicate_sequences {
+        write_duplicate_sequences(&duplicate_sequences.groups, &mut output).map_err(write_error)?;
+    }
+
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::basic_statistics;
+/// Write the Nx values for the percentiles from 1 to 100, one per line.
+fn write_nx_curve(nx_curve: &[usize], mut output: impl Write) -> io::Result<()> {
+    for (percentile, nx) in (1..).zip(nx_curve) {
+        writeln!(output, "N{percentile}: {nx}")?;
+    }
 
-    #[test]
-    fn test() {
-        let fasta = b">1\nAAAGCGCTNNNNNTTCGAGGA\n>2\nGTGCTAGCGGGCC\nNCCCTTTTTTTTTTTT\n>3\nACGCTTATG\n>4\nGCTAACTGAGNNNNAAATTTCGGG\n>5\nAAAGGGCCTTCC\n";
-        basic_statistics(fasta.as_slice(), fasta.len() as u64, &[], &[90]).unwrap();
+    Ok(())
+}
+
+fn write_quality_histogram(
+    histogram: &[usize],
+    mut output: impl Write,
+    bars: bool,
+) -> io::Result<()> {
+    let total: usize = histogram.iter().sum();
+    let max = histogram.iter().copied().max().unwrap_or(0);
+
+    writeln!(output, "quality\tcount\tfraction")?;
+    for (quality, count) in histogram.iter().copied().enumerate() {
+        write!(
+            output,
+            "{quality}\t{count}\t{:.4}",
+            count as f64 / total as f64
+        )?;
+        if bars {
+            write!(output, "\t{}", histogram_bar(count, max))?;
+        }
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
+
+fn write_length_histogram(
+    histogram: &LengthHistogram,
+    mut output: impl Write,
+    bars: bool,
+) -> io::Result<()> {
+    let total: usize = histogram.bins.iter().map(|bin| bin.count).sum();
+    let max = histogram
+        .bins
+        .iter()
+        .map(|bin| bin.count)
+        .max()
+        .unwrap_or(0);
+
+    writeln!(output, "bin_start\tbin_end\tcount\tfraction")?;
+    for bin in &histogram.bins {
+        write!(
+            output,
+            "{:.2}\t{:.2}\t{}\t{:.4}",
+            bin.start,
+            bin.end,
+            bin.count,
+            bin.count as f64 / total as f64
+        )?;
+        if bars {
+            write!(output, "\t{}", histogram_bar(bin.count, max))?;
+        }
+        writeln!(output)?;
     }
+
+    Ok(())
+}
+
+fn write_homopolymer_distribution(
+    run_length_counts: &[usize],
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(output, "run_length\tcount")?;
+    for (run_length, count) in run_length_counts.iter().copied().enumerate() {
+        if count > 0 {
+            writeln!(output, "{run_length}\t{count}")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_dinucleotide_frequency(
+    dinucleotides: &DinucleotideCounts,
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(output, "dinucleotide\tcount\tfrequency")?;
+    for (dinucleotide, count, frequency) in dinucleotides.frequencies() {
+        writeln!(output, "{dinucleotide}\t{count}\t{frequency}")?;
+    }
+
+    Ok(())
+}
+
+fn write_telomere_sequences(
+    sequences: &[TelomereSequence],
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(output, "id\tstart_telomere_repeats\tend_telomere_repeats")?;
+    for sequence in sequences {
+        writeln!(
+            output,
+            "{}\t{}\t{}",
+            sequence.id, sequence.start_repeats, sequence.end_repeats
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_longest_sequences(
+    longest_sequences: &[LongSequence],
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(output, "id\tlength")?;
+    for sequence in longest_sequences {
+        writeln!(output, "{}\t{}", sequence.id, sequence.length)?;
+    }
+
+    Ok(())
+}
+
+fn write_groups(groups: &[GroupSummary], mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "group\trecords\ttotal_length\tn50")?;
+    for group in groups {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}",
+            group.prefix, group.record_count, group.total_length, group.n50
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write the ids of each group of duplicate sequences on one line, separated by tabs.
+fn write_duplicate_sequences(groups: &[Vec<String>], mut output: impl Write) -> io::Result<()> {
+    if groups.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(output, "duplicate_ids")?;
+    for ids in groups {
+        writeln!(output, "{}", ids.join("\t"))?;
+    }
+
+    Ok(())
+}
+
+/// Render a bar of `#` characters whose length is proportional to `count / max`.
+fn histogram_bar(count: usize, max: usize) -> String {
+    const MAX_BAR_WIDTH: usize = 50;
+    let width = if max == 0 {
+        0
+    } else {
+        ((count as f64 / max as f64) * MAX_BAR_WIDTH as f64).round() as usize
+    };
+    "#".repeat(width)
 }
@@ -0,0 +1,1246 @@
+//! Flatten summaries into lists of named metrics, for rendering them in line-based formats.
+
+use crate::adapter::AdapterSummary;
+use crate::composition::{BaseComposition, BasePercentages};
+use crate::duplicates::{DuplicateIdSummary, DuplicateSequenceSummary};
+use crate::kmer::KmerSummary;
+use crate::long_reads::LengthThresholdSummary;
+use crate::ngx::NgxSummary;
+use crate::paired::PairedSummary;
+use crate::poly_tail::PolyTailSummary;
+use crate::quality::QualitySummary;
+use crate::streaming::StreamingSummary;
+use crate::telomere::TelomereSummary;
+use crate::{AssemblySummary, ExcludedCounts, GcSummary, LengthSummary, NxSummary};
+use std::fmt::{Display, Formatter};
+
+/// A single named statistic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    /// A lowercase and underscored identifier that is stable across versions.
+    pub key: String,
+    /// A human-readable name.
+    pub label: String,
+    pub value: MetricValue,
+    /// If true, line-based human-readable output shows the metric on the same line as the previous one,
+    /// such that related metrics form a table row.
+    pub same_line: bool,
+    /// A sentence that replaces `label: value` in human-readable output.
+    /// If it is empty, then the metric is not shown in human-readable output,
+    /// e.g. because the sentence of the previous metric includes its value.
+    pub sentence: Option<String>,
+}
+
+/// The value of a [`Metric`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricValue {
+    /// A number of records or bases.
+    Count(u64),
+    /// A sequence length or a total number of bases.
+    Length(u64),
+    /// A real number.
+    Float(f64),
+    /// A percentage between 0 and 100.
+    Percentage(f64),
+    /// A coefficient between 0 and 1, shown with more decimals than a [`MetricValue::Float`].
+    Coefficient(f64),
+    /// A free-form text.
+    Text(String),
+    /// A value that is not defined, with the reason why.
+    NotAvailable(String),
+}
+
+impl Metric {
+    fn new(key: impl Into<String>, label: impl Into<String>, value: MetricValue) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+            value,
+            same_line: false,
+            sentence: None,
+        }
+    }
+
+    /// Format the metric for human-readable output, as its sentence if it has one, and as `label: value` otherwise.
+    pub fn to_human_string(&self) -> String {
+        match &self.sentence {
+            Some(sentence) => sentence.clone(),
+            None => format!("{}: {}", self.label, self.value.to_human_string()),
+        }
+    }
+
+    /// Show the metric on the same line as the previous one in human-readable output.
+    fn on_same_line(mut self) -> Self {
+        self.same_line = true;
+        self
+    }
+
+    /// Show the metric as the given sentence in human-readable output.
+    fn with_sentence(mut self, sentence: String) -> Self {
+        self.sentence = Some(sentence);
+        self
+    }
+}
+
+impl MetricValue {
+    /// Format the value for human-readable output.
+    /// Unlike the [`Display`] implementation, this may round values and add units.
+    pub fn to_human_string(&self) -> String {
+        match self {
+            MetricValue::Count(_) | MetricValue::Text(_) => self.to_string(),
+            MetricValue::Length(value) => format_length(*value),
+            MetricValue::Float(value) => format!("{value:.2}"),
+            MetricValue::Percentage(value) => format!("{value:.2}%"),
+            MetricValue::Coefficient(value) => format!("{value:.4}"),
+            MetricValue::NotAvailable(reason) => format!("N/A ({reason})"),
+        }
+    }
+}
+
+/// Format a number of bases with thousands separators, followed by an approximation in kbp, Mbp or Gbp
+/// if it is at least 1000, e.g. `3,145,726,000 bp (3.15 Gbp)`.
+fn format_length(length: u64) -> String {
+    let digits = length.to_string();
+    let mut separated = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            separated.push(',');
+        }
+        separated.push(digit);
+    }
+
+    if length < 1_000 {
+        return format!("{separated} bp");
+    }
+    let (divisor, unit) = if length < 1_000_000 {
+        (1e3, "kbp")
+    } else if length < 1_000_000_000 {
+        (1e6, "Mbp")
+    } else {
+        (1e9, "Gbp")
+    };
+    format!("{separated} bp ({:.2} {unit})", length as f64 / divisor)
+}
+
+/// Formats the value in a machine-readable way.
+impl Display for MetricValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricValue::Count(value) | MetricValue::Length(value) => write!(f, "{value}"),
+            MetricValue::Float(value)
+            | MetricValue::Percentage(value)
+            | MetricValue::Coefficient(value) => write!(f, "{value}"),
+            MetricValue::Text(value) => write!(f, "{value}"),
+            MetricValue::NotAvailable(_) => write!(f, "N/A"),
+        }
+    }
+}
+
+impl AssemblySummary {
+    /// List all metrics of this summary in the order in which they are reported.
+    pub fn metrics(&self) -> Vec<Metric> {
+        let mut metrics = vec![Metric::new(
+            "record_count",
+            "# records",
+            MetricValue::Count(self.record_count as u64),
+        )];
+        if let Some(record_limit) = self.record_limit {
+            metrics.push(Metric::new(
+                "record_limit",
+                "record limit",
+                MetricValue::Count(record_limit as u64),
+            ));
+        }
+        if let Some(subsample) = &self.subsample {
+            metrics.push(Metric::new(
+                "subsample_fraction",
+                "subsample fraction",
+                MetricValue::Coefficient(subsample.fraction),
+            ));
+            metrics.push(Metric::new(
+                "subsample_seen_count",
+                "# records seen for subsampling",
+                MetricValue::Count(subsample.seen_count as u64),
+            ));
+            metrics.push(Metric::new(
+                "subsample_sampled_count",
+                "# records sampled",
+                MetricValue::Count(subsample.sampled_count as u64),
+            ));
+        }
+        if self.empty_sequence_count > 0 {
+            metrics.push(Metric::new(
+                "empty_sequence_count",
+                "# empty sequences",
+                MetricValue::Count(self.empty_sequence_count as u64),
+            ));
+        }
+        if self.unequal_quality_length_count > 0 {
+            metrics.push(Metric::new(
+                "unequal_quality_length_count",
+                "# records with unequal sequence and quality lengths",
+                MetricValue::Count(self.unequal_quality_length_count as u64),
+            ));
+        }
+        if let Some(invalid_character_count) = self.invalid_character_count {
+            metrics.push(Metric::new(
+                "invalid_character_count",
+                "# sequences with invalid characters",
+                MetricValue::Count(invalid_character_count as u64),
+            ));
+        }
+        if self.all_n_count > 0 {
+            // Both values are shown on one line, e.g. `# all-N sequences: 2 (0.50%)`.
+            metrics.push(
+                Metric::new(
+                    "all_n_count",
+                    "# all-N sequences",
+                    MetricValue::Count(self.all_n_count as u64),
+                )
+                .with_sentence(format!(
+                    "# all-N sequences: {} ({:.2}%)",
+                    self.all_n_count, self.all_n_percent
+                )),
+            );
+            metrics.push(
+                Metric::new(
+                    "all_n_percent",
+                    "% all-N sequences",
+                    MetricValue::Percentage(self.all_n_percent),
+                )
+                .with_sentence(String::new()),
+            );
+        }
+        self.excluded.push_metrics(&mut metrics);
+        if let Some(duplicate_ids) = &self.duplicate_ids {
+            duplicate_ids.push_metrics(&mut metrics);
+        }
+        if let Some(duplicate_sequences) = &self.duplicate_sequences {
+            duplicate_sequences.push_metrics(&mut metrics);
+        }
+        if let Some(lengths) = &self.lengths {
+            lengths.push_metrics(&mut metrics, "", "");
+        }
+        if let Some(ngx) = &self.ngx {
+            ngx.push_metrics(&mut metrics);
+        }
+        for long_reads in self.long_reads.iter().flatten() {
+            long_reads.push_metrics(&mut metrics);
+        }
+        if let Some(hoco_lengths) = &self.hoco_lengths {
+            hoco_lengths.push_metrics(&mut metrics, "hoco_", "hoco ");
+        }
+        if let Some(streaming) = &self.streaming {
+            streaming.push_metrics(&mut metrics);
+        }
+        if let Some(contigs) = &self.contigs {
+            metrics.push(Metric::new(
+                "contig_count",
+                "# contigs",
+                MetricValue::Count(contigs.contig_count as u64),
+            ));
+            contigs
+                .lengths
+                .push_metrics(&mut metrics, "contig_", "contig ", "", "");
+        }
+        if self.record_count > 0 {
+            self.base_composition
+                .push_metrics(&self.base_percentages, &mut metrics);
+            metrics.push(Metric::new(
+                "soft_masked_count",
+                "# soft-masked bases",
+                MetricValue::Count(self.soft_masked_count),
+            ));
+            metrics.push(Metric::new(
+                "soft_masked_percent",
+                "soft-masked fraction",
+                MetricValue::Percentage(self.soft_masked_percent),
+            ));
+            metrics.push(Metric::new(
+                "ambiguous_count",
+                "# ambiguous bases (non-N IUPAC)",
+                MetricValue::Count(self.ambiguous_count),
+            ));
+            metrics.push(Metric::new(
+                "uncertain_count",
+                "# total uncertain bases (N + IUPAC)",
+                MetricValue::Count(self.uncertain_count),
+            ));
+            metrics.push(Metric::new(
+                "cpg_count",
+                "# CpG dinucleotides",
+                MetricValue::Count(self.cpg_count),
+            ));
+            metrics.push(Metric::new(
+                "cpg_oe_ratio",
+                "CpG O/E ratio",
+                self.cpg_oe_ratio.map_or_else(
+                    || MetricValue::NotAvailable("no C or G bases".to_owned()),
+                    MetricValue::Float,
+                ),
+            ));
+        }
+        if let Some(homopolymers) = &self.homopolymers {
+            metrics.push(Metric::new(
+                "homopolymer_run_count",
+                "# homopolymer runs",
+                MetricValue::Count(homopolymers.run_count as u64),
+            ));
+            metrics.push(Metric::new(
+                "max_homopolymer_run_length",
+                "max homopolymer run length",
+                MetricValue::Length(homopolymers.max_run_length as u64),
+            ));
+            metrics.push(Metric::new(
+                "mean_homopolymer_run_length",
+                "mean homopolymer run length",
+                MetricValue::Float(homopolymers.mean_run_length),
+            ));
+            metrics.push(Metric::new(
+                "homopolymer_run_length_n50",
+                "homopolymer run length N50",
+                MetricValue::Length(homopolymers.run_length_n50 as u64),
+            ));
+        }
+        if let Some(quality) = &self.quality {
+            quality.push_metrics(&mut metrics);
+        }
+        if let Some(poly_tails) = &self.poly_tails {
+            poly_tails.push_metrics(&mut metrics);
+        }
+        if let Some(telomeres) = &self.telomeres {
+            telomeres.push_metrics(&mut metrics);
+        }
+        for adapter in self.adapters.iter().flatten() {
+            adapter.push_metrics(&mut metrics);
+        }
+        if let Some(kmers) = &self.kmers {
+            kmers.push_metrics(&mut metrics);
+        }
+        metrics
+    }
+}
+
+impl PairedSummary {
+    /// List the metrics about the concordance of the pairs, without the metrics of the individual ends.
+    pub fn pair_metrics(&self) -> Vec<Metric> {
+        vec![
+            Metric::new(
+                "r1_read_count",
+                "# R1 reads",
+                MetricValue::Count(self.r1_read_count as u64),
+            ),
+            Metric::new(
+                "r2_read_count",
+                "# R2 reads",
+                MetricValue::Count(self.r2_read_count as u64),
+            ),
+            Metric::new(
+                "mismatched_pair_count",
+                "# pairs with mismatched ids",
+                MetricValue::Count(self.mismatched_pair_count as u64),
+            ),
+        ]
+    }
+}
+
+impl ExcludedCounts {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        for (key, label, count) in [
+            ("excluded_by_id", "# records excluded by id", self.by_id),
+            (
+                "excluded_by_id_regex",
+                "# records excluded by id regex",
+                self.by_id_regex,
+            ),
+            (
+                "excluded_not_included",
+                "# records not among included ids",
+                self.not_included,
+            ),
+            (
+                "excluded_by_description",
+                "# records excluded by description",
+                self.by_description,
+            ),
+            (
+                "excluded_too_short",
+                "# records shorter than minimum length",
+                self.too_short,
+            ),
+            (
+                "excluded_too_long",
+                "# records longer than maximum length",
+                self.too_long,
+            ),
+            (
+                "excluded_by_gc",
+                "# records outside of GC range",
+                self.by_gc,
+            ),
+            (
+                "excluded_by_n_fraction",
+                "# records above maximum N fraction",
+                self.by_n_fraction,
+            ),
+            (
+                "excluded_low_complexity",
+                "# records below minimum complexity",
+                self.low_complexity,
+            ),
+            ("excluded_all_n", "# all-N records excluded", self.all_n),
+        ] {
+            if let Some(count) = count {
+                metrics.push(Metric::new(key, label, MetricValue::Count(count as u64)));
+            }
+        }
+    }
+}
+
+impl DuplicateIdSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        for (key, label, count) in [
+            ("id_count", "# ids", self.id_count),
+            ("unique_id_count", "# unique ids", self.unique_id_count),
+            (
+                "duplicate_id_count",
+                "# duplicate ids",
+                self.duplicate_id_count,
+            ),
+        ] {
+            metrics.push(Metric::new(key, label, MetricValue::Count(count as u64)));
+        }
+    }
+}
+
+impl DuplicateSequenceSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        // Without verification, the sequences are only known to have the same hash.
+        let qualifier = if self.verified { "" } else { "potential " };
+        metrics.push(Metric::new(
+            "duplicate_sequence_count",
+            format!("# {qualifier}duplicate sequences"),
+            MetricValue::Count(self.duplicate_count as u64),
+        ));
+        metrics.push(Metric::new(
+            "duplicate_sequence_group_count",
+            format!("# groups of {qualifier}duplicate sequences"),
+            MetricValue::Count(self.group_count as u64),
+        ));
+    }
+}
+
+impl LengthSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>, key_prefix: &str, label_prefix: &str) {
+        metrics.push(Metric::new(
+            format!("{key_prefix}n_bases"),
+            format!("{label_prefix}# Ns"),
+            MetricValue::Count(self.n_bases),
+        ));
+        metrics.push(Metric::new(
+            format!("{key_prefix}n_fraction"),
+            format!("{label_prefix}N fraction"),
+            MetricValue::Percentage(self.n_percent),
+        ));
+        metrics.push(Metric::new(
+            format!("{key_prefix}non_n_fraction"),
+            format!("{label_prefix}non-N fraction"),
+            MetricValue::Percentage(self.non_n_percent),
+        ));
+        self.gc.push_metrics(metrics, key_prefix, label_prefix);
+        self.with_ns
+            .push_metrics(metrics, key_prefix, label_prefix, "", "");
+        self.without_ns.push_metrics(
+            metrics,
+            key_prefix,
+            label_prefix,
+            "_without_ns",
+            " without Ns",
+        );
+        for top_fraction in &self.top_fractions {
+            metrics.push(
+                Metric::new(
+                    format!(
+                        "{key_prefix}top{}_bases_percent",
+                        top_fraction.sequence_percent
+                    ),
+                    format!(
+                        "{label_prefix}% of bases in top {}% of sequences",
+                        top_fraction.sequence_percent
+                    ),
+                    MetricValue::Percentage(top_fraction.bases_percent),
+                )
+                .with_sentence(format!(
+                    "{label_prefix}top {}% of sequences contain {:.1}% of bases",
+                    top_fraction.sequence_percent, top_fraction.bases_percent
+                )),
+            );
+        }
+        // The percentiles form a single row, e.g. `P10: X   P25: Y   P50: Z`.
+        for (index, length_percentile) in self.length_percentiles.iter().enumerate() {
+            let metric = Metric::new(
+                format!("{key_prefix}p{}_len", length_percentile.percentile),
+                format!("{label_prefix}P{}", length_percentile.percentile),
+                MetricValue::Float(length_percentile.length),
+            );
+            metrics.push(if index == 0 {
+                metric
+            } else {
+                metric.on_same_line()
+            });
+        }
+        metrics.push(Metric::new(
+            format!("{key_prefix}gini_coefficient"),
+            format!("{label_prefix}Gini coefficient"),
+            MetricValue::Coefficient(self.gini_coefficient),
+        ));
+    }
+}
+
+impl LengthThresholdSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        let threshold = self.threshold;
+        metrics.push(Metric::new(
+            format!("count_min_{threshold}"),
+            format!("# sequences >= {threshold} bp"),
+            MetricValue::Count(self.count as u64),
+        ));
+        metrics.push(Metric::new(
+            format!("count_percent_min_{threshold}"),
+            format!("% sequences >= {threshold} bp"),
+            MetricValue::Percentage(self.count_percent),
+        ));
+        metrics.push(Metric::new(
+            format!("total_length_min_{threshold}"),
+            format!("total length of sequences >= {threshold} bp"),
+            MetricValue::Length(self.total_length),
+        ));
+        metrics.push(Metric::new(
+            format!("length_percent_min_{threshold}"),
+            format!("% of total length in sequences >= {threshold} bp"),
+            MetricValue::Percentage(self.length_percent),
+        ));
+    }
+}
+
+impl NgxSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        metrics.push(Metric::new(
+            "genome_size",
+            "genome size",
+            MetricValue::Length(self.genome_size),
+        ));
+        for (percentile, ngx) in [(50, self.ng50), (75, self.ng75), (90, self.ng90)] {
+            metrics.push(Metric::new(
+                format!("ng{percentile}"),
+                format!("NG{percentile}"),
+                if let Some(ngx) = ngx {
+                    MetricValue::Length(ngx as u64)
+                } else {
+                    MetricValue::NotAvailable(format!(
+                        "the sequences cover less than {percentile}% of the genome size"
+                    ))
+                },
+            ));
+        }
+        for (percentile, lgx) in [(50, self.lg50), (75, self.lg75), (90, self.lg90)] {
+            metrics.push(Metric::new(
+                format!("lg{percentile}"),
+                format!("LG{percentile}"),
+                if let Some(lgx) = lgx {
+                    MetricValue::Count(lgx as u64)
+                } else {
+                    MetricValue::NotAvailable(format!(
+                        "the sequences cover less than {percentile}% of the genome size"
+                    ))
+                },
+            ));
+        }
+        metrics.push(Metric::new("aung", "auNG", MetricValue::Float(self.aung)));
+    }
+}
+
+impl BaseComposition {
+    fn push_metrics(&self, percentages: &BasePercentages, metrics: &mut Vec<Metric>) {
+        for (key, label, count, percent) in [
+            ("a", "A", self.a, percentages.a),
+            ("c", "C", self.c, percentages.c),
+            ("g", "G", self.g, percentages.g),
+            ("t", "T", self.t, percentages.t),
+            ("n", "N", self.n, percentages.n),
+            ("other", "other", self.other, percentages.other),
+        ] {
+            metrics.push(Metric::new(
+                format!("{key}_count"),
+                format!("# {label}"),
+                MetricValue::Count(count),
+            ));
+            metrics.push(Metric::new(
+                format!("{key}_percent"),
+                format!("{label}%"),
+                MetricValue::Percentage(percent),
+            ));
+        }
+        metrics.push(Metric::new(
+            "other_characters",
+            "other characters",
+            MetricValue::Text(self.other_characters_string()),
+        ));
+    }
+}
+
+impl QualitySummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        for (key, label, value) in [
+            ("mean_quality", "mean read quality", self.mean_quality),
+            (
+                "min_mean_quality",
+                "min mean read quality",
+                self.min_mean_quality,
+            ),
+            (
+                "max_mean_quality",
+                "max mean read quality",
+                self.max_mean_quality,
+            ),
+            (
+                "q1_mean_quality",
+                "Q1 mean read quality",
+                self.q1_mean_quality,
+            ),
+            (
+                "median_mean_quality",
+                "median mean read quality",
+                self.median_mean_quality,
+            ),
+            (
+                "q3_mean_quality",
+                "Q3 mean read quality",
+                self.q3_mean_quality,
+            ),
+            (
+                "iqr_mean_quality",
+                "IQR mean read quality",
+                self.iqr_mean_quality,
+            ),
+        ] {
+            metrics.push(Metric::new(key, label, MetricValue::Float(value)));
+        }
+        for (key, label, value) in [
+            (
+                "mean_error_percent",
+                "mean read error rate",
+                self.mean_error_percent,
+            ),
+            (
+                "median_error_percent",
+                "median read error rate",
+                self.median_error_percent,
+            ),
+            (
+                "max_one_expected_error_percent",
+                "reads with <= 1 expected error",
+                self.max_one_expected_error_percent,
+            ),
+        ] {
+            metrics.push(Metric::new(key, label, MetricValue::Percentage(value)));
+        }
+        for (quality, count, percent) in [
+            (20, self.q20_count, self.q20_percent),
+            (30, self.q30_count, self.q30_percent),
+        ] {
+            metrics.push(Metric::new(
+                format!("q{quality}_count"),
+                format!("# Q{quality} bases"),
+                MetricValue::Count(count),
+            ));
+            metrics.push(Metric::new(
+                format!("q{quality}_fraction"),
+                format!("Q{quality} fraction"),
+                MetricValue::Percentage(percent),
+            ));
+        }
+    }
+}
+
+impl StreamingSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        metrics.push(Metric::new(
+            "n_bases",
+            "# Ns",
+            MetricValue::Count(self.n_bases),
+        ));
+        self.gc.push_metrics(metrics, "", "");
+        metrics.push(Metric::new(
+            "total_length",
+            "total length",
+            MetricValue::Length(self.total_length),
+        ));
+        metrics.push(Metric::new(
+            "sample_size",
+            "# sequence lengths sampled for the approximate Nx",
+            MetricValue::Count(self.sample_size as u64),
+        ));
+        for (key, label, value) in [
+            ("approximate_n50", "approximate N50", self.approximate_n50),
+            ("approximate_n75", "approximate N75", self.approximate_n75),
+            ("approximate_n90", "approximate N90", self.approximate_n90),
+        ] {
+            metrics.push(Metric::new(key, label, MetricValue::Length(value as u64)));
+        }
+        metrics.push(Metric::new(
+            "mean_len",
+            "mean len",
+            MetricValue::Float(self.mean_len),
+        ));
+        metrics.push(Metric::new(
+            "max_len",
+            "max len",
+            MetricValue::Length(self.max_len as u64),
+        ));
+        metrics.push(Metric::new(
+            "min_len",
+            "min len",
+            MetricValue::Length(self.min_len as u64),
+        ));
+    }
+}
+
+impl AdapterSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        let adapter = &self.adapter;
+        metrics.push(Metric::new(
+            format!("adapter_{adapter}_read_count"),
+            format!("# reads with adapter {adapter}"),
+            MetricValue::Count(self.read_count as u64),
+        ));
+        metrics.push(Metric::new(
+            format!("adapter_{adapter}_read_percent"),
+            format!("reads with adapter {adapter}"),
+            MetricValue::Percentage(self.read_percent),
+        ));
+        metrics.push(Metric::new(
+            format!("adapter_{adapter}_mean_position"),
+            format!("mean position of adapter {adapter}"),
+            self.mean_first_position.map_or_else(
+                || MetricValue::NotAvailable("no reads with adapter".to_owned()),
+                MetricValue::Float,
+            ),
+        ));
+    }
+}
+
+impl TelomereSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        metrics.push(Metric::new(
+            "telomere_motif",
+            "telomere motif",
+            MetricValue::Text(self.motif.clone()),
+        ));
+        metrics.push(Metric::new(
+            "telomere_min_repeats",
+            "min telomere repeats",
+            MetricValue::Count(self.min_repeats as u64),
+        ));
+        metrics.push(Metric::new(
+            "telomere_repeat_count",
+            "# telomere repeats",
+            MetricValue::Count(self.repeat_count),
+        ));
+        metrics.push(Metric::new(
+            "telomere_repeat_percent",
+            "telomere repeat fraction",
+            MetricValue::Percentage(self.repeat_percent),
+        ));
+        for (key, label, count) in [
+            ("telomere_start_count", "start", self.start_count),
+            ("telomere_end_count", "end", self.end_count),
+            (
+                "telomere_both_ends_count",
+                "both ends",
+                self.both_ends_count,
+            ),
+        ] {
+            metrics.push(Metric::new(
+                key,
+                format!("# sequences with telomere at {label}"),
+                MetricValue::Count(count as u64),
+            ));
+        }
+    }
+}
+
+impl KmerSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        metrics.push(Metric::new(
+            "kmer_length",
+            "k-mer length",
+            MetricValue::Length(self.k as u64),
+        ));
+        metrics.push(Metric::new(
+            "kmer_count",
+            "# k-mers",
+            MetricValue::Count(self.kmer_count),
+        ));
+        metrics.push(Metric::new(
+            "distinct_kmer_count",
+            "# distinct k-mers",
+            MetricValue::Count(self.distinct_count),
+        ));
+        metrics.push(Metric::new(
+            "unique_kmer_count",
+            "# unique k-mers",
+            MetricValue::Count(self.unique_count),
+        ));
+        metrics.push(Metric::new(
+            "unique_kmer_fraction",
+            "unique k-mer fraction",
+            MetricValue::Percentage(self.unique_percent),
+        ));
+    }
+}
+
+impl PolyTailSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>) {
+        metrics.push(Metric::new(
+            "min_poly_tail_length",
+            "min poly tail length",
+            MetricValue::Length(self.min_length as u64),
+        ));
+        for (key, label, tails) in [
+            ("poly_g", "polyG", &self.poly_g),
+            ("poly_a", "polyA", &self.poly_a),
+        ] {
+            let no_tails = || MetricValue::NotAvailable("no tails".to_owned());
+            metrics.push(Metric::new(
+                format!("{key}_tail_count"),
+                format!("# sequences with {label} tail"),
+                MetricValue::Count(tails.count as u64),
+            ));
+            metrics.push(Metric::new(
+                format!("{key}_tail_percent"),
+                format!("sequences with {label} tail"),
+                MetricValue::Percentage(tails.percent),
+            ));
+            metrics.push(Metric::new(
+                format!("mean_{key}_tail_length"),
+                format!("mean {label} tail length"),
+                tails.mean_length.map_or_else(no_tails, MetricValue::Float),
+            ));
+            metrics.push(Metric::new(
+                format!("p95_{key}_tail_length"),
+                format!("P95 {label} tail length"),
+                tails.p95_length.map_or_else(no_tails, MetricValue::Float),
+            ));
+        }
+    }
+}
+
+impl GcSummary {
+    fn push_metrics(&self, metrics: &mut Vec<Metric>, key_prefix: &str, label_prefix: &str) {
+        let mut push = |key: &str, label: &str, value| {
+            metrics.push(Metric::new(
+                format!("{key_prefix}{key}"),
+                format!("{label_prefix}{label}"),
+                value,
+            ))
+        };
+
+        push(
+            "gc_percent",
+            "GC%",
+            MetricValue::Percentage(self.gc_percent),
+        );
+        push(
+            "min_gc_percent",
+            "min GC%",
+            MetricValue::Percentage(self.min_gc_percent),
+        );
+        push(
+            "max_gc_percent",
+            "max GC%",
+            MetricValue::Percentage(self.max_gc_percent),
+        );
+        push(
+            "mean_gc_percent",
+            "mean GC%",
+            MetricValue::Percentage(self.mean_gc_percent),
+        );
+        push(
+            "undefined_gc_count",
+            "# sequences with undefined GC% (counted as 0%)",
+            MetricValue::Count(self.undefined_gc_count as u64),
+        );
+    }
+}
+
+impl NxSummary {
+    fn push_metrics(
+        &self,
+        metrics: &mut Vec<Metric>,
+        key_prefix: &str,
+        label_prefix: &str,
+        key_suffix: &str,
+        label_suffix: &str,
+    ) {
+        let mut push = |key: &str, label: &str, value| {
+            metrics.push(Metric::new(
+                format!("{key_prefix}{key}{key_suffix}"),
+                format!("{label_prefix}{label}{label_suffix}"),
+                value,
+            ))
+        };
+
+        push(
+            "total_length",
+            "total length",
+            MetricValue::Length(self.total_length),
+        );
+        push("n50", "N50", MetricValue::Length(self.n50 as u64));
+        push("n75", "N75", MetricValue::Length(self.n75 as u64));
+        push("n90", "N90", MetricValue::Length(self.n90 as u64));
+        for percentile in &self.additional_percentiles {
+            push(
+                &format!("n{}", percentile.percentile),
+                &format!("N{}", percentile.percentile),
+                MetricValue::Length(percentile.nx as u64),
+            );
+        }
+        push("l50", "L50", MetricValue::Count(self.l50 as u64));
+        push("l75", "L75", MetricValue::Count(self.l75 as u64));
+        push("l90", "L90", MetricValue::Count(self.l90 as u64));
+        for percentile in &self.additional_percentiles {
+            push(
+                &format!("l{}", percentile.percentile),
+                &format!("L{}", percentile.percentile),
+                MetricValue::Count(percentile.lx as u64),
+            );
+        }
+        push("aun", "auN", MetricValue::Float(self.aun));
+        push("e_size", "E-size", MetricValue::Float(self.e_size));
+        push("mean_len", "mean len", MetricValue::Float(self.mean_len));
+        push(
+            "median_len",
+            "median len",
+            MetricValue::Float(self.median_len),
+        );
+        push(
+            "std_dev_len",
+            "std dev len",
+            MetricValue::Float(self.std_dev_len),
+        );
+        push("cv_len", "cv len", MetricValue::Percentage(self.cv_len));
+        push(
+            "max_len",
+            "max len",
+            MetricValue::Length(self.max_len as u64),
+        );
+        push(
+            "min_len",
+            "min len",
+            MetricValue::Length(self.min_len as u64),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::MetricValue;
+    use crate::{basic_statistics, StatisticsOptions};
+    #[cfg(feature = "serde")]
+    use crate::{LENGTH_PERCENTILES, TOP_FRACTIONS};
+    #[cfg(feature = "serde")]
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_human_length() {
+        let human = |length| MetricValue::Length(length).to_human_string();
+        assert_eq!(human(0), "0 bp");
+        assert_eq!(human(999), "999 bp");
+        assert_eq!(human(1_000), "1,000 bp (1.00 kbp)");
+        assert_eq!(human(123_456_789), "123,456,789 bp (123.46 Mbp)");
+        assert_eq!(human(3_145_726_000), "3,145,726,000 bp (3.15 Gbp)");
+        assert_eq!(
+            MetricValue::Length(3_145_726_000_u64).to_string(),
+            "3145726000"
+        );
+    }
+
+    #[test]
+    fn test_all_n_metrics() {
+        let fasta = b">1\nNNNN\n>2\nACGT\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let metrics = summary.metrics();
+        let metric = |key| metrics.iter().find(|metric| metric.key == key).unwrap();
+        assert_eq!(
+            metric("all_n_count").to_human_string(),
+            "# all-N sequences: 1 (50.00%)"
+        );
+        assert_eq!(metric("all_n_percent").value, MetricValue::Percentage(50.0));
+        assert_eq!(metric("all_n_percent").to_human_string(), "");
+    }
+
+    #[test]
+    fn test_metric_keys() {
+        let fasta = b">1\nACGTN\n>2\nAACC\n";
+        let options = StatisticsOptions {
+            additional_percentiles: vec![10],
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        let metrics = summary.metrics();
+        let keys: Vec<_> = metrics.iter().map(|metric| metric.key.as_str()).collect();
+
+        assert_eq!(keys[0], "record_count");
+        assert!(keys.contains(&"n90_without_ns"));
+        assert!(keys.contains(&"hoco_l10"));
+        assert!(keys.contains(&"hoco_min_len_without_ns"));
+        assert!(keys.contains(&"contig_e_size"));
+        let percentiles: Vec<_> = metrics
+            .iter()
+            .filter(|metric| metric.key.starts_with('p') && metric.key.ends_with("_len"))
+            .map(|metric| (metric.label.as_str(), metric.same_line))
+            .collect();
+        assert_eq!(
+            percentiles,
+            [
+                ("P10", false),
+                ("P25", true),
+                ("P50", true),
+                ("P75", true),
+                ("P90", true)
+            ]
+        );
+        assert_eq!(
+            metrics
+                .iter()
+                .find(|metric| metric.key == "hoco_total_length")
+                .unwrap()
+                .value
+                .to_string(),
+            "7"
+        );
+        assert_eq!(
+            metrics
+                .iter()
+                .find(|metric| metric.key == "top50_bases_percent")
+                .unwrap()
+                .to_human_string(),
+            "top 50% of sequences contain 55.6% of bases"
+        );
+        assert!(keys.iter().all(|key| key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')));
+    }
+
+    /// The json pointer of the value of the metric with the given key, for the options of [`test_json_keys`].
+    #[cfg(feature = "serde")]
+    fn json_pointer(key: &str) -> String {
+        let (mut prefix, key) = if let Some(key) = key.strip_prefix("hoco_") {
+            ("/hoco".to_owned(), key)
+        } else if let Some(key) = key.strip_prefix("contig_").filter(|&key| key != "count") {
+            ("/contigs".to_owned(), key)
+        } else {
+            (String::new(), key)
+        };
+        let key = if let Some(key) = key.strip_suffix("_without_ns") {
+            prefix.push_str("/without_ns");
+            key
+        } else {
+            key
+        };
+
+        let index = |values: &[u8], value: &str| {
+            values
+                .iter()
+                .position(|candidate| candidate.to_string() == value)
+                .unwrap()
+        };
+        let path = match key {
+            "n10" => "additional_percentiles/0/nx".to_owned(),
+            "l10" => "additional_percentiles/0/lx".to_owned(),
+            "n_fraction" | "non_n_fraction" => key.replace("_fraction", "_percent"),
+            "contig_count" => "contigs/contig_count".to_owned(),
+            "id_count" | "unique_id_count" | "duplicate_id_count" => format!("duplicate_ids/{key}"),
+            "duplicate_sequence_count" => "duplicate_sequences/duplicate_count".to_owned(),
+            "duplicate_sequence_group_count" => "duplicate_sequences/group_count".to_owned(),
+            "genome_size" | "ng50" | "ng75" | "ng90" | "lg50" | "lg75" | "lg90" | "aung" => {
+                format!("ngx/{key}")
+            }
+            "a_count" | "c_count" | "g_count" | "t_count" | "n_count" | "other_count"
+            | "other_characters" => {
+                format!("base_composition/{}", key.trim_end_matches("_count"))
+            }
+            "a_percent" | "c_percent" | "g_percent" | "t_percent" | "n_percent"
+            | "other_percent" => format!("base_percentages/{}", key.trim_end_matches("_percent")),
+            "min_poly_tail_length" => "poly_tails/min_length".to_owned(),
+            "kmer_length" => "kmers/k".to_owned(),
+            "kmer_count" | "distinct_kmer_count" | "unique_kmer_count" | "unique_kmer_fraction" => {
+                format!(
+                    "kmers/{}",
+                    key.replacen("_kmer", "", 1).replace("fraction", "percent")
+                )
+            }
+            _ => {
+                if let Some(key) = key.strip_prefix("subsample_") {
+                    format!("subsample/{key}")
+                } else if let Some(key) = key.strip_prefix("excluded_") {
+                    format!("excluded/{key}")
+                } else if let Some(key) = key.strip_prefix("telomere_") {
+                    format!("telomeres/{key}")
+                } else if let Some(key) = key.strip_suffix("_min_10") {
+                    format!("long_reads/0/{key}")
+                } else if let Some(key) = key.strip_prefix("adapter_ACGT_") {
+                    format!(
+                        "adapters/0/{}",
+                        key.replace("mean_position", "mean_first_position")
+                    )
+                } else if key.contains("homopolymer") {
+                    format!("homopolymers/{}", key.replacen("homopolymer_", "", 1))
+                } else if key.contains("mean_quality")
+                    || key.contains("error")
+                    || key.starts_with('q')
+                {
+                    format!("quality/{}", key.replace("fraction", "percent"))
+                } else if key.contains("poly_") {
+                    let base = if key.contains("poly_g") {
+                        "poly_g"
+                    } else {
+                        "poly_a"
+                    };
+                    // E.g. `mean_poly_g_tail_length` is `mean_length` of `poly_g`.
+                    let key = key.replace(&format!("{base}_tail_"), "");
+                    format!("poly_tails/{base}/{key}")
+                } else if let Some(percent) = key
+                    .strip_prefix("top")
+                    .and_then(|key| key.strip_suffix("_bases_percent"))
+                {
+                    format!(
+                        "top_fractions/{}/bases_percent",
+                        index(&TOP_FRACTIONS, percent)
+                    )
+                } else if let Some(percentile) = key
+                    .strip_prefix('p')
+                    .and_then(|key| key.strip_suffix("_len"))
+                {
+                    format!(
+                        "length_percentiles/{}/length",
+                        index(&LENGTH_PERCENTILES, percentile)
+                    )
+                } else {
+                    key.to_owned()
+                }
+            }
+        };
+        format!("{prefix}/{path}")
+    }
+
+    /// Collect the json pointers of the scalar values of the json value.
+    #[cfg(feature = "serde")]
+    fn json_scalars(value: &serde_json::Value, pointer: String, pointers: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object {
+                    json_scalars(value, format!("{pointer}/{key}"), pointers);
+                }
+            }
+            serde_json::Value::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    json_scalars(value, format!("{pointer}/{index}"), pointers);
+                }
+            }
+            _ => {
+                pointers.insert(pointer);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_keys() {
+        let fastq = b"@1 a\nACGTNNNNNNNNNNNNNACGGGG\n+\nIIIIIIIIIIIIIIIIIIIIIIII\n\
+            @2\nAACCaaaaaaaaRaaTTAGGGTTAGGGAAAA\n+\nIIIIIIIIIIIIIIIIIIIIIIIIIIIIIII\n\
+            @3\nAACCaaaaaaaaRaaTTAGGGTTAGGGAAAA\n+\n+++++++++++++++++++++++++++++++\n\
+            @empty\n\n+\n\n@unequal\nAC\n+\nI\n@all_n\nNNNN\n+\nIIII\n@limit\nA\n+\nI\n";
+        let options = StatisticsOptions {
+            additional_percentiles: vec![10],
+            min_length: Some(1),
+            genome_size: Some(100),
+            long_read_thresholds: vec![10],
+            poly_tail_min_length: Some(3),
+            telomere_motif: Some("TTAGGG".to_owned()),
+            min_telomere_repeats: 1,
+            adapters: vec!["ACGT".to_owned()],
+            kmer_length: Some(3),
+            check_duplicate_ids: true,
+            check_duplicate_sequences: true,
+            check_characters: true,
+            subsample: Some(1.0),
+            limit: Some(6),
+            ..Default::default()
+        };
+        let summary = basic_statistics(fastq.as_slice(), fastq.len() as u64, &options).unwrap();
+        let json = serde_json::to_value(&summary).unwrap();
+
+        let mut metric_pointers = BTreeSet::new();
+        for metric in summary.metrics() {
+            let pointer = json_pointer(&metric.key);
+            let value = json
+                .pointer(&pointer)
+                .unwrap_or_else(|| panic!("{} is not at {pointer} in json", metric.key));
+            match &metric.value {
+                MetricValue::Count(count) | MetricValue::Length(count) => {
+                    assert_eq!(value.as_u64(), Some(*count), "{}", metric.key)
+                }
+                MetricValue::Float(float)
+                | MetricValue::Percentage(float)
+                | MetricValue::Coefficient(float) => {
+                    assert_eq!(value.as_f64(), Some(*float), "{}", metric.key)
+                }
+                // The set of other characters is serialised as a list of bytes.
+                MetricValue::Text(text) => {
+                    assert!(value.is_array() || value.as_str() == Some(text))
+                }
+                MetricValue::NotAvailable(_) => assert!(value.is_null(), "{}", metric.key),
+            }
+            metric_pointers.insert(pointer);
+        }
+
+        let mut json_pointers = BTreeSet::new();
+        json_scalars(&json, String::new(), &mut json_pointers);
+        // Parameters identifying the elements of lists, data that only fits structured formats,
+        // and details that are reflected in the labels of other metrics.
+        json_pointers.retain(|pointer| {
+            let field = pointer.rsplit('/').next().unwrap();
+            ![
+                "percentile",
+                "threshold",
+                "adapter",
+                "sequence_percent",
+                "sequence_count",
+                "verified",
+                "min_quality_byte",
+                "max_quality_byte",
+            ]
+            .contains(&field)
+                && ![
+                    "/base_composition/other_characters/",
+                    "/homopolymers/run_length_counts/",
+                    "/quality/mean_quality_histogram/",
+                    "/telomeres/sequences/",
+                    "/duplicate_sequences/groups/",
+                ]
+                .iter()
+                .any(|prefix| pointer.starts_with(prefix))
+        });
+        // The list of other characters is a single metric.
+        json_pointers.insert("/base_composition/other_characters".to_owned());
+        assert!(json_pointers.len() > 200);
+        assert_eq!(metric_pointers, json_pointers);
+    }
+}
@@ -0,0 +1,64 @@
+//! Write statistics as MultiQC custom content, such that they appear in the general statistics table of a MultiQC report.
+//!
+//! Only available with the `serde` feature.
+
+use crate::metrics::MetricValue;
+use crate::AssemblySummary;
+use serde_json::{json, Map, Value};
+
+/// Build a MultiQC custom content document with one row per sample in the general statistics table.
+/// The columns are the keys of the metrics, and metrics that are not available are omitted.
+pub fn multiqc_report(samples: &[(String, &AssemblySummary)]) -> Value {
+    let data: Map<String, Value> = samples
+        .iter()
+        .map(|(sample, summary)| {
+            let metrics: Map<String, Value> = summary
+                .metrics()
+                .into_iter()
+                .filter_map(|metric| {
+                    let value = match metric.value {
+                        MetricValue::Count(value) | MetricValue::Length(value) => json!(value),
+                        MetricValue::Float(value)
+                        | MetricValue::Percentage(value)
+                        | MetricValue::Coefficient(value) => json!(value),
+                        MetricValue::Text(value) => json!(value),
+                        MetricValue::NotAvailable(_) => return None,
+                    };
+                    Some((metric.key, value))
+                })
+                .collect();
+            (sample.clone(), Value::Object(metrics))
+        })
+        .collect();
+
+    json!({
+        "id": "fastx_statistics",
+        "section_name": "fastx-statistics",
+        "description": concat!("Sequence statistics computed by fastx-statistics ", env!("CARGO_PKG_VERSION"), "."),
+        "plot_type": "generalstats",
+        "data": data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multiqc::multiqc_report;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_multiqc_report() {
+        let fasta = b">1\nACGT\n>2\nAC\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let report = multiqc_report(&[("assembly".to_owned(), &summary)]);
+        assert_eq!(report["plot_type"], "generalstats");
+        let data = &report["data"]["assembly"];
+        assert_eq!(data["record_count"], 2);
+        assert_eq!(data["n50"], 4);
+        assert_eq!(data["gc_percent"], 50.0);
+    }
+}
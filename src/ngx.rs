@@ -0,0 +1,158 @@
+//! Length statistics relative to an expected genome size.
+
+use crate::percentage_of;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The NGx metrics of a set of sequence lengths.
+/// They are like the Nx metrics, but relative to the expected genome size instead of the total length.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NgxSummary {
+    pub genome_size: u64,
+    /// `None` if the sequences cover less than half of the genome size.
+    pub ng50: Option<usize>,
+    /// `None` if the sequences cover less than 75% of the genome size.
+    pub ng75: Option<usize>,
+    /// `None` if the sequences cover less than 90% of the genome size.
+    pub ng90: Option<usize>,
+    /// The number of sequences needed to cover 50% of the genome size, `None` if they cover less.
+    pub lg50: Option<usize>,
+    /// The number of sequences needed to cover 75% of the genome size, `None` if they cover less.
+    pub lg75: Option<usize>,
+    /// The number of sequences needed to cover 90% of the genome size, `None` if they cover less.
+    pub lg90: Option<usize>,
+    /// The area under the NGx curve.
+    pub aung: f64,
+}
+
+impl NgxSummary {
+    /// Compute the statistics of the given list of sequence lengths, which must be sorted in descending order.
+    pub fn new(sorted_sequence_lengths: &[usize], genome_size: u64) -> Self {
+        let [(ng50, lg50), (ng75, lg75), (ng90, lg90)] = [50, 75, 90].map(|percentile| {
+            ngx_lgx(sorted_sequence_lengths, genome_size, percentile)
+                .map_or((None, None), |(ngx, lgx)| (Some(ngx), Some(lgx)))
+        });
+        Self {
+            genome_size,
+            ng50,
+            ng75,
+            ng90,
+            lg50,
+            lg75,
+            lg90,
+            aung: aung(sorted_sequence_lengths, genome_size),
+        }
+    }
+}
+
+/// Compute the NGx and LGx metrics of the given lengths, which must be sorted in descending order.
+/// The LGx metric is the number of sequences needed to cover the given percentage of the genome size.
+/// Returns `None` if the lengths do not sum up to the required number of bases.
+pub fn ngx_lgx(sorted: &[usize], genome_size: u64, percentile: u8) -> Option<(usize, usize)> {
+    debug_assert!(sorted.windows(2).all(|w| w[0] >= w[1]));
+
+    let required_covered_bases = percentage_of(genome_size, percentile);
+    let mut sum = 0;
+    for (index, len) in sorted.iter().copied().enumerate() {
+        sum += len as u64;
+        if sum >= required_covered_bases {
+            return Some((len, index + 1));
+        }
+    }
+
+    None
+}
+
+/// Compute the NGx and LGx metrics of the given lengths for multiple percentiles in a single pass.
+/// The lengths must be sorted in descending order, and the percentiles must be sorted in ascending order.
+/// Returns `None` for the percentiles whose required number of bases the lengths do not sum up to.
+pub fn compute_all_ngx_lgx(
+    sorted: &[usize],
+    genome_size: u64,
+    percentiles: &[u8],
+) -> Vec<Option<(usize, usize)>> {
+    debug_assert!(sorted.windows(2).all(|w| w[0] >= w[1]));
+    debug_assert!(percentiles.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut ngx_lgxs = Vec::with_capacity(percentiles.len());
+    let mut lengths = sorted.iter().copied();
+    let mut ngx_lgx = None;
+    let mut covered_bases = 0;
+    for &percentile in percentiles {
+        let required_covered_bases = percentage_of(genome_size, percentile);
+        // Like in `ngx_lgx`, at least one length is needed, even if no bases need to be covered.
+        while ngx_lgx.is_none() || covered_bases < required_covered_bases {
+            if let Some(len) = lengths.next() {
+                covered_bases += len as u64;
+                ngx_lgx = Some((len, ngx_lgx.map_or(1, |(_, lgx)| lgx + 1)));
+            } else {
+                // The percentiles are ascending, so the remaining ones are not reached either.
+                ngx_lgxs.resize(percentiles.len(), None);
+                return ngx_lgxs;
+            }
+        }
+        ngx_lgxs.push(ngx_lgx);
+    }
+    ngx_lgxs
+}
+
+/// Compute the area under the NGx curve of the given lengths, which must be sorted in descending order.
+/// This is the sum of the squared lengths divided by the genome size.
+pub fn aung(sorted_lengths: &[usize], genome_size: u64) -> f64 {
+    debug_assert!(sorted_lengths.windows(2).all(|w| w[0] >= w[1]));
+
+    if genome_size == 0 {
+        return 0.0;
+    }
+
+    let squared_sum: u128 = sorted_lengths
+        .iter()
+        .map(|len| (*len as u128) * (*len as u128))
+        .sum();
+    squared_sum as f64 / genome_size as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ngx::{aung, compute_all_ngx_lgx, ngx_lgx, NgxSummary};
+
+    #[test]
+    fn test_ngx() {
+        let lengths = [29, 24, 21, 12, 9];
+        assert_eq!(ngx_lgx(&lengths, 100, 50), Some((24, 2)));
+        assert_eq!(ngx_lgx(&lengths, 100, 0), Some((29, 1)));
+        assert_eq!(ngx_lgx(&lengths, 200, 50), None);
+        assert_eq!(
+            NgxSummary::new(&lengths, 120),
+            NgxSummary {
+                genome_size: 120,
+                ng50: Some(21),
+                ng75: Some(9),
+                ng90: None,
+                lg50: Some(3),
+                lg75: Some(5),
+                lg90: None,
+                aung: 2083.0 / 120.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_all_ngx_lgx() {
+        let lengths = [29, 24, 21, 12, 9];
+        let percentiles: Vec<_> = (0..=100).collect();
+        let ngx_lgxs = compute_all_ngx_lgx(&lengths, 120, &percentiles);
+        for (percentile, actual) in percentiles.into_iter().zip(ngx_lgxs) {
+            assert_eq!(actual, ngx_lgx(&lengths, 120, percentile));
+        }
+        assert_eq!(compute_all_ngx_lgx(&[], 120, &[50]), [None]);
+    }
+
+    #[test]
+    fn test_aung() {
+        assert_eq!(aung(&[], 100), 0.0);
+        // (4^2 + 2^2 + 2^2) / 12
+        assert_eq!(aung(&[4, 2, 2], 12), 2.0);
+    }
+}
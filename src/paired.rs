@@ -0,0 +1,355 @@
+//! Statistics about paired-end reads.
+
+use crate::error::FastxStatisticsError;
+use crate::fai::SequenceLayout;
+use crate::format::SequenceReader;
+use crate::input::InputProgress;
+use crate::{
+    check_id, create_progress_bar, is_sampled, read_chunk, record_id, AssemblySummary,
+    StatisticsAccumulator, StatisticsOptions, CHUNK_BYTE_COUNT, CHUNK_RECORD_COUNT,
+};
+use log::{info, warn};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use seq_io::fastx::OwnedRecord;
+use seq_io::BaseRecord;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+/// Statistics about the two ends of paired-end reads.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PairedSummary {
+    /// The statistics of the first ends.
+    pub r1: AssemblySummary,
+    /// The statistics of the second ends.
+    pub r2: AssemblySummary,
+    /// The number of first ends, including filtered ones.
+    pub r1_read_count: usize,
+    /// The number of second ends, including filtered ones.
+    pub r2_read_count: usize,
+    /// The number of pairs whose ids do not match.
+    pub mismatched_pair_count: usize,
+}
+
+/// Compute statistics about paired-end reads given as two fasta or fastq inputs, which are read simultaneously.
+///
+/// The ids of the `i`th record of both inputs must match, up to a `/1` or `/2` suffix.
+/// Each mismatch is logged as a warning, as is a different number of records in the two inputs.
+/// Filters are applied to each end separately.
+/// A single progress bar is shown for both inputs together.
+pub fn paired_statistics(
+    r1: impl Read,
+    r1_progress: InputProgress,
+    r2: impl Read,
+    r2_progress: InputProgress,
+    options: &StatisticsOptions,
+) -> Result<PairedSummary, FastxStatisticsError> {
+    info!("Reading paired fasta or fastq files...");
+    let options = &*options.with_lowercase_ids()?;
+    let pb = create_progress_bar([&r1_progress, &r2_progress].into_iter(), options);
+
+    let mut r1_reader = SequenceReader::new(BufReader::new(r1), options.format);
+    let mut r2_reader = SequenceReader::new(BufReader::new(r2), options.format);
+    let mut accumulator = PairedAccumulator::new(options);
+    let mut last_update = Instant::now();
+    let mut skip = options.skip;
+
+    loop {
+        let r1_record = r1_reader
+            .next_record(false)
+            .transpose()
+            .map_err(|err| FastxStatisticsError::parse("Error parsing R1 fastx", err))?;
+        let r2_record = r2_reader
+            .next_record(false)
+            .transpose()
+            .map_err(|err| FastxStatisticsError::parse("Error parsing R2 fastx", err))?;
+        if r1_record.is_none() && r2_record.is_none() {
+            break;
+        }
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        if accumulator.stop_at_limit() {
+            break;
+        }
+
+        accumulator.add_pair(r1_record, r2_record)?;
+
+        let now = Instant::now();
+        if last_update + Duration::from_millis(200) <= now {
+            pb.set_position(
+                r1_progress.position(r1_reader.position().byte())
+                    + r2_progress.position(r2_reader.position().byte()),
+            );
+            last_update = now;
+        }
+    }
+
+    pb.finish_and_clear();
+    accumulator.finish()
+}
+
+/// Compute statistics about interleaved paired-end reads, i.e. a single fasta or fastq input that alternates between first and second ends.
+///
+/// Pair `i` consists of records `2i - 1` and `2i`, counting from one.
+/// Otherwise, this behaves like [`paired_statistics`].
+pub fn interleaved_statistics(
+    input: impl Read,
+    progress: InputProgress,
+    options: &StatisticsOptions,
+) -> Result<PairedSummary, FastxStatisticsError> {
+    info!("Reading interleaved fasta or fastq file...");
+    let options = &*options.with_lowercase_ids()?;
+    let pb = create_progress_bar([&progress].into_iter(), options);
+
+    let mut reader = SequenceReader::new(BufReader::new(input), options.format);
+    let mut accumulator = PairedAccumulator::new(options);
+    let mut last_update = Instant::now();
+    let mut r1_record = None;
+    // Skip both records of each skipped pair.
+    // Once the input is exhausted, skipping more records has no effect, so the product can saturate.
+    let mut skip = options.skip.saturating_mul(2);
+
+    while let Some(record) = reader.next_record(false) {
+        let record =
+            record.map_err(|err| FastxStatisticsError::parse("Error parsing fastx", err))?;
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        // The first record of each pair is buffered until the second one is read.
+        if let Some(r1_record) = r1_record.take() {
+            accumulator.add_pair(Some(r1_record), Some(record))?;
+        } else {
+            if accumulator.stop_at_limit() {
+                break;
+            }
+            r1_record = Some(record);
+        }
+
+        let now = Instant::now();
+        if last_update + Duration::from_millis(200) <= now {
+            pb.set_position(progress.position(reader.position().byte()));
+            last_update = now;
+        }
+    }
+    if r1_record.is_some() {
+        accumulator.add_pair(r1_record, None)?;
+    }
+
+    pb.finish_and_clear();
+    accumulator.finish()
+}
+
+/// Accumulates the statistics of both ends of paired-end reads, and checks that the ids of the pairs match.
+pub(crate) struct PairedAccumulator<'options> {
+    options: &'options StatisticsOptions,
+    r1: StatisticsAccumulator,
+    r2: StatisticsAccumulator,
+    r1_chunk: Vec<(OwnedRecord, SequenceLayout)>,
+    r2_chunk: Vec<(OwnedRecord, SequenceLayout)>,
+    chunk_byte_count: usize,
+    r1_read_count: usize,
+    r2_read_count: usize,
+    mismatched_pair_count: usize,
+    rng: StdRng,
+}
+
+impl<'options> PairedAccumulator<'options> {
+    pub(crate) fn new(options: &'options StatisticsOptions) -> Self {
+        Self {
+            options,
+            r1: StatisticsAccumulator::new(options),
+            r2: StatisticsAccumulator::new(options),
+            r1_chunk: Vec::new(),
+            r2_chunk: Vec::new(),
+            chunk_byte_count: 0,
+            r1_read_count: 0,
+            r2_read_count: 0,
+            mismatched_pair_count: 0,
+            rng: StdRng::seed_from_u64(options.seed),
+        }
+    }
+
+    /// Add the next pair of records, together with the layouts of their sequences in their inputs.
+    /// One of the ends may be missing if one input has more records than the other.
+    pub(crate) fn add_pair(
+        &mut self,
+        r1: Option<(OwnedRecord, SequenceLayout)>,
+        r2: Option<(OwnedRecord, SequenceLayout)>,
+    ) -> Result<(), FastxStatisticsError> {
+        // 1-based, like line numbers, and counting the skipped pairs to match the position in the input.
+        let pair_number = self.options.skip + self.r1_read_count.max(self.r2_read_count) + 1;
+        if let (Some((r1, _)), Some((r2, _))) = (&r1, &r2) {
+            let r1_id = record_id(r1)?;
+            let r2_id = record_id(r2)?;
+            if pair_name(r1_id) != pair_name(r2_id) {
+                warn!("Mismatched ids in pair {pair_number}: R1 is {r1_id:?}, but R2 is {r2_id:?}");
+                self.mismatched_pair_count += 1;
+            }
+        }
+
+        // Both ends of a pair are sampled together.
+        let sampled = is_sampled(self.options, &mut self.rng);
+        if r1.is_some() {
+            self.r1.count_sampled(sampled);
+        }
+        if r2.is_some() {
+            self.r2.count_sampled(sampled);
+        }
+        if !sampled {
+            self.r1_read_count += usize::from(r1.is_some());
+            self.r2_read_count += usize::from(r2.is_some());
+            return Ok(());
+        }
+
+        if let Some(r1) = r1 {
+            self.r1_read_count += 1;
+            if !check_id(record_id(&r1.0)?, r1.0.head(), self.options, &mut self.r1)? {
+                self.chunk_byte_count += r1.0.seq().len();
+                self.r1_chunk.push(r1);
+            }
+        }
+        if let Some(r2) = r2 {
+            self.r2_read_count += 1;
+            if !check_id(record_id(&r2.0)?, r2.0.head(), self.options, &mut self.r2)? {
+                self.chunk_byte_count += r2.0.seq().len();
+                self.r2_chunk.push(r2);
+            }
+        }
+
+        if self.r1_chunk.len().max(self.r2_chunk.len()) >= CHUNK_RECORD_COUNT
+            || self.chunk_byte_count >= CHUNK_BYTE_COUNT
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the record limit of the options is reached,
+    /// and notes the limit in the statistics of both ends.
+    /// Must only be called if there are more records to read.
+    pub(crate) fn stop_at_limit(&mut self) -> bool {
+        let pair_count = self.r1_read_count.max(self.r2_read_count);
+        if self
+            .options
+            .limit
+            .map_or(false, |limit| pair_count >= limit)
+        {
+            self.r1.record_limit = self.options.limit;
+            self.r2.record_limit = self.options.limit;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Accumulate the statistics of the buffered records.
+    fn flush(&mut self) -> Result<(), FastxStatisticsError> {
+        read_chunk(&self.r1_chunk, self.options, &mut self.r1, |_| Ok(()))?;
+        read_chunk(&self.r2_chunk, self.options, &mut self.r2, |_| Ok(()))?;
+        self.r1_chunk.clear();
+        self.r2_chunk.clear();
+        self.chunk_byte_count = 0;
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<PairedSummary, FastxStatisticsError> {
+        self.flush()?;
+
+        if self.r1_read_count != self.r2_read_count {
+            warn!(
+                "R1 has {} reads, but R2 has {} reads",
+                self.r1_read_count, self.r2_read_count
+            );
+        }
+        if self.mismatched_pair_count > 0 {
+            warn!("{} pairs have mismatched ids", self.mismatched_pair_count);
+        }
+        self.r1.log_warnings();
+        self.r2.log_warnings();
+
+        Ok(PairedSummary {
+            r1: self.r1.finish(self.options),
+            r2: self.r2.finish(self.options),
+            r1_read_count: self.r1_read_count,
+            r2_read_count: self.r2_read_count,
+            mismatched_pair_count: self.mismatched_pair_count,
+        })
+    }
+}
+
+/// The name of a read without the `/1` or `/2` suffix that marks its end.
+///
+/// Illumina-style ` 1:...` or ` 2:...` suffixes are part of the description and hence never part of the id.
+pub fn pair_name(id: &str) -> &str {
+    id.strip_suffix("/1")
+        .or_else(|| id.strip_suffix("/2"))
+        .unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paired::{interleaved_statistics, pair_name, paired_statistics};
+    use crate::StatisticsOptions;
+
+    #[test]
+    fn test_pair_name() {
+        assert_eq!(pair_name("read/1"), "read");
+        assert_eq!(pair_name("read/2"), "read");
+        assert_eq!(pair_name("read/3"), "read/3");
+        assert_eq!(pair_name("read"), "read");
+    }
+
+    #[test]
+    fn test_paired_statistics() {
+        let r1: &[u8] = b"@a/1\nACGT\n+\nIIII\n@b 1:N:0\nAC\n+\nII\n@c/1\nA\n+\nI\n";
+        let r2: &[u8] =
+            b"@a/2\nACG\n+\nIII\n@b 2:N:0\nACGTAC\n+\nIIIIII\n@d/2\nA\n+\nI\n@e/2\nA\n+\nI\n";
+        let summary = paired_statistics(
+            r1,
+            (r1.len() as u64).into(),
+            r2,
+            (r2.len() as u64).into(),
+            &StatisticsOptions {
+                min_length: Some(2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(summary.r1_read_count, 3);
+        assert_eq!(summary.r2_read_count, 4);
+        assert_eq!(summary.mismatched_pair_count, 1);
+        assert_eq!(summary.r1.record_count, 2);
+        assert_eq!(summary.r1.excluded.too_short, Some(1));
+        assert_eq!(summary.r2.record_count, 2);
+        assert_eq!(summary.r2.lengths.unwrap().with_ns.total_length, 9);
+    }
+
+    #[test]
+    fn test_interleaved_statistics() {
+        let input: &[u8] = b">a/1\nACGT\n>a/2\nACG\n>b/1\nAC\n>c/2\nACGTAC\n>d/1\nA\n";
+        let summary =
+            interleaved_statistics(input, (input.len() as u64).into(), &Default::default())
+                .unwrap();
+
+        assert_eq!(summary.r1_read_count, 3);
+        assert_eq!(summary.r2_read_count, 2);
+        assert_eq!(summary.mismatched_pair_count, 1);
+        assert_eq!(summary.r1.lengths.unwrap().with_ns.total_length, 7);
+        assert_eq!(summary.r2.lengths.unwrap().with_ns.total_length, 9);
+
+        let options = StatisticsOptions {
+            skip: usize::MAX,
+            ..Default::default()
+        };
+        let summary = interleaved_statistics(input, (input.len() as u64).into(), &options).unwrap();
+        assert_eq!(summary.r1_read_count, 0);
+        assert_eq!(summary.r2_read_count, 0);
+    }
+}
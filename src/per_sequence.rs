@@ -0,0 +1,280 @@
+//! Write statistics of individual records.
+
+use crate::checksum::ChecksumAlgorithm;
+use crate::error::FastxStatisticsError;
+use crate::AcceptedRecord;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// The statistics of a single record as they appear in the per-sequence output.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SequenceRow<'a> {
+    pub id: &'a str,
+    pub length: usize,
+    pub hoco_length: usize,
+    pub n_count: usize,
+    pub gc_count: usize,
+    pub gc_percent: f64,
+    pub soft_masked_count: usize,
+    /// The number of IUPAC ambiguity codes other than N.
+    pub ambiguous_count: usize,
+    /// The number of Ns and other IUPAC ambiguity codes.
+    pub uncertain_count: usize,
+    /// The Shannon entropy of the bases, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub entropy: Option<f64>,
+    /// The checksum of the sequence, if requested.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub checksum: Option<String>,
+}
+
+impl<'a> SequenceRow<'a> {
+    /// Create the row of the given record, including its entropy if `complexity` is set,
+    /// and the checksum of its sequence if a checksum algorithm is given.
+    pub fn new(
+        record: AcceptedRecord<'a>,
+        complexity: bool,
+        checksum: Option<ChecksumAlgorithm>,
+    ) -> Self {
+        let statistics = record.statistics;
+        Self {
+            id: record.id,
+            length: statistics.len,
+            hoco_length: statistics.hoco_len,
+            n_count: statistics.len - statistics.len_without_ns,
+            gc_count: statistics.gc_count,
+            gc_percent: statistics.gc_percent(),
+            soft_masked_count: statistics.soft_masked_len,
+            ambiguous_count: statistics.ambiguous_count,
+            uncertain_count: statistics.len - statistics.len_without_ns
+                + statistics.ambiguous_count,
+            entropy: if complexity {
+                Some(statistics.entropy())
+            } else {
+                None
+            },
+            checksum: checksum.map(|algorithm| algorithm.checksum(record.seq)),
+        }
+    }
+}
+
+/// The format of the per-sequence output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerSequenceFormat {
+    /// A header line followed by one tab-separated line per record.
+    Tsv,
+    /// A JSON array with one object per record.
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+/// Writes one row of statistics per record while the input is being read.
+pub struct PerSequenceWriter<W: Write> {
+    output: W,
+    format: PerSequenceFormat,
+    complexity: bool,
+    checksum: Option<ChecksumAlgorithm>,
+    row_count: usize,
+}
+
+impl<W: Write> PerSequenceWriter<W> {
+    /// Create a new writer, writing the header right away.
+    /// If `complexity` is set, then the entropy of each record is written as well.
+    /// If a checksum algorithm is given, then the checksum of the sequence of each record is written as well.
+    pub fn new(
+        mut output: W,
+        format: PerSequenceFormat,
+        complexity: bool,
+        checksum: Option<ChecksumAlgorithm>,
+    ) -> Result<Self, FastxStatisticsError> {
+        match format {
+            PerSequenceFormat::Tsv => {
+                write!(
+                    output,
+                    "id\tlength\thoco_length\tn_count\tgc_count\tgc_percent\tsoft_masked_count\tambiguous_count\tuncertain_count"
+                )
+                .and_then(|()| {
+                    if complexity {
+                        write!(output, "\tentropy")?;
+                    }
+                    if checksum.is_some() {
+                        write!(output, "\tchecksum")?;
+                    }
+                    writeln!(output)
+                })
+            }
+            #[cfg(feature = "serde")]
+            PerSequenceFormat::Json => write!(output, "["),
+        }
+        .map_err(write_error)?;
+
+        Ok(Self {
+            output,
+            format,
+            complexity,
+            checksum,
+            row_count: 0,
+        })
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        let row = SequenceRow::new(record, self.complexity, self.checksum);
+        match self.format {
+            PerSequenceFormat::Tsv => write!(
+                self.output,
+                "{}\t{}\t{}\t{}\t{}\t{:.2}\t{}\t{}\t{}",
+                row.id,
+                row.length,
+                row.hoco_length,
+                row.n_count,
+                row.gc_count,
+                row.gc_percent,
+                row.soft_masked_count,
+                row.ambiguous_count,
+                row.uncertain_count
+            )
+            .and_then(|()| {
+                if let Some(entropy) = row.entropy {
+                    write!(self.output, "\t{entropy:.4}")?;
+                }
+                if let Some(checksum) = &row.checksum {
+                    write!(self.output, "\t{checksum}")?;
+                }
+                writeln!(self.output)
+            })
+            .map_err(write_error)?,
+            #[cfg(feature = "serde")]
+            PerSequenceFormat::Json => {
+                if self.row_count > 0 {
+                    write!(self.output, ",").map_err(write_error)?;
+                }
+                write!(self.output, "\n  ").map_err(write_error)?;
+                serde_json::to_writer(&mut self.output, &row).map_err(write_error)?;
+            }
+        }
+
+        self.row_count += 1;
+        Ok(())
+    }
+
+    /// Write the closing part of the output and flush it.
+    pub fn finish(mut self) -> Result<(), FastxStatisticsError> {
+        #[cfg(feature = "serde")]
+        if self.format == PerSequenceFormat::Json {
+            writeln!(self.output, "\n]").map_err(write_error)?;
+        }
+        self.output.flush().map_err(write_error)
+    }
+}
+
+/// The fields of the JSON Lines output.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct JsonLinesRow<'a> {
+    id: &'a str,
+    length: usize,
+    hoco_length: usize,
+    n_count: usize,
+    gc_count: usize,
+    soft_masked_count: usize,
+}
+
+/// Writes one JSON object per line and record while the input is being read.
+#[cfg(feature = "serde")]
+pub struct JsonLinesWriter<W: Write> {
+    output: W,
+}
+
+#[cfg(feature = "serde")]
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(output: W) -> Self {
+        Self { output }
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        let row = SequenceRow::new(record, false, None);
+        let line = serde_json::to_string(&JsonLinesRow {
+            id: row.id,
+            length: row.length,
+            hoco_length: row.hoco_length,
+            n_count: row.n_count,
+            gc_count: row.gc_count,
+            soft_masked_count: row.soft_masked_count,
+        })
+        .map_err(write_error)?;
+        writeln!(self.output, "{line}").map_err(write_error)
+    }
+
+    /// Flush the output.
+    pub fn finish(mut self) -> Result<(), FastxStatisticsError> {
+        self.output.flush().map_err(write_error)
+    }
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write per-sequence statistics", err)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use crate::per_sequence::JsonLinesWriter;
+    use crate::per_sequence::{PerSequenceFormat, PerSequenceWriter};
+    use crate::{basic_statistics_with_callback, StatisticsOptions};
+
+    fn write_per_sequence(format: PerSequenceFormat, complexity: bool) -> String {
+        let fasta = b">1 desc\nACGgNR\n>2\nAACc\n";
+        let mut output = Vec::new();
+        let mut writer = PerSequenceWriter::new(&mut output, format, complexity, None).unwrap();
+        basic_statistics_with_callback(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+            |record| writer.write_record(record),
+        )
+        .unwrap();
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert_eq!(
+            write_per_sequence(PerSequenceFormat::Tsv, false),
+            "id\tlength\thoco_length\tn_count\tgc_count\tgc_percent\tsoft_masked_count\tambiguous_count\tuncertain_count\n1\t6\t5\t1\t3\t60.00\t1\t1\t2\n2\t4\t2\t0\t2\t50.00\t1\t0\t0\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&write_per_sequence(PerSequenceFormat::Json, true)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[0]["id"], "1");
+        assert_eq!(json[1]["hoco_length"], 2);
+        assert_eq!(json[1]["entropy"], 1.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_lines() {
+        let fasta = b">1 desc\nACGgNR\n>2\nAACc\n";
+        let mut output = Vec::new();
+        let mut writer = JsonLinesWriter::new(&mut output);
+        basic_statistics_with_callback(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+            |record| writer.write_record(record),
+        )
+        .unwrap();
+        writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"id\":\"1\",\"length\":6,\"hoco_length\":5,\"n_count\":1,\"gc_count\":3,\"soft_masked_count\":1}\n{\"id\":\"2\",\"length\":4,\"hoco_length\":2,\"n_count\":0,\"gc_count\":2,\"soft_masked_count\":1}\n"
+        );
+    }
+}
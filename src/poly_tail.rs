@@ -0,0 +1,152 @@
+//! Statistics about polyG and polyA tails at the 3' end of sequences.
+//!
+//! PolyG tails are artifacts of two-colour sequencing chemistry, where a lost signal is read as G.
+//! PolyA tails are expected in RNA-seq data.
+
+use crate::length_percentile;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use crate::defaults::DEFAULT_MIN_POLY_TAIL_LENGTH;
+
+/// Statistics about the polyG and polyA tails of all sequences.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PolyTailSummary {
+    /// The minimum length of a tail.
+    pub min_length: usize,
+    pub poly_g: TailSummary,
+    pub poly_a: TailSummary,
+}
+
+/// Statistics about the tails of a single base.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TailSummary {
+    /// The number of sequences with a tail.
+    pub count: usize,
+    /// The percentage of sequences with a tail.
+    pub percent: f64,
+    /// The mean length of the tails, `None` if there are no tails.
+    pub mean_length: Option<f64>,
+    /// The 95th percentile of the lengths of the tails, `None` if there are no tails.
+    pub p95_length: Option<f64>,
+}
+
+/// Collects the lengths of the tails of the sequences.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PolyTailAccumulator {
+    min_length: usize,
+    sequence_count: usize,
+    poly_g_lengths: Vec<usize>,
+    poly_a_lengths: Vec<usize>,
+}
+
+impl PolyTailAccumulator {
+    pub(crate) fn new(min_length: usize) -> Self {
+        Self {
+            min_length,
+            ..Default::default()
+        }
+    }
+
+    /// The lengths of the polyG and polyA tails of the given sequence, to be passed to [`Self::add`].
+    pub(crate) fn tail_lengths(seq: &[u8]) -> [usize; 2] {
+        [tail_length(seq, b'G'), tail_length(seq, b'A')]
+    }
+
+    /// Count a sequence with the given lengths of its polyG and polyA tails.
+    pub(crate) fn add(&mut self, tail_lengths: [usize; 2]) {
+        self.sequence_count += 1;
+        for (length, lengths) in tail_lengths
+            .into_iter()
+            .zip([&mut self.poly_g_lengths, &mut self.poly_a_lengths])
+        {
+            if length >= self.min_length {
+                lengths.push(length);
+            }
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        self.sequence_count += other.sequence_count;
+        self.poly_g_lengths.extend_from_slice(&other.poly_g_lengths);
+        self.poly_a_lengths.extend_from_slice(&other.poly_a_lengths);
+    }
+
+    /// Returns `None` if there are no sequences.
+    pub(crate) fn finish(self) -> Option<PolyTailSummary> {
+        if self.sequence_count == 0 {
+            return None;
+        }
+
+        let sequence_count = self.sequence_count;
+        let summarise = |mut lengths: Vec<usize>| {
+            lengths.sort_unstable_by(|a, b| b.cmp(a));
+            TailSummary {
+                count: lengths.len(),
+                percent: lengths.len() as f64 / sequence_count as f64 * 100.0,
+                mean_length: if lengths.is_empty() {
+                    None
+                } else {
+                    Some(lengths.iter().sum::<usize>() as f64 / lengths.len() as f64)
+                },
+                p95_length: if lengths.is_empty() {
+                    None
+                } else {
+                    Some(length_percentile(&lengths, 95))
+                },
+            }
+        };
+
+        Some(PolyTailSummary {
+            min_length: self.min_length,
+            poly_g: summarise(self.poly_g_lengths),
+            poly_a: summarise(self.poly_a_lengths),
+        })
+    }
+}
+
+/// The number of consecutive occurrences of the given uppercase base at the end of the sequence, ignoring case.
+/// Line terminators are ignored, such that multiline fasta sequences can be passed directly.
+pub fn tail_length(seq: &[u8], base: u8) -> usize {
+    seq.iter()
+        .rev()
+        .filter(|&&byte| byte != b'\n' && byte != b'\r')
+        .take_while(|byte| byte.to_ascii_uppercase() == base)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poly_tail::{tail_length, PolyTailAccumulator};
+
+    #[test]
+    fn test_tail_length() {
+        assert_eq!(tail_length(b"ACGGgg", b'G'), 4);
+        assert_eq!(tail_length(b"GGG\nGA", b'G'), 0);
+        assert_eq!(tail_length(b"AGG\r\nGG", b'G'), 4);
+        assert_eq!(tail_length(b"", b'A'), 0);
+    }
+
+    #[test]
+    fn test_poly_tail_summary() {
+        let mut accumulator = PolyTailAccumulator::new(3);
+        for seq in [b"ACGGG".as_slice(), b"GGGGG", b"CGG", b"CAAAA"] {
+            accumulator.add(PolyTailAccumulator::tail_lengths(seq));
+        }
+        let summary = accumulator.finish().unwrap();
+        assert_eq!(summary.poly_g.count, 2);
+        assert_eq!(summary.poly_g.percent, 50.0);
+        assert_eq!(summary.poly_g.mean_length, Some(4.0));
+        assert_eq!(summary.poly_g.p95_length, Some(4.9));
+        assert_eq!(summary.poly_a.count, 1);
+
+        let mut accumulator = PolyTailAccumulator::new(3);
+        accumulator.add(PolyTailAccumulator::tail_lengths(b"ACGT"));
+        let summary = accumulator.finish().unwrap();
+        assert_eq!(summary.poly_g.mean_length, None);
+        assert_eq!(summary.poly_g.p95_length, None);
+        assert_eq!(PolyTailAccumulator::new(3).finish(), None);
+    }
+}
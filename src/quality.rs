@@ -0,0 +1,500 @@
+//! Statistics about the quality scores of fastq records.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::ops::AddAssign;
+
+/// The offset of Phred quality scores in fastq quality strings.
+pub const PHRED_OFFSET: u8 = 33;
+
+/// The offset of Phred quality scores in the quality strings of old Illumina pipelines.
+/// Since Phred+33 quality strings rarely contain only scores of 31 and above,
+/// a minimum quality byte of at least this offset suggests this encoding,
+/// if there are also quality bytes above [`MAX_ILLUMINA_PHRED33_BYTE`].
+pub const PHRED64_OFFSET: u8 = 64;
+
+/// The highest quality byte of Phred+33 encoded Illumina reads, i.e. a score of 41.
+pub const MAX_ILLUMINA_PHRED33_BYTE: u8 = b'J';
+
+/// Statistics about the mean quality scores of a set of reads.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QualitySummary {
+    /// The mean of the mean qualities of the reads.
+    pub mean_quality: f64,
+    pub min_mean_quality: f64,
+    pub max_mean_quality: f64,
+    /// The first quartile of the mean qualities of the reads.
+    pub q1_mean_quality: f64,
+    /// The median of the mean qualities of the reads.
+    pub median_mean_quality: f64,
+    /// The third quartile of the mean qualities of the reads.
+    pub q3_mean_quality: f64,
+    /// The interquartile range of the mean qualities of the reads, i.e. the difference of the third and the first quartile.
+    /// A high interquartile range may indicate multiple populations of reads with different qualities.
+    pub iqr_mean_quality: f64,
+    /// The number of reads per rounded mean quality, from 0 to [`MAX_HISTOGRAM_QUALITY`].
+    /// Reads with a higher mean quality are counted in the last bin.
+    pub mean_quality_histogram: Vec<usize>,
+    /// The number of bases with a quality score of at least 20.
+    pub q20_count: u64,
+    /// The percentage of bases with a quality score of at least 20.
+    pub q20_percent: f64,
+    /// The number of bases with a quality score of at least 30.
+    pub q30_count: u64,
+    /// The percentage of bases with a quality score of at least 30.
+    pub q30_percent: f64,
+    /// The lowest quality byte of all bases.
+    pub min_quality_byte: u8,
+    /// The highest quality byte of all bases.
+    pub max_quality_byte: u8,
+    /// The mean of the estimated error rates of the reads, as a percentage.
+    /// The error rate of a read is its expected number of errors according to the quality scores divided by its length.
+    pub mean_error_percent: f64,
+    /// The median of the estimated error rates of the reads, as a percentage.
+    pub median_error_percent: f64,
+    /// The percentage of reads with at most one expected error.
+    pub max_one_expected_error_percent: f64,
+}
+
+/// The highest quality in [`QualitySummary::mean_quality_histogram`].
+pub const MAX_HISTOGRAM_QUALITY: usize = 40;
+
+impl QualitySummary {
+    /// Compute the statistics of the given non-empty list of mean read qualities,
+    /// the quality counts of the bases of the same reads, the error rates of the same reads
+    /// and the number of these reads with at most one expected error.
+    /// The lists are sorted in the process.
+    pub fn new(
+        mean_qualities: &mut [f64],
+        base_qualities: &BaseQualityCounts,
+        error_rates: &mut [f64],
+        max_one_expected_error_count: usize,
+    ) -> Self {
+        debug_assert_eq!(mean_qualities.len(), error_rates.len());
+        mean_qualities.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut mean_quality_histogram = vec![0; MAX_HISTOGRAM_QUALITY + 1];
+        for mean_quality in mean_qualities.iter() {
+            mean_quality_histogram[(mean_quality.round() as usize).min(MAX_HISTOGRAM_QUALITY)] += 1;
+        }
+
+        error_rates.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let q1_mean_quality = quantile(mean_qualities, 0.25);
+        let q3_mean_quality = quantile(mean_qualities, 0.75);
+        Self {
+            mean_quality: mean_qualities.iter().sum::<f64>() / mean_qualities.len() as f64,
+            min_mean_quality: *mean_qualities.first().unwrap(),
+            max_mean_quality: *mean_qualities.last().unwrap(),
+            q1_mean_quality,
+            median_mean_quality: quantile(mean_qualities, 0.5),
+            q3_mean_quality,
+            iqr_mean_quality: q3_mean_quality - q1_mean_quality,
+            mean_quality_histogram,
+            q20_count: base_qualities.q20_count,
+            q20_percent: base_qualities.percent(base_qualities.q20_count),
+            q30_count: base_qualities.q30_count,
+            q30_percent: base_qualities.percent(base_qualities.q30_count),
+            min_quality_byte: base_qualities.min_quality_byte.unwrap_or(PHRED_OFFSET),
+            max_quality_byte: base_qualities.max_quality_byte.unwrap_or(PHRED_OFFSET),
+            mean_error_percent: error_rates.iter().sum::<f64>() / error_rates.len() as f64 * 100.0,
+            median_error_percent: quantile(error_rates, 0.5) * 100.0,
+            max_one_expected_error_percent: max_one_expected_error_count as f64
+                / error_rates.len() as f64
+                * 100.0,
+        }
+    }
+
+    /// Returns true if the qualities appear to be encoded with [`PHRED64_OFFSET`] instead of [`PHRED_OFFSET`].
+    pub fn is_phred64_suspected(&self) -> bool {
+        self.min_quality_byte >= PHRED64_OFFSET && self.max_quality_byte > MAX_ILLUMINA_PHRED33_BYTE
+    }
+}
+
+/// The number of bases by quality score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BaseQualityCounts {
+    pub base_count: u64,
+    /// The number of bases with a quality score of at least 20.
+    pub q20_count: u64,
+    /// The number of bases with a quality score of at least 30.
+    pub q30_count: u64,
+    /// The lowest quality byte, `None` if there are no bases.
+    pub min_quality_byte: Option<u8>,
+    /// The highest quality byte, `None` if there are no bases.
+    pub max_quality_byte: Option<u8>,
+}
+
+impl BaseQualityCounts {
+    /// Count the bases of the given quality string by quality score.
+    /// Line terminators are ignored, such that multiline fastq quality strings with `\n` or `\r\n` line endings can be passed directly.
+    pub fn new(qual: &[u8]) -> Self {
+        let mut counts = Self::default();
+        for byte in qual.iter().copied() {
+            if byte != b'\n' && byte != b'\r' {
+                counts.add(byte);
+            }
+        }
+        counts
+    }
+
+    /// Count a single base with the given quality byte.
+    fn add(&mut self, byte: u8) {
+        let quality = byte.saturating_sub(PHRED_OFFSET);
+        self.base_count += 1;
+        self.q20_count += u64::from(quality >= 20);
+        self.q30_count += u64::from(quality >= 30);
+        self.min_quality_byte = Some(self.min_quality_byte.map_or(byte, |min| min.min(byte)));
+        self.max_quality_byte = Some(self.max_quality_byte.map_or(byte, |max| max.max(byte)));
+    }
+
+    /// The given number of bases as a percentage of all bases.
+    fn percent(&self, count: u64) -> f64 {
+        if self.base_count == 0 {
+            0.0
+        } else {
+            count as f64 / self.base_count as f64 * 100.0
+        }
+    }
+}
+
+impl AddAssign<&BaseQualityCounts> for BaseQualityCounts {
+    fn add_assign(&mut self, other: &BaseQualityCounts) {
+        self.base_count += other.base_count;
+        self.q20_count += other.q20_count;
+        self.q30_count += other.q30_count;
+        self.min_quality_byte = match (self.min_quality_byte, other.min_quality_byte) {
+            (Some(min), Some(other_min)) => Some(min.min(other_min)),
+            (min, other_min) => min.or(other_min),
+        };
+        self.max_quality_byte = match (self.max_quality_byte, other.max_quality_byte) {
+            (Some(max), Some(other_max)) => Some(max.max(other_max)),
+            (max, other_max) => max.or(other_max),
+        };
+    }
+}
+
+/// The statistics of the quality string of a single read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadQuality {
+    /// The mean Phred quality of the bases.
+    pub mean_quality: f64,
+    /// The expected number of errors, i.e. the sum of the error probabilities of the bases.
+    pub expected_errors: f64,
+    pub base_qualities: BaseQualityCounts,
+}
+
+impl ReadQuality {
+    /// Compute the statistics of the given quality string in a single pass, or `None` if it contains no quality scores.
+    /// Line terminators are ignored, such that multiline fastq quality strings with `\n` or `\r\n` line endings can be passed directly.
+    pub fn new(qual: &[u8]) -> Option<Self> {
+        let mut quality_sum = 0u64;
+        let mut expected_errors = 0.0;
+        let mut base_qualities = BaseQualityCounts::default();
+        for byte in qual.iter().copied() {
+            if byte == b'\n' || byte == b'\r' {
+                continue;
+            }
+
+            let quality = byte.saturating_sub(PHRED_OFFSET);
+            quality_sum += u64::from(quality);
+            let quality = usize::from(quality.min(MAX_PHRED_QUALITY));
+            expected_errors +=
+                ERROR_PROBABILITIES[quality % 10] * NEGATIVE_POWERS_OF_TEN[quality / 10];
+            base_qualities.add(byte);
+        }
+
+        if base_qualities.base_count == 0 {
+            None
+        } else {
+            Some(Self {
+                mean_quality: quality_sum as f64 / base_qualities.base_count as f64,
+                expected_errors,
+                base_qualities,
+            })
+        }
+    }
+
+    /// The expected number of errors divided by the number of bases.
+    pub fn error_rate(&self) -> f64 {
+        self.expected_errors / self.base_qualities.base_count as f64
+    }
+}
+
+/// The mean quality of the bases at a position of the reads.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CycleQuality {
+    /// The position in the reads, starting from 1.
+    pub position: usize,
+    pub mean_quality: f64,
+    /// The number of reads that reach this position.
+    pub read_count: u64,
+}
+
+/// Sums up the qualities of the bases at each position of the reads.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PerCycleQualityAccumulator {
+    quality_sums: Vec<u64>,
+    read_counts: Vec<u64>,
+}
+
+impl PerCycleQualityAccumulator {
+    pub(crate) fn add(&mut self, qual: &[u8]) {
+        let qualities = qual.iter().filter(|&&byte| byte != b'\n' && byte != b'\r');
+        for (position, byte) in qualities.enumerate() {
+            if position == self.quality_sums.len() {
+                self.quality_sums.push(0);
+                self.read_counts.push(0);
+            }
+            self.quality_sums[position] += u64::from(byte.saturating_sub(PHRED_OFFSET));
+            self.read_counts[position] += 1;
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        if self.quality_sums.len() < other.quality_sums.len() {
+            self.quality_sums.resize(other.quality_sums.len(), 0);
+            self.read_counts.resize(other.read_counts.len(), 0);
+        }
+        for (sum, other_sum) in self.quality_sums.iter_mut().zip(&other.quality_sums) {
+            *sum += other_sum;
+        }
+        for (count, other_count) in self.read_counts.iter_mut().zip(&other.read_counts) {
+            *count += other_count;
+        }
+    }
+
+    /// Compute the mean quality per position, omitting the positions that are reached by less than 0.1% of the reads.
+    pub(crate) fn finish(self) -> Vec<CycleQuality> {
+        // Every read with a quality reaches the first position.
+        let total_read_count = self.read_counts.first().copied().unwrap_or(0);
+        self.quality_sums
+            .iter()
+            .zip(&self.read_counts)
+            .enumerate()
+            .take_while(|(_, (_, &read_count))| read_count * 1000 >= total_read_count)
+            .map(|(position, (&quality_sum, &read_count))| CycleQuality {
+                position: position + 1,
+                mean_quality: quality_sum as f64 / read_count as f64,
+                read_count,
+            })
+            .collect()
+    }
+}
+
+/// Write the mean quality per position as tab-separated data with a header.
+pub fn write_per_cycle_quality(cycles: &[CycleQuality], mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "position\tmean_quality\tread_count")?;
+    for cycle in cycles {
+        writeln!(
+            output,
+            "{}\t{}\t{}",
+            cycle.position, cycle.mean_quality, cycle.read_count
+        )?;
+    }
+    Ok(())
+}
+
+/// The error probabilities `10^(-q/10)` of the Phred qualities from 0 to 9.
+/// The error probability of a higher quality is obtained by additionally dividing by a power of ten.
+const ERROR_PROBABILITIES: [f64; 10] = [
+    1.0,
+    0.7943282347242815,
+    0.6309573444801932,
+    0.5011872336272722,
+    0.3981071705534972,
+    0.31622776601683794,
+    0.251188643150958,
+    0.19952623149688797,
+    0.15848931924611134,
+    0.12589254117941673,
+];
+
+/// The powers of ten from `10^0` to `10^-9`.
+const NEGATIVE_POWERS_OF_TEN: [f64; 10] =
+    [1.0, 1e-1, 1e-2, 1e-3, 1e-4, 1e-5, 1e-6, 1e-7, 1e-8, 1e-9];
+
+/// The highest Phred quality that can be encoded in a fastq quality string, i.e. `~`.
+const MAX_PHRED_QUALITY: u8 = 93;
+
+/// Compute the given quantile of the given non-empty list, which must be sorted in ascending order.
+/// The quantile is interpolated linearly between adjacent elements, like [`crate::length_percentile`].
+pub fn quantile(sorted: &[f64], quantile: f64) -> f64 {
+    debug_assert!((0.0..=1.0).contains(&quantile));
+    let position = (sorted.len() - 1) as f64 * quantile;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quality::{
+        write_per_cycle_quality, BaseQualityCounts, PerCycleQualityAccumulator, QualitySummary,
+        ReadQuality, MAX_HISTOGRAM_QUALITY,
+    };
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_mean_quality() {
+        let mean_quality = |qual| ReadQuality::new(qual).map(|quality| quality.mean_quality);
+        assert_eq!(mean_quality(b"!+5"), Some(10.0));
+        assert_eq!(mean_quality(b"II\nI"), Some(40.0));
+        assert_eq!(mean_quality(b""), None);
+    }
+
+    #[test]
+    fn test_expected_errors() {
+        let quality = ReadQuality::new(b"!!").unwrap();
+        assert_eq!(quality.expected_errors, 2.0);
+        assert_eq!(quality.error_rate(), 1.0);
+        let quality = ReadQuality::new(b"+5\r\n?I~").unwrap();
+        assert_eq!(quality.base_qualities, BaseQualityCounts::new(b"+5?I~"));
+        assert!(
+            (quality.expected_errors - (0.1 + 0.01 + 0.001 + 0.0001 + 10f64.powf(-9.3))).abs()
+                < 1e-15
+        );
+        assert_eq!(ReadQuality::new(b"\n"), None);
+    }
+
+    #[test]
+    fn test_base_quality_counts() {
+        let mut counts = BaseQualityCounts::new(b"!5?\r\nIJ");
+        assert_eq!(
+            counts,
+            BaseQualityCounts {
+                base_count: 5,
+                q20_count: 4,
+                q30_count: 3,
+                min_quality_byte: Some(b'!'),
+                max_quality_byte: Some(b'J'),
+            }
+        );
+        counts += &BaseQualityCounts::new(b"");
+        assert_eq!(counts.min_quality_byte, Some(b'!'));
+        let mut empty = BaseQualityCounts::default();
+        empty += &counts;
+        assert_eq!(empty, counts);
+    }
+
+    #[test]
+    fn test_per_cycle_quality() {
+        let mut accumulator = PerCycleQualityAccumulator::default();
+        accumulator.add(b"I5");
+        let mut other = PerCycleQualityAccumulator::default();
+        other.add(b"++\n+");
+        accumulator.extend(&other);
+        let mut output = Vec::new();
+        write_per_cycle_quality(&accumulator.finish(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "position\tmean_quality\tread_count\n1\t25\t2\n2\t15\t2\n3\t10\t1\n"
+        );
+
+        // A position reached by less than 0.1% of the reads is omitted.
+        let mut rare = PerCycleQualityAccumulator::default();
+        rare.add(b"55");
+        for _ in 0..1000 {
+            rare.add(b"5");
+        }
+        let cycles = rare.finish();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].read_count, 1001);
+    }
+
+    #[test]
+    fn test_quality_summary() {
+        let mut mean_qualities = vec![30.0, 10.0, 20.0, 40.0, 50.0, 9.6];
+        let mut mean_quality_histogram = vec![0; MAX_HISTOGRAM_QUALITY + 1];
+        mean_quality_histogram[10] = 2;
+        mean_quality_histogram[20] = 1;
+        mean_quality_histogram[30] = 1;
+        mean_quality_histogram[40] = 2;
+        let base_qualities = BaseQualityCounts {
+            base_count: 8,
+            q20_count: 4,
+            q30_count: 2,
+            min_quality_byte: Some(b'+'),
+            max_quality_byte: Some(b'I'),
+        };
+        let mut error_rates = vec![0.5, 0.25, 0.5, 0.125, 0.0, 0.25];
+        assert_eq!(
+            QualitySummary::new(&mut mean_qualities, &base_qualities, &mut error_rates, 4),
+            QualitySummary {
+                mean_quality: 159.6 / 6.0,
+                min_mean_quality: 9.6,
+                max_mean_quality: 50.0,
+                q1_mean_quality: 12.5,
+                median_mean_quality: 25.0,
+                q3_mean_quality: 37.5,
+                iqr_mean_quality: 25.0,
+                mean_quality_histogram,
+                q20_count: 4,
+                q20_percent: 50.0,
+                q30_count: 2,
+                q30_percent: 25.0,
+                min_quality_byte: b'+',
+                max_quality_byte: b'I',
+                mean_error_percent: 1.625 / 6.0 * 100.0,
+                median_error_percent: 25.0,
+                max_one_expected_error_percent: 4.0 / 6.0 * 100.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fastq() {
+        let fastq = b"@1\nACGT\n+\nIIII\n@2\nAC\n+\n!!\n";
+        let summary = basic_statistics(
+            fastq.as_slice(),
+            fastq.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let quality = summary.quality.unwrap();
+        assert_eq!(quality.mean_quality, 20.0);
+        assert_eq!(quality.max_mean_quality, 40.0);
+        assert_eq!(quality.q30_count, 4);
+        assert_eq!(quality.q30_percent, 4.0 / 6.0 * 100.0);
+        assert!(!quality.is_phred64_suspected());
+        assert_eq!(quality.mean_error_percent, 0.0001 / 2.0 * 100.0 + 50.0);
+        assert_eq!(quality.max_one_expected_error_percent, 50.0);
+
+        for (fastq, phred64) in [
+            (b"@1\nACGT\n+\nIIII\n".as_slice(), false),
+            (b"@1\nACGT\n+\nhhhB\n".as_slice(), true),
+        ] {
+            let summary =
+                basic_statistics(fastq, fastq.len() as u64, &StatisticsOptions::default()).unwrap();
+            assert_eq!(summary.quality.unwrap().is_phred64_suspected(), phred64);
+        }
+
+        // The median of an even number of reads lies between the two middle reads.
+        let fastq = b"@1\nA\n+\n(\n@2\nA\n+\nG\n";
+        let summary = basic_statistics(
+            fastq.as_slice(),
+            fastq.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let quality = summary.quality.unwrap();
+        assert_eq!(quality.q1_mean_quality, 14.75);
+        assert_eq!(quality.median_mean_quality, 22.5);
+        assert_eq!(quality.q3_mean_quality, 30.25);
+        let error_rates = [10f64.powf(-0.7), 10f64.powf(-3.8)];
+        assert!(
+            (quality.median_error_percent - (error_rates[0] + error_rates[1]) * 50.0).abs() < 1e-9
+        );
+
+        let fasta = b">1\nACGT\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(summary.quality, None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Write statistics in the layout of the `report.tsv` of QUAST 5, for tools that parse QUAST reports.
+//!
+//! Unlike QUAST, all sequences are counted regardless of their length, i.e. there is no minimum contig length.
+
+use crate::AssemblySummary;
+use std::io::{self, Write};
+
+/// Write one row per metric and one column per assembly, preceded by a header row with the assembly names.
+/// Metrics that are undefined because an assembly has no sequences are written as `-`, as QUAST does.
+pub fn write_quast_report(
+    assemblies: &[(String, &AssemblySummary)],
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut rows: Vec<(&str, Vec<String>)> = vec![
+        ("Assembly", Vec::new()),
+        ("# contigs", Vec::new()),
+        ("Largest contig", Vec::new()),
+        ("Total length", Vec::new()),
+        ("GC (%)", Vec::new()),
+        ("N50", Vec::new()),
+        ("N75", Vec::new()),
+        ("L50", Vec::new()),
+        ("L75", Vec::new()),
+        ("# N's per 100 kbp", Vec::new()),
+    ];
+
+    for (name, summary) in assemblies {
+        let values = if let Some(lengths) = &summary.lengths {
+            let nx = &lengths.with_ns;
+            [
+                name.clone(),
+                summary.record_count.to_string(),
+                nx.max_len.to_string(),
+                nx.total_length.to_string(),
+                format!("{:.2}", lengths.gc.gc_percent),
+                nx.n50.to_string(),
+                nx.n75.to_string(),
+                nx.l50.to_string(),
+                nx.l75.to_string(),
+                format!(
+                    "{:.2}",
+                    lengths.n_bases as f64 * 100_000.0 / nx.total_length as f64
+                ),
+            ]
+        } else {
+            let mut values = [(); 10].map(|()| "-".to_owned());
+            values[0] = name.clone();
+            values[1] = "0".to_owned();
+            values[3] = "0".to_owned();
+            values
+        };
+        for ((_, row), value) in rows.iter_mut().zip(values) {
+            row.push(value);
+        }
+    }
+
+    for (name, values) in rows {
+        writeln!(output, "{name}\t{}", values.join("\t"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quast::write_quast_report;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_quast_report() {
+        let fasta = b">1\nACGTNNNNGG\n>2\nAC\n";
+        let options = StatisticsOptions::default();
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        let empty = basic_statistics(b"".as_slice(), 0, &options).unwrap();
+        let mut output = Vec::new();
+        write_quast_report(
+            &[("asm".to_owned(), &summary), ("empty".to_owned(), &empty)],
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Assembly\tasm\tempty
+# contigs\t2\t0
+Largest contig\t10\t-
+Total length\t12\t0
+GC (%)\t62.50\t-
+N50\t10\t-
+N75\t10\t-
+L50\t1\t-
+L75\t1\t-
+# N's per 100 kbp\t33333.33\t-
+"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Write the records that were not filtered.
+
+use crate::error::FastxStatisticsError;
+use crate::AcceptedRecord;
+use seq_io::{fasta, fastq};
+use std::io::{self, Write};
+
+/// The format in which records are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    Fasta,
+    /// Fastq, which requires the input records to have quality scores.
+    Fastq,
+    /// Fastq for records with quality scores, and fasta otherwise.
+    SameAsInput,
+}
+
+/// Writes records while the input is being read.
+pub struct RecordWriter<W: Write> {
+    output: W,
+    format: RecordFormat,
+    record_count: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(output: W, format: RecordFormat) -> Self {
+        Self {
+            output,
+            format,
+            record_count: 0,
+        }
+    }
+
+    pub fn write_record(&mut self, record: AcceptedRecord) -> Result<(), FastxStatisticsError> {
+        let seq = lines(record.seq);
+        match (self.format, record.qual) {
+            (RecordFormat::Fasta, _) | (RecordFormat::SameAsInput, None) => {
+                fasta::write_iter(&mut self.output, record.head, seq)
+            }
+            (RecordFormat::Fastq, _) | (RecordFormat::SameAsInput, Some(_)) => {
+                let qual = record.qual.ok_or_else(|| {
+                    format!(
+                        "Cannot write record {} as fastq, since it has no quality scores",
+                        record.id
+                    )
+                })?;
+                fastq::write_iter(&mut self.output, record.head, seq, lines(qual))
+            }
+        }
+        .map_err(write_error)?;
+
+        self.record_count += 1;
+        Ok(())
+    }
+
+    /// Flush the output and return the number of written records.
+    pub fn finish(mut self) -> Result<usize, FastxStatisticsError> {
+        self.output.flush().map_err(write_error)?;
+        Ok(self.record_count)
+    }
+}
+
+/// Split a possibly multiline sequence or quality string into its lines, removing line terminators.
+fn lines(multiline: &[u8]) -> impl Iterator<Item = &[u8]> {
+    multiline
+        .split(|&byte| byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+fn write_error(err: impl Into<io::Error>) -> FastxStatisticsError {
+    FastxStatisticsError::io("Cannot write records", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::FastxStatisticsError;
+    use crate::records::{RecordFormat, RecordWriter};
+    use crate::{basic_statistics_with_callback, StatisticsOptions};
+
+    fn write_records(input: &[u8], format: RecordFormat) -> Result<String, FastxStatisticsError> {
+        let mut output = Vec::new();
+        let mut writer = RecordWriter::new(&mut output, format);
+        basic_statistics_with_callback(
+            input,
+            input.len() as u64,
+            &StatisticsOptions {
+                min_length: Some(3),
+                ..Default::default()
+            },
+            |record| writer.write_record(record),
+        )?;
+        assert_eq!(writer.finish().unwrap(), 1);
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_fasta() {
+        assert_eq!(
+            write_records(b">1 desc\nACG\nTA\n>2\nA\n", RecordFormat::Fasta).unwrap(),
+            ">1 desc\nACGTA\n"
+        );
+    }
+
+    #[test]
+    fn test_fastq() {
+        let fastq = b"@1 desc\nACGT\n+\nII!I\n@2\nA\n+\nI\n";
+        assert_eq!(
+            write_records(fastq, RecordFormat::Fastq).unwrap(),
+            "@1 desc\nACGT\n+\nII!I\n"
+        );
+        assert_eq!(
+            write_records(fastq, RecordFormat::Fasta).unwrap(),
+            ">1 desc\nACGT\n"
+        );
+        assert!(write_records(b">1\nACGT\n", RecordFormat::Fastq).is_err());
+        assert_eq!(
+            write_records(fastq, RecordFormat::SameAsInput).unwrap(),
+            "@1 desc\nACGT\n+\nII!I\n"
+        );
+        assert_eq!(
+            write_records(b">1\nACGT\n", RecordFormat::SameAsInput).unwrap(),
+            ">1\nACGT\n"
+        );
+    }
+}
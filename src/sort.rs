@@ -0,0 +1,125 @@
+//! Sort the records of an input by length or by id.
+
+use crate::error::FastxStatisticsError;
+use crate::fai::SequenceLayout;
+use crate::format::SequenceReader;
+use crate::input::InputProgress;
+use crate::{
+    create_progress_bar, record_id, AcceptedRecord, SequenceStatistics, StatisticsOptions,
+};
+use log::{info, warn};
+use seq_io::fastx::OwnedRecord;
+use seq_io::BaseRecord;
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+/// Inputs larger than this many bytes may not fit into memory when sorting them.
+pub const LARGE_INPUT_BYTES: u64 = 4 << 30;
+
+/// The order in which [`sort_records`] returns the records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Descending by sequence length.
+    Length,
+    /// Ascending by id, comparing the bytes of the ids.
+    Name,
+}
+
+/// Read all records of the input into memory, and call `record_callback` for each of them in the given order,
+/// or in the reverse order if `reverse` is set. Records that compare equal keep their order in the input.
+/// Returns the number of records.
+///
+/// Only the `format` and `show_progress` options are used.
+pub fn sort_records(
+    input: impl Read,
+    progress: InputProgress,
+    options: &StatisticsOptions,
+    order: SortOrder,
+    reverse: bool,
+    mut record_callback: impl FnMut(AcceptedRecord) -> Result<(), FastxStatisticsError>,
+) -> Result<usize, FastxStatisticsError> {
+    if progress.total > LARGE_INPUT_BYTES {
+        warn!(
+            "Sorting reads the whole input of {} bytes into memory",
+            progress.total
+        );
+    }
+    info!("Reading fasta or fastq file...");
+    let pb = create_progress_bar([&progress].into_iter(), options);
+
+    let mut reader = SequenceReader::new(BufReader::new(input), options.format);
+    let mut last_update = Instant::now();
+    let mut records: Vec<(OwnedRecord, SequenceLayout, SequenceStatistics)> = Vec::new();
+    while let Some(record) = reader.next_record(true) {
+        let (record, layout) =
+            record.map_err(|err| FastxStatisticsError::parse("Error parsing fastx", err))?;
+        record_id(&record)?;
+        let statistics = SequenceStatistics::new(record.seq());
+        records.push((record, layout, statistics));
+
+        let now = Instant::now();
+        if last_update + Duration::from_millis(200) <= now {
+            pb.set_position(progress.position(reader.position().byte()));
+            last_update = now;
+        }
+    }
+    pb.finish_and_clear();
+
+    info!("Sorting {} records...", records.len());
+    records.sort_by(|(a, _, a_statistics), (b, _, b_statistics)| {
+        let ordering = match order {
+            SortOrder::Length => b_statistics.len.cmp(&a_statistics.len),
+            SortOrder::Name => a.id_bytes().cmp(b.id_bytes()),
+        };
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    for (record, layout, statistics) in &records {
+        record_callback(AcceptedRecord {
+            // The id was checked to be valid utf-8 when reading the record.
+            id: record.id().unwrap(),
+            head: record.head(),
+            seq: record.seq(),
+            qual: record.opt_qual(),
+            layout: *layout,
+            statistics,
+        })?;
+    }
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sort::{sort_records, SortOrder};
+
+    #[test]
+    fn test_sort_records() {
+        let fasta: &[u8] = b">b\nAC\n>c\nACGT\n>a\nA\nC\n>d\nACG\n";
+        let sort = |order, reverse| {
+            let mut ids = Vec::new();
+            let count = sort_records(
+                fasta,
+                (fasta.len() as u64).into(),
+                &Default::default(),
+                order,
+                reverse,
+                |record| {
+                    ids.push(record.id.to_owned());
+                    Ok(())
+                },
+            )
+            .unwrap();
+            assert_eq!(count, 4);
+            ids
+        };
+
+        assert_eq!(sort(SortOrder::Length, false), ["c", "d", "b", "a"]);
+        assert_eq!(sort(SortOrder::Length, true), ["b", "a", "d", "c"]);
+        assert_eq!(sort(SortOrder::Name, false), ["a", "b", "c", "d"]);
+        assert_eq!(sort(SortOrder::Name, true), ["d", "c", "b", "a"]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Approximate length statistics with bounded memory, for inputs with too many records to store all lengths.
+//!
+//! The record count, total length, mean, minimum and maximum are exact,
+//! while the Nx metrics are computed from a uniform random sample of the sequence lengths.
+
+use crate::{compute_all_nx, total_length, GcAccumulator, GcSummary};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use crate::defaults::DEFAULT_RESERVOIR_SIZE;
+
+/// Length statistics of all sequences, where the Nx metrics are approximated from a sample of the sequence lengths.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StreamingSummary {
+    pub total_length: u64,
+    /// The number of N characters.
+    pub n_bases: u64,
+    /// Statistics about the GC content, which are exact.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub gc: GcSummary,
+    pub mean_len: f64,
+    pub max_len: usize,
+    pub min_len: usize,
+    /// The number of sequence lengths that the Nx metrics are computed from.
+    pub sample_size: usize,
+    pub approximate_n50: usize,
+    pub approximate_n75: usize,
+    pub approximate_n90: usize,
+}
+
+/// Collects exact counts of the sequence lengths and a uniform sample of them by reservoir sampling.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamingAccumulator {
+    reservoir: Vec<usize>,
+    reservoir_size: usize,
+    sequence_count: usize,
+    total_length: u64,
+    len_without_ns: u64,
+    max_len: usize,
+    min_len: usize,
+    gc: GcAccumulator,
+    rng: StdRng,
+}
+
+impl StreamingAccumulator {
+    pub(crate) fn new(reservoir_size: usize, seed: u64) -> Self {
+        Self {
+            reservoir: Vec::new(),
+            reservoir_size,
+            sequence_count: 0,
+            total_length: 0,
+            len_without_ns: 0,
+            max_len: 0,
+            min_len: usize::MAX,
+            gc: GcAccumulator::default(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub(crate) fn add(&mut self, len: usize, len_without_ns: usize, gc_count: usize) {
+        self.sequence_count += 1;
+        self.total_length += len as u64;
+        self.len_without_ns += len_without_ns as u64;
+        self.max_len = self.max_len.max(len);
+        self.min_len = self.min_len.min(len);
+        self.gc.add(gc_count, len_without_ns);
+
+        if self.reservoir.len() < self.reservoir_size {
+            self.reservoir.push(len);
+        } else {
+            let index = self.rng.gen_range(0..self.sequence_count);
+            if index < self.reservoir_size {
+                self.reservoir[index] = len;
+            }
+        }
+    }
+
+    /// Add all sequences accumulated in `other` to `self`.
+    /// If the samples do not fit into the reservoir together, each contributes in proportion to its number of sequences.
+    pub(crate) fn extend(&mut self, other: &Self) {
+        let sequence_count = self.sequence_count + other.sequence_count;
+        if self.reservoir.len() + other.reservoir.len() <= self.reservoir_size {
+            self.reservoir.extend_from_slice(&other.reservoir);
+        } else {
+            let other_share = (self.reservoir_size as f64 * other.sequence_count as f64
+                / sequence_count as f64)
+                .round() as usize;
+            let other_share = other_share.min(other.reservoir.len());
+            let own_share = (self.reservoir_size - other_share).min(self.reservoir.len());
+
+            self.reservoir.shuffle(&mut self.rng);
+            self.reservoir.truncate(own_share);
+            let mut other_reservoir = other.reservoir.clone();
+            other_reservoir.shuffle(&mut self.rng);
+            self.reservoir
+                .extend_from_slice(&other_reservoir[..other_share]);
+        }
+
+        self.sequence_count = sequence_count;
+        self.total_length += other.total_length;
+        self.len_without_ns += other.len_without_ns;
+        self.max_len = self.max_len.max(other.max_len);
+        self.min_len = self.min_len.min(other.min_len);
+        self.gc.extend(&other.gc);
+    }
+
+    /// The number of sequences that were added.
+    pub(crate) fn sequence_count(&self) -> usize {
+        self.sequence_count
+    }
+
+    /// Returns `None` if there are no sequences.
+    pub(crate) fn finish(mut self) -> Option<StreamingSummary> {
+        if self.sequence_count == 0 {
+            return None;
+        }
+
+        self.reservoir.sort_unstable_by(|a, b| b.cmp(a));
+        let sample_length = total_length(&self.reservoir);
+        let nxs = compute_all_nx(&self.reservoir, sample_length, &[50, 75, 90]);
+        Some(StreamingSummary {
+            total_length: self.total_length,
+            n_bases: self.total_length - self.len_without_ns,
+            gc: self.gc.finish(),
+            mean_len: self.total_length as f64 / self.sequence_count as f64,
+            max_len: self.max_len,
+            min_len: self.min_len,
+            sample_size: self.reservoir.len(),
+            approximate_n50: nxs[0],
+            approximate_n75: nxs[1],
+            approximate_n90: nxs[2],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::streaming::StreamingAccumulator;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_streaming() {
+        let fasta = b">1\nACGTN\n>2\nAACC\n>3\nA\n";
+        let options = StatisticsOptions {
+            reservoir_size: Some(10),
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        assert_eq!(summary.record_count, 3);
+        assert_eq!(summary.lengths, None);
+        let streaming = summary.streaming.unwrap();
+        assert_eq!(streaming.total_length, 10);
+        assert_eq!(streaming.n_bases, 1);
+        assert_eq!(streaming.max_len, 5);
+        assert_eq!(streaming.min_len, 1);
+        assert_eq!(streaming.sample_size, 3);
+        assert_eq!(streaming.approximate_n50, 5);
+        assert_eq!(streaming.approximate_n90, 4);
+    }
+
+    #[test]
+    fn test_reservoir() {
+        let mut accumulator = StreamingAccumulator::new(100, 0);
+        for len in 1..=10_000 {
+            accumulator.add(len, len, 0);
+        }
+        let mut other = StreamingAccumulator::new(100, 1);
+        other.add(20_000, 20_000, 0);
+        accumulator.extend(&other);
+
+        let summary = accumulator.finish().unwrap();
+        assert_eq!(summary.sample_size, 100);
+        assert_eq!(summary.max_len, 20_000);
+        assert_eq!(summary.min_len, 1);
+        // The exact N50 is 7072, the sample should be roughly uniform.
+        assert!((5_000..=9_000).contains(&summary.approximate_n50));
+    }
+}
@@ -0,0 +1,240 @@
+//! Count the occurrences of telomere repeats, which chromosome-level assemblies should contain at the ends of the chromosomes.
+
+use crate::without_line_terminators;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use crate::defaults::{DEFAULT_MIN_TELOMERE_REPEATS, DEFAULT_TELOMERE_MOTIF};
+
+/// The number of bases at each end of a sequence that are searched for a telomere.
+pub const TELOMERE_END_LENGTH: usize = 10_000;
+
+/// Statistics about the telomere repeats of all sequences.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TelomereSummary {
+    /// The motif, whose reverse complement is counted as well.
+    pub motif: String,
+    /// The minimum number of occurrences at an end of a sequence to count as a telomere.
+    pub min_repeats: usize,
+    /// The number of occurrences of the motif and its reverse complement.
+    pub repeat_count: u64,
+    /// The percentage of the total length covered by the occurrences.
+    pub repeat_percent: f64,
+    /// The number of sequences with a telomere at the start.
+    pub start_count: usize,
+    /// The number of sequences with a telomere at the end.
+    pub end_count: usize,
+    /// The number of sequences with a telomere at both ends.
+    pub both_ends_count: usize,
+    /// The sequences with a telomere at either end, in the order of the input.
+    pub sequences: Vec<TelomereSequence>,
+}
+
+/// The occurrences of the telomere repeat at the ends of a sequence that has a telomere at either end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TelomereSequence {
+    pub id: String,
+    /// The number of occurrences within the first [`TELOMERE_END_LENGTH`] bases.
+    pub start_repeats: usize,
+    /// The number of occurrences within the last [`TELOMERE_END_LENGTH`] bases.
+    pub end_repeats: usize,
+}
+
+/// The occurrences of the telomere repeat in a single sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TelomereCounts {
+    /// The length of the sequence without line terminators.
+    length: usize,
+    repeat_count: u64,
+    /// The number of bases covered by the occurrences.
+    covered_length: u64,
+    start_repeats: usize,
+    end_repeats: usize,
+}
+
+/// Counts the telomere repeats of the sequences.
+#[derive(Debug, Clone)]
+pub(crate) struct TelomereAccumulator {
+    motif: Vec<u8>,
+    reverse_complement: Vec<u8>,
+    min_repeats: usize,
+    repeat_count: u64,
+    covered_length: u64,
+    total_length: u64,
+    start_count: usize,
+    end_count: usize,
+    both_ends_count: usize,
+    sequences: Vec<TelomereSequence>,
+}
+
+impl TelomereAccumulator {
+    /// The motif must be a non-empty uppercase sequence of ACGT.
+    pub(crate) fn new(motif: &str, min_repeats: usize) -> Self {
+        let motif = motif.as_bytes().to_vec();
+        Self {
+            reverse_complement: reverse_complement(&motif),
+            motif,
+            min_repeats,
+            repeat_count: 0,
+            covered_length: 0,
+            total_length: 0,
+            start_count: 0,
+            end_count: 0,
+            both_ends_count: 0,
+            sequences: Vec::new(),
+        }
+    }
+
+    /// Count the occurrences in the given sequence, ignoring case, to be passed to [`Self::add`].
+    /// Line terminators are ignored, such that multiline fasta sequences can be passed directly.
+    /// Sequences with line terminators are copied into `buffer` without them.
+    pub(crate) fn count(&self, seq: &[u8], buffer: &mut Vec<u8>) -> TelomereCounts {
+        let seq = without_line_terminators(seq, buffer);
+        let motif_length = self.motif.len();
+        let end_start = seq.len().saturating_sub(TELOMERE_END_LENGTH);
+        let mut counts = TelomereCounts {
+            length: seq.len(),
+            repeat_count: 0,
+            covered_length: 0,
+            start_repeats: 0,
+            end_repeats: 0,
+        };
+        // The end of the last occurrence, such that overlapping occurrences are not covered twice.
+        let mut covered_until = 0;
+        for (offset, window) in seq.windows(motif_length).enumerate() {
+            if !window.eq_ignore_ascii_case(&self.motif)
+                && !window.eq_ignore_ascii_case(&self.reverse_complement)
+            {
+                continue;
+            }
+
+            counts.repeat_count += 1;
+            counts.covered_length += (offset + motif_length - covered_until.max(offset)) as u64;
+            covered_until = offset + motif_length;
+            if offset + motif_length <= TELOMERE_END_LENGTH {
+                counts.start_repeats += 1;
+            }
+            if offset >= end_start {
+                counts.end_repeats += 1;
+            }
+        }
+        counts
+    }
+
+    /// Accumulate the occurrences in the sequence with the given id.
+    pub(crate) fn add(&mut self, id: &str, counts: &TelomereCounts) {
+        self.total_length += counts.length as u64;
+        self.repeat_count += counts.repeat_count;
+        self.covered_length += counts.covered_length;
+
+        let has_start = counts.start_repeats >= self.min_repeats;
+        let has_end = counts.end_repeats >= self.min_repeats;
+        self.start_count += usize::from(has_start);
+        self.end_count += usize::from(has_end);
+        self.both_ends_count += usize::from(has_start && has_end);
+        if has_start || has_end {
+            self.sequences.push(TelomereSequence {
+                id: id.to_owned(),
+                start_repeats: counts.start_repeats,
+                end_repeats: counts.end_repeats,
+            });
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        self.repeat_count += other.repeat_count;
+        self.covered_length += other.covered_length;
+        self.total_length += other.total_length;
+        self.start_count += other.start_count;
+        self.end_count += other.end_count;
+        self.both_ends_count += other.both_ends_count;
+        self.sequences.extend_from_slice(&other.sequences);
+    }
+
+    pub(crate) fn finish(self) -> TelomereSummary {
+        TelomereSummary {
+            motif: String::from_utf8(self.motif).unwrap(),
+            min_repeats: self.min_repeats,
+            repeat_count: self.repeat_count,
+            repeat_percent: if self.total_length == 0 {
+                0.0
+            } else {
+                self.covered_length as f64 / self.total_length as f64 * 100.0
+            },
+            start_count: self.start_count,
+            end_count: self.end_count,
+            both_ends_count: self.both_ends_count,
+            sequences: self.sequences,
+        }
+    }
+}
+
+/// The reverse complement of the given uppercase sequence of ACGT.
+fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|base| match base {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            other => *other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::telomere::{TelomereAccumulator, TelomereSequence, TELOMERE_END_LENGTH};
+
+    #[test]
+    fn test_telomeres() {
+        let mut accumulator = TelomereAccumulator::new("TTAGGG", 2);
+        accumulator.add(
+            "1",
+            &accumulator.count(b"ccctaaCCCTAAACGT\nACGTTTAGGG", &mut Vec::new()),
+        );
+        let mut other = TelomereAccumulator::new("TTAGGG", 2);
+        other.add("2", &other.count(b"ACGTACGTTTAGGGACGT", &mut Vec::new()));
+        let mut long = "TTAGGGTTAGGG".to_owned();
+        long.push_str(&"A".repeat(TELOMERE_END_LENGTH));
+        other.add("3", &other.count(long.as_bytes(), &mut Vec::new()));
+        accumulator.extend(&other);
+
+        let summary = accumulator.finish();
+        assert_eq!(summary.repeat_count, 6);
+        assert_eq!(
+            summary.repeat_percent,
+            36.0 / (26.0 + 18.0 + long.len() as f64) * 100.0
+        );
+        assert_eq!(summary.start_count, 2);
+        assert_eq!(summary.end_count, 1);
+        assert_eq!(summary.both_ends_count, 1);
+        assert_eq!(
+            summary.sequences,
+            [
+                TelomereSequence {
+                    id: "1".to_owned(),
+                    start_repeats: 3,
+                    end_repeats: 3,
+                },
+                TelomereSequence {
+                    id: "3".to_owned(),
+                    start_repeats: 2,
+                    end_repeats: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_palindromic_motif() {
+        let mut accumulator = TelomereAccumulator::new("ACGT", 1);
+        accumulator.add("1", &accumulator.count(b"ACGTACGT", &mut Vec::new()));
+        let summary = accumulator.finish();
+        assert_eq!(summary.repeat_count, 2);
+        assert_eq!(summary.repeat_percent, 100.0);
+    }
+}
@@ -0,0 +1,122 @@
+//! Render metrics into a user-defined format string.
+
+use crate::error::FastxStatisticsError;
+use crate::metrics::Metric;
+
+/// A format string with `{key}` placeholders for the keys of [`Metric`]s.
+///
+/// `{{` and `}}` stand for literal braces, and the escape sequences `\n` and `\t` for a newline and a tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+impl Template {
+    /// Parse the given format string, returning an error if a brace is not matched.
+    pub fn parse(template: &str) -> Result<Self, FastxStatisticsError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(character) = chars.next() {
+            match character {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(character) => key.push(character),
+                            None => {
+                                return Err(FastxStatisticsError::InvalidTemplate(format!(
+                                    "Unterminated placeholder {{{key}"
+                                )))
+                            }
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(key));
+                }
+                '}' => {
+                    return Err(FastxStatisticsError::InvalidTemplate(
+                        "Unmatched '}', use '}}' for a literal brace".to_owned(),
+                    ))
+                }
+                '\\' if chars.peek() == Some(&'n') => {
+                    chars.next();
+                    literal.push('\n');
+                }
+                '\\' if chars.peek() == Some(&'t') => {
+                    chars.next();
+                    literal.push('\t');
+                }
+                character => literal.push(character),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Replace each placeholder by the machine-readable value of the metric with that key.
+    /// Returns an error if there is no such metric.
+    pub fn render(&self, metrics: &[Metric]) -> Result<String, FastxStatisticsError> {
+        let mut output = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => output.push_str(literal),
+                Segment::Placeholder(key) => {
+                    let metric = metrics
+                        .iter()
+                        .find(|metric| &metric.key == key)
+                        .ok_or_else(|| FastxStatisticsError::UnknownMetric(key.clone()))?;
+                    output.push_str(&metric.value.to_string());
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::template::Template;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_template() {
+        let fasta = b">1\nACGTN\n>2\nAACC\n";
+        let summary = basic_statistics(
+            fasta.as_slice(),
+            fasta.len() as u64,
+            &StatisticsOptions::default(),
+        )
+        .unwrap();
+        let metrics = summary.metrics();
+
+        let template = Template::parse(r"N50={n50}, total={total_length}\n{{gc}}").unwrap();
+        assert_eq!(template.render(&metrics).unwrap(), "N50=5, total=9\n{gc}");
+        assert!(Template::parse("{n50").is_err());
+        assert!(Template::parse("n50}").is_err());
+        assert!(Template::parse("{unknown}")
+            .unwrap()
+            .render(&metrics)
+            .is_err());
+    }
+}
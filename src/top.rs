@@ -0,0 +1,88 @@
+//! Find the longest sequences.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// The id and length of one of the longest sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LongSequence {
+    pub id: String,
+    pub length: usize,
+}
+
+/// Keeps the longest sequences seen so far.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TopSequences {
+    /// A min-heap, such that the shortest of the kept sequences can be replaced quickly.
+    heap: BinaryHeap<Reverse<(usize, String)>>,
+    count: usize,
+}
+
+impl TopSequences {
+    /// Keep the `count` longest sequences.
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            heap: BinaryHeap::with_capacity(count + 1),
+            count,
+        }
+    }
+
+    pub(crate) fn add(&mut self, id: &str, length: usize) {
+        if self.heap.len() < self.count {
+            self.heap.push(Reverse((length, id.to_owned())));
+        } else if let Some(Reverse((shortest_length, _))) = self.heap.peek() {
+            if length > *shortest_length {
+                self.heap.pop();
+                self.heap.push(Reverse((length, id.to_owned())));
+            }
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &Self) {
+        for Reverse((length, id)) in &other.heap {
+            self.add(id, *length);
+        }
+    }
+
+    /// Return the kept sequences ordered by descending length.
+    pub(crate) fn finish(self) -> Vec<LongSequence> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((length, id))| LongSequence { id, length })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FASTA;
+    use crate::top::LongSequence;
+    use crate::{basic_statistics, StatisticsOptions};
+
+    #[test]
+    fn test_top_sequences() {
+        let options = StatisticsOptions {
+            top: Some(2),
+            filter_ids: vec!["4".to_owned()],
+            ..Default::default()
+        };
+        let summary = basic_statistics(FASTA, FASTA.len() as u64, &options).unwrap();
+        assert_eq!(
+            summary.longest_sequences.unwrap(),
+            [
+                LongSequence {
+                    id: "2".to_owned(),
+                    length: 29,
+                },
+                LongSequence {
+                    id: "1".to_owned(),
+                    length: 21,
+                },
+            ]
+        );
+    }
+}
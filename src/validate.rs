@@ -0,0 +1,230 @@
+//! Check fasta and fastq files for problems that may affect their processing.
+
+use crate::format::SequenceReader;
+use crate::input::InputProgress;
+use crate::{create_progress_bar, StatisticsOptions};
+use log::info;
+use seq_io::BaseRecord;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
+
+/// How severe a problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The file can be processed, but the results may be unexpected.
+    Warning,
+    /// The file cannot be processed safely.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// The 1-based line number of the start of the record, or of the parse error.
+    /// `None` if the position of a parse error is unknown.
+    pub line: Option<u64>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// The problems found in the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The number of records that were parsed successfully.
+    pub record_count: usize,
+    /// The problems in the order of their occurrence.
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// The severity of the most severe problem, `None` if there are no problems.
+    pub fn severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|issue| issue.severity).max()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    fn push(&mut self, line: Option<u64>, severity: Severity, message: String) {
+        self.issues.push(Issue {
+            line,
+            severity,
+            message,
+        });
+    }
+}
+
+/// Parse all records of the input and report the following problems:
+///
+///  * ids that are not valid utf-8, which cannot be processed,
+///  * ids with non-ASCII characters,
+///  * sequences with characters that are not IUPAC nucleotide codes or gaps,
+///  * duplicate ids,
+///  * empty sequences,
+///  * parse errors, including fastq records whose sequence and quality strings differ in length.
+///
+/// Parse errors are reported as errors and stop the validation, and ids that are not valid utf-8 are reported as errors.
+/// All other problems are reported as warnings.
+/// Only the `format` and `show_progress` options are used.
+pub fn validate(
+    input: impl Read,
+    progress: InputProgress,
+    options: &StatisticsOptions,
+) -> ValidationReport {
+    info!("Validating fasta or fastq file...");
+    let pb = create_progress_bar([&progress].into_iter(), options);
+
+    let mut reader = SequenceReader::new(BufReader::new(input), options.format);
+    let mut last_update = Instant::now();
+    let mut report = ValidationReport::default();
+    // The line of the first occurrence of each id.
+    let mut id_lines = HashMap::new();
+
+    while let Some(record) = reader.next_record(true) {
+        let record = match record {
+            Ok((record, _)) => record,
+            Err(err) => {
+                let line = err
+                    .position()
+                    .and_then(|position| position.position())
+                    .map(|position| position.line() + 1);
+                report.push(line, Severity::Error, err.to_string());
+                break;
+            }
+        };
+        report.record_count += 1;
+        let line = reader.position().line() + 1;
+        let id = String::from_utf8_lossy(record.id_bytes());
+
+        if std::str::from_utf8(record.id_bytes()).is_err() {
+            report.push(
+                Some(line),
+                Severity::Error,
+                format!("Record id {id:?} is not utf-8 encoded"),
+            );
+        } else if !record.id_bytes().is_ascii() {
+            report.push(
+                Some(line),
+                Severity::Warning,
+                format!("Record id {id:?} contains non-ASCII characters"),
+            );
+        }
+        if let Some((_, character)) = find_invalid_character(record.seq()) {
+            report.push(
+                Some(line),
+                Severity::Warning,
+                format!(
+                    "Record {id:?} contains the character {:?}, which is not an IUPAC nucleotide code",
+                    char::from(character)
+                ),
+            );
+        }
+        if record.seq().is_empty() {
+            report.push(
+                Some(line),
+                Severity::Warning,
+                format!("Record {id:?} has an empty sequence"),
+            );
+        }
+        if let Some(first_line) = id_lines.get(id.as_ref()) {
+            report.push(
+                Some(line),
+                Severity::Warning,
+                format!("Duplicate record id {id:?}, first used in line {first_line}"),
+            );
+        } else {
+            id_lines.insert(id.into_owned(), line);
+        }
+
+        let now = Instant::now();
+        if last_update + Duration::from_millis(200) <= now {
+            pb.set_position(progress.position(reader.position().byte()));
+            last_update = now;
+        }
+    }
+
+    pb.finish_and_clear();
+    report
+}
+
+/// Returns the 1-based position and the value of the first character of the sequence that is not
+/// an IUPAC nucleotide code or a gap, ignoring case.
+/// Line terminators are skipped, such that multiline fasta sequences can be passed directly.
+pub(crate) fn find_invalid_character(seq: &[u8]) -> Option<(usize, u8)> {
+    seq.iter()
+        .copied()
+        .filter(|&byte| byte != b'\n' && byte != b'\r')
+        .enumerate()
+        .find(|&(_, byte)| !is_nucleotide_code(byte))
+        .map(|(offset, byte)| (offset + 1, byte))
+}
+
+/// Returns true if the byte is an IUPAC nucleotide code or a gap, ignoring case.
+fn is_nucleotide_code(byte: u8) -> bool {
+    matches!(
+        byte.to_ascii_uppercase(),
+        b'A' | b'C'
+            | b'G'
+            | b'T'
+            | b'U'
+            | b'R'
+            | b'Y'
+            | b'S'
+            | b'W'
+            | b'K'
+            | b'M'
+            | b'B'
+            | b'D'
+            | b'H'
+            | b'V'
+            | b'N'
+            | b'-'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validate::{validate, Severity};
+
+    #[test]
+    fn test_validate() {
+        let fasta: &[u8] = ">ä\nACGT\n>2\nACXT\nA\n>3\n>2\nAC\n".as_bytes();
+        let report = validate(fasta, (fasta.len() as u64).into(), &Default::default());
+        assert_eq!(report.record_count, 4);
+        assert_eq!(report.severity(), Some(Severity::Warning));
+        let lines: Vec<_> = report.issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, [Some(1), Some(3), Some(6), Some(7)]);
+        assert!(report.issues[0].message.contains("non-ASCII"));
+
+        let fastq: &[u8] = b"@1\nACGT\n+\nIIII\n@2\nACGT\n+\nII\n";
+        let report = validate(fastq, (fastq.len() as u64).into(), &Default::default());
+        assert_eq!(report.record_count, 1);
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.issues[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_non_utf8_id() {
+        let fasta: &[u8] = b">a\xff\nACGT\n>b\nAC\n";
+        let report = validate(fasta, (fasta.len() as u64).into(), &Default::default());
+        assert_eq!(report.record_count, 2);
+        assert_eq!(report.severity(), Some(Severity::Error));
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].line, Some(1));
+    }
+}
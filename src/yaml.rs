@@ -0,0 +1,46 @@
+//! Render serialisable values as YAML, with the same structure and field order as their json serialisation.
+//!
+//! Only available with the `serde` feature.
+
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Write the given value as a block-style YAML document.
+pub fn write_yaml(value: &impl Serialize, output: impl Write) -> io::Result<()> {
+    serde_yaml::to_writer(output, value).map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::yaml::write_yaml;
+    use crate::{basic_statistics, StatisticsOptions};
+    use serde_yaml::Value;
+
+    #[test]
+    fn test_yaml() {
+        let fasta = b">1\nACGTN\n>2\nAC\nGG\n>3\nA\n";
+        let options = StatisticsOptions {
+            additional_percentiles: vec![90],
+            ..Default::default()
+        };
+        let summary = basic_statistics(fasta.as_slice(), fasta.len() as u64, &options).unwrap();
+        let mut yaml = Vec::new();
+        write_yaml(&summary, &mut yaml).unwrap();
+        let json = serde_json::to_string(&summary).unwrap();
+
+        // Json is valid YAML, so both can be parsed the same way.
+        let yaml: Value = serde_yaml::from_slice(&yaml).unwrap();
+        let json: Value = serde_yaml::from_str(&json).unwrap();
+        assert_eq!(yaml, json);
+        let keys = |value: &Value| -> Vec<Value> {
+            value
+                .as_mapping()
+                .unwrap()
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        assert_eq!(keys(&yaml), keys(&json));
+        assert_eq!(keys(&yaml)[0], "record_count");
+    }
+}